@@ -14,50 +14,164 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-//! Alias built-in.
+//! Alias built-in
 //!
-//! TODO Elaborate
+//! The **`alias`** built-in defines or prints aliases.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! alias [-gp] [name[=value]...]
+//! ```
+//!
+//! # Semantics
+//!
+//! For each operand of the form `name=value`, the alias built-in defines an
+//! alias named *name* that is substituted with *value*. An existing alias
+//! with the same name is replaced.
+//!
+//! For each operand that does not contain `=`, the built-in prints the
+//! definition of the named alias. If there are no operands, the built-in
+//! prints all aliases.
+//!
+//! The definitions are printed in the form of `name=value`, where the value is
+//! quoted with [`yash_quote`] so that the output can be reused as an operand
+//! of the alias built-in.
+//!
+//! # Options
+//!
+//! The **`-g`** (**`--global`**) option makes the defined aliases global.
+//! A global alias is substituted in any position of a simple command, not only
+//! in the command name position.
+//!
+//! The **`-p`** (**`--prefix`**) option makes the built-in print each
+//! definition as a complete alias command like `alias name=value` (or
+//! `alias -g name=value` for a global alias), which can be fed back to the
+//! shell to redefine the alias.
+//!
+//! # Operands
+//!
+//! Operands are alias definitions of the form `name=value` or names of
+//! aliases to be printed.
+//!
+//! # Exit status
+//!
+//! Zero unless an error occurs.
+//!
+//! # Errors
+//!
+//! It is an error if an operand names an alias that is not defined.
+//!
+//! # Portability
+//!
+//! The `-g` and `-p` options are non-standard extensions. POSIX does not
+//! define global aliases.
 
+use crate::common::not_found_message;
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax::OptionSpec;
+use crate::common::Print;
+use std::fmt::Write;
 use yash_env::builtin::Result;
-use yash_env::semantics::ExitStatus;
 use yash_env::semantics::Field;
 use yash_env::Env;
+use yash_quote::quoted;
+use yash_syntax::alias::Alias;
 use yash_syntax::alias::HashEntry;
 
-// TODO Split into syntax and semantics submodules
+const OPTION_SPECS: &[OptionSpec] = &[
+    OptionSpec::new().short('g').long("global"),
+    OptionSpec::new().short('p').long("prefix"),
+];
+
+/// Appends the definition of the alias to the output.
+///
+/// If `prefix` is true, the definition is printed as an alias command.
+fn print_alias(output: &mut String, alias: &Alias, prefix: bool) {
+    if prefix {
+        output.push_str("alias ");
+        if alias.global {
+            output.push_str("-g ");
+        }
+        if alias.name.starts_with('-') {
+            output.push_str("-- ");
+        }
+    }
+    writeln!(
+        output,
+        "{}={}",
+        quoted(&alias.name),
+        quoted(&alias.replacement)
+    )
+    .unwrap()
+}
 
 /// Entry point for executing the `alias` built-in
-pub fn main(env: &mut Env, args: Vec<Field>) -> Result {
-    // TODO support options
-    // TODO print alias definitions if there are no operands
-
-    if args.is_empty() {
-        for alias in &env.aliases {
-            // TODO should print via IoEnv rather than directly to stdout
-            println!("{}={}", &alias.0.name, &alias.0.replacement);
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (options, operands) = match parse_arguments(OPTION_SPECS, Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+
+    let mut global = false;
+    let mut prefix = false;
+    for option in options {
+        match option.spec.get_short() {
+            Some('g') => global = true,
+            Some('p') => prefix = true,
+            _ => unreachable!("unhandled option: {:?}", option),
+        }
+    }
+
+    let mut output = String::new();
+    if operands.is_empty() {
+        let mut aliases: Vec<_> = env.aliases.iter().map(|entry| &entry.0).collect();
+        // TODO apply current locale's collation
+        aliases.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        for alias in aliases {
+            print_alias(&mut output, alias, prefix);
         }
-        return ExitStatus::SUCCESS.into();
+        return env.print(&output).await;
     }
 
-    for Field { value, origin } in args {
-        if let Some(eq_index) = value.find('=') {
-            let name = value[..eq_index].to_owned();
+    let mut result = Result::default();
+    for operand in operands {
+        if let Some(eq_index) = operand.value.find('=') {
+            let name = operand.value[..eq_index].to_owned();
             // TODO reject invalid name
-            let replacement = value[eq_index + 1..].to_owned();
-            let entry = HashEntry::new(name, replacement, false, origin);
+            let replacement = operand.value[eq_index + 1..].to_owned();
+            let entry = HashEntry::new(name, replacement, global, operand.origin);
             env.aliases.replace(entry);
+        } else if let Some(entry) = env.aliases.get(operand.value.as_str()) {
+            print_alias(&mut output, &entry.0, prefix);
         } else {
-            // TODO print alias definition
+            result = print_failure_message(env, not_found_message("alias", &operand)).await;
         }
     }
 
-    ExitStatus::SUCCESS.into()
+    let print_result = env.print(&output).await;
+    if print_result.exit_status().is_successful() {
+        result
+    } else {
+        print_result
+    }
 }
 
 #[allow(clippy::bool_assert_comparison)]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
     use yash_syntax::source::Location;
     use yash_syntax::source::Source;
 
@@ -66,7 +180,7 @@ mod tests {
         let mut env = Env::new_virtual();
         let args = Field::dummies(["foo=bar baz"]);
 
-        let result = main(&mut env, args);
+        let result = main(&mut env, args).now_or_never().unwrap();
         assert_eq!(result, Result::new(ExitStatus::SUCCESS));
 
         assert_eq!(env.aliases.len(), 1);
@@ -86,7 +200,7 @@ mod tests {
         let mut env = Env::new_virtual();
         let args = Field::dummies(["abc=xyz", "yes=no", "ls=ls --color"]);
 
-        let result = main(&mut env, args);
+        let result = main(&mut env, args).now_or_never().unwrap();
         assert_eq!(result, Result::new(ExitStatus::SUCCESS));
 
         assert_eq!(env.aliases.len(), 3);
@@ -124,12 +238,12 @@ mod tests {
         let mut env = Env::new_virtual();
         let args = Field::dummies(["foo=1"]);
 
-        let result = main(&mut env, args);
+        let result = main(&mut env, args).now_or_never().unwrap();
         assert_eq!(result, Result::new(ExitStatus::SUCCESS));
 
         let args = Field::dummies(["foo=2"]);
 
-        let result = main(&mut env, args);
+        let result = main(&mut env, args).now_or_never().unwrap();
         assert_eq!(result, Result::new(ExitStatus::SUCCESS));
 
         assert_eq!(env.aliases.len(), 1);
@@ -138,13 +252,22 @@ mod tests {
         assert_eq!(alias.name, "foo");
         assert_eq!(alias.replacement, "2");
         assert_eq!(alias.global, false);
-        // TODO Test with the global option
-        // assert_eq!(alias.global, true);
     }
 
     #[test]
-    fn builtin_prints_all_aliases() {
+    fn builtin_defines_global_alias() {
         let mut env = Env::new_virtual();
+        let args = Field::dummies(["-g", "foo=bar"]);
+
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+
+        let alias = env.aliases.get("foo").unwrap().0.as_ref();
+        assert_eq!(alias.replacement, "bar");
+        assert_eq!(alias.global, true);
+    }
+
+    fn define_some_aliases(env: &mut Env) {
         env.aliases.insert(HashEntry::new(
             "foo".to_string(),
             "bar".to_string(),
@@ -153,11 +276,70 @@ mod tests {
         ));
         env.aliases.insert(HashEntry::new(
             "hello".to_string(),
-            "world".to_string(),
-            false,
+            "echo world".to_string(),
+            true,
             Location::dummy(""),
         ));
-        // TODO builtin should print to IoEnv rather than real standard output
     }
-    // TODO test case with global aliases
+
+    #[test]
+    fn builtin_prints_all_aliases() {
+        in_virtual_system(|mut env, state| async move {
+            define_some_aliases(&mut env);
+
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| {
+                assert_eq!(stdout, "foo=bar\nhello='echo world'\n")
+            });
+        })
+    }
+
+    #[test]
+    fn builtin_prints_aliases_with_prefix() {
+        in_virtual_system(|mut env, state| async move {
+            define_some_aliases(&mut env);
+
+            let args = Field::dummies(["-p"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| {
+                assert_eq!(stdout, "alias foo=bar\nalias -g hello='echo world'\n")
+            });
+        })
+    }
+
+    #[test]
+    fn builtin_prints_named_aliases() {
+        in_virtual_system(|mut env, state| async move {
+            define_some_aliases(&mut env);
+
+            let args = Field::dummies(["hello", "foo"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| {
+                assert_eq!(stdout, "hello='echo world'\nfoo=bar\n")
+            });
+        })
+    }
+
+    #[test]
+    fn printing_undefined_alias() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("alias"),
+                is_special: false,
+            });
+            define_some_aliases(&mut env);
+
+            let args = Field::dummies(["foo", "bar", "baz=1"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::FAILURE));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "foo=bar\n"));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("bar"), "{stderr:?}")
+            });
+            assert!(env.aliases.contains("baz"));
+        })
+    }
 }
@@ -0,0 +1,263 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Bg built-in
+//!
+//! The **`bg`** built-in resumes suspended jobs in the background.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! bg [job_id...]
+//! ```
+//!
+//! # Semantics
+//!
+//! The built-in resumes the specified jobs by sending the `SIGCONT` signal to
+//! them. The jobs continue running in the background.
+//!
+//! For each job, the built-in prints the job number and name to the standard
+//! output in the form `[%d] %s\n` before resuming it.
+//!
+//! A resumed job is no longer suspended, so the current and previous jobs are
+//! reselected as described in [`JobSet::update_status`].
+//!
+//! # Options
+//!
+//! None.
+//!
+//! # Operands
+//!
+//! Operands are [job IDs](yash_env::job::id) that specify which jobs to
+//! resume. If omitted, the built-in resumes the [current
+//! job](JobSet::current_job).
+//!
+//! # Exit status
+//!
+//! Zero if all the jobs were resumed successfully, non-zero otherwise.
+//!
+//! # Errors
+//!
+//! This built-in fails if a specified job is not found or is not
+//! job-controlled, that is, not running in its own process group.
+//!
+//! An error for a job does not prevent the other jobs from being resumed.
+//!
+//! # Portability
+//!
+//! Many implementations allow omitting the leading `%` from job IDs, though
+//! it is not required by POSIX.
+//!
+//! # Implementation notes
+//!
+//! A job that is not suspended is not sent the signal, but its name is still
+//! printed.
+//!
+//! [`JobSet::update_status`]: yash_env::job::JobSet::update_status
+//! [`JobSet::current_job`]: yash_env::job::JobSet::current_job
+
+use crate::common::job::check_job_controlled;
+use crate::common::job::current_job;
+use crate::common::job::find_job;
+use crate::common::job::resume_error_message;
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::BuiltinEnv;
+use crate::common::Print;
+use std::ops::ControlFlow::Continue;
+use yash_env::builtin::Result;
+use yash_env::job::Pid;
+use yash_env::job::WaitStatus;
+use yash_env::semantics::Field;
+use yash_env::system::Errno;
+use yash_env::trap::Signal;
+use yash_env::Env;
+use yash_env::System;
+use yash_syntax::source::Location;
+
+/// Resumes the job at the specified index in the background.
+///
+/// The job must be job-controlled. This function prints the job number and
+/// name, and sends `SIGCONT` to the job if it is suspended.
+async fn resume_job_by_index(env: &mut Env, index: usize) -> std::result::Result<Result, Errno> {
+    let job = &env.jobs[index];
+    let pid = job.pid;
+    let is_suspended = job.is_suspended();
+    let line = format!("[{}] {}\n", index + 1, job.name);
+
+    let result = env.print(&line).await;
+    if !result.exit_status().is_successful() {
+        return Ok(result);
+    }
+
+    if is_suspended {
+        env.system
+            .kill(Pid::from_raw(-pid.as_raw()), Some(Signal::SIGCONT))?;
+        env.jobs.update_status(WaitStatus::Continued(pid));
+    }
+    Ok(result)
+}
+
+/// Resumes the job specified by the operand.
+async fn resume_job_by_operand(env: &mut Env, operand: &Field) -> Result {
+    match find_job(&env.jobs, operand) {
+        Ok(index) => resume_job(env, index, &operand.origin).await,
+        Err(message) => print_failure_message(env, message).await,
+    }
+}
+
+/// Resumes the job at the index, printing an error message on failure.
+async fn resume_job(env: &mut Env, index: usize, location: &Location) -> Result {
+    if let Err(message) = check_job_controlled(&env.jobs, index, location) {
+        return print_failure_message(env, message).await;
+    }
+
+    match resume_job_by_index(env, index).await {
+        Ok(result) => result,
+        Err(errno) => {
+            let label = errno.desc().to_string();
+            print_failure_message(env, resume_error_message(label, location)).await
+        }
+    }
+}
+
+/// Entry point for executing the `bg` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (_options, operands) = match parse_arguments(&[], Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+
+    if operands.is_empty() {
+        let location = env.builtin_name().origin.clone();
+        return match current_job(&env.jobs, &location) {
+            Ok(index) => resume_job(env, index, &location).await,
+            Err(message) => print_failure_message(env, message).await,
+        };
+    }
+
+    let mut final_result = Result::default();
+    for operand in &operands {
+        let result = resume_job_by_operand(env, operand).await;
+        if result.divert() != Continue(()) {
+            return result;
+        }
+        if !result.exit_status().is_successful() {
+            final_result = result;
+        }
+    }
+    final_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use crate::tests::stub_tty;
+    use crate::tests::suspended_job;
+    use yash_env::option::Option::Monitor;
+    use yash_env::option::State::On;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
+    use yash_env::system::r#virtual::ProcessState;
+
+    #[test]
+    fn resuming_current_job() {
+        in_virtual_system(|mut env, state| async move {
+            env.options.set(Monitor, On);
+            stub_tty(&state);
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("bg"),
+                is_special: false,
+            });
+            let index = suspended_job(&mut env, "my job", ExitStatus(0)).await;
+            let pid = env.jobs[index].pid;
+
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "[1] my job\n"));
+            assert_eq!(env.jobs[index].status, WaitStatus::Continued(pid));
+            assert_ne!(
+                state.borrow().processes[&pid].state(),
+                ProcessState::Stopped(Signal::SIGSTOP)
+            );
+        })
+    }
+
+    #[test]
+    fn resuming_jobs_by_operands_updates_current_job() {
+        in_virtual_system(|mut env, state| async move {
+            env.options.set(Monitor, On);
+            stub_tty(&state);
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("bg"),
+                is_special: false,
+            });
+            let first = suspended_job(&mut env, "first", ExitStatus(0)).await;
+            let second = suspended_job(&mut env, "second", ExitStatus(0)).await;
+            let third = suspended_job(&mut env, "third", ExitStatus(0)).await;
+            assert_eq!(env.jobs.current_job(), Some(first));
+            assert_eq!(env.jobs.previous_job(), Some(second));
+
+            let result = main(&mut env, Field::dummies(["%first"])).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "[1] first\n"));
+            assert_eq!(env.jobs.current_job(), Some(second));
+            assert_eq!(env.jobs.previous_job(), Some(third));
+        })
+    }
+
+    #[test]
+    fn error_does_not_stop_other_jobs() {
+        in_virtual_system(|mut env, state| async move {
+            env.options.set(Monitor, On);
+            stub_tty(&state);
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("bg"),
+                is_special: false,
+            });
+            suspended_job(&mut env, "job", ExitStatus(0)).await;
+
+            let result = main(&mut env, Field::dummies(["%nosuch", "%job"])).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "[1] job\n"));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("%nosuch"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn no_job() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("bg"),
+                is_special: false,
+            });
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("no job"), "{stderr:?}")
+            });
+        })
+    }
+}
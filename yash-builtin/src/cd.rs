@@ -0,0 +1,432 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Cd built-in.
+//!
+//! The **`cd`** built-in changes the working directory.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! cd [-L|-P] [directory]
+//! ```
+//!
+//! # Semantics
+//!
+//! The built-in changes the working directory to the specified directory. The
+//! new working directory is determined from the option and operand as follows:
+//!
+//! 1. If the operand is omitted, the value of `$HOME` is used for the operand.
+//!    If the operand is a single hyphen (`-`), the value of `$OLDPWD` is used
+//!    for the operand.
+//! 2. If the operand is an absolute path, it is used as the target.
+//!    If the operand starts with a `.` or `..` component, it is appended to
+//!    `$PWD` to make the target.
+//!    Otherwise, the built-in searches `$CDPATH` for a directory that contains
+//!    the operand. If found, the target is the pathname of the found directory.
+//!    If not found, the operand is appended to `$PWD` to make the target.
+//! 3. With the `-L` option, the target is canonicalized by removing `.` and
+//!    `..` components lexically. (A `..` component is removed together with
+//!    the preceding component only if the preceding component names an
+//!    existing directory.)
+//! 4. The working directory is changed to the target.
+//!
+//! After changing the working directory, the built-in sets `$OLDPWD` to the
+//! previous value of `$PWD` and `$PWD` to the new working directory path.
+//! With the `-L` option, the new `$PWD` is the canonicalized target. With the
+//! `-P` option, it is the physical path obtained from the system.
+//!
+//! If the new working directory was found by a non-empty `$CDPATH` entry or
+//! the operand was `-`, the built-in prints the new `$PWD` to the standard
+//! output.
+//!
+//! # Options
+//!
+//! With the **`-L`** (**`--logical`**) option, symbolic links in the target
+//! path are kept in `$PWD`. `..` components are resolved lexically.
+//!
+//! With the **`-P`** (**`--physical`**) option, the target path is passed to
+//! the system as is, and `$PWD` is set to the physical path of the new working
+//! directory, which does not contain any symbolic links.
+//!
+//! These two options are mutually exclusive. The last specified one applies if
+//! given both. The default is `-L`.
+//!
+//! # Operands
+//!
+//! The ***directory*** operand is the directory to change to. It may be
+//! omitted or `-` as described above.
+//!
+//! # Exit status
+//!
+//! Zero if the working directory was changed successfully; non-zero otherwise.
+//!
+//! # Errors
+//!
+//! The built-in fails if:
+//!
+//! - the operand is omitted and `$HOME` is not set or empty,
+//! - the operand is `-` and `$OLDPWD` is not set or empty,
+//! - the operand is an empty string,
+//! - a component preceding `..` in the target does not name a directory,
+//! - the working directory cannot be changed to the target, or
+//! - `$PWD` or `$OLDPWD` is read-only.
+//!
+//! # Portability
+//!
+//! The `-L` and `-P` options and the `-` operand are defined in POSIX. The
+//! behavior is unspecified in POSIX if the operand is omitted and `$HOME` is
+//! not set or empty.
+//!
+//! POSIX allows the built-in to fall back to the `-P` behavior if the
+//! canonicalized target is longer than PATH_MAX. This implementation does not
+//! do so.
+//!
+//! # Implementation notes
+//!
+//! The working directory is changed with [`System::chdir`].
+
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::BuiltinEnv;
+use crate::common::Print;
+use std::ffi::CString;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::variable::Scope::Global;
+use yash_env::variable::Variable;
+use yash_env::Env;
+use yash_env::System;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+
+/// Choice of the behavior of the built-in
+#[derive(Debug, Clone, Copy, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Mode {
+    /// Keeps symbolic links in `$PWD` and resolves `..` lexically.
+    #[default]
+    Logical,
+
+    /// Sets `$PWD` to the physical path of the new working directory.
+    Physical,
+}
+
+/// Parsed command line arguments of the cd built-in
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Command {
+    /// How to handle symbolic links
+    pub mode: Mode,
+    /// Directory operand, if any
+    pub operand: Option<Field>,
+}
+
+pub mod canonicalize;
+pub mod syntax;
+pub mod target;
+
+/// Returns the physical working directory pathname.
+fn getcwd(env: &Env) -> Option<String> {
+    env.system
+        .getcwd()
+        .ok()?
+        .into_os_string()
+        .into_string()
+        .ok()
+}
+
+/// Assigns the new values to `$OLDPWD` and `$PWD`.
+async fn update_variables(env: &mut Env, old_pwd: String, new_pwd: String) -> Result {
+    let location = env.builtin_name().origin.clone();
+    for (name, value) in [("OLDPWD", old_pwd), ("PWD", new_pwd)] {
+        let value = Variable::new(value).set_assigned_location(location.clone());
+        if let Err(error) = env.assign_variable(Global, name.to_owned(), value) {
+            let message = Message {
+                r#type: AnnotationType::Error,
+                title: format!("cannot update ${name}").into(),
+                annotations: vec![
+                    Annotation::new(AnnotationType::Error, error.to_string().into(), &location),
+                    Annotation::new(
+                        AnnotationType::Info,
+                        "the variable was made read-only here".into(),
+                        &error.read_only_location,
+                    ),
+                ],
+            };
+            return print_failure_message(env, message).await;
+        }
+    }
+    Result::default()
+}
+
+/// Entry point for executing the `cd` built-in
+///
+/// This function uses the [`syntax`] and [`target`] modules to execute the
+/// built-in.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let command = match syntax::parse(env, args) {
+        Ok(command) => command,
+        Err(e) => return print_error_message(env, &e).await,
+    };
+
+    let old_pwd = match env.get_pwd_if_correct() {
+        Some(pwd) => pwd.to_owned(),
+        None => getcwd(env).unwrap_or_default(),
+    };
+
+    let (path, origin) = match target::target(env, &command, &old_pwd) {
+        Ok(target) => target,
+        Err(e) => return print_failure_message(env, &e).await,
+    };
+
+    if let Err(errno) = env
+        .system
+        .chdir(&CString::new(path.as_str()).unwrap_or_default())
+    {
+        let location = match &command.operand {
+            Some(operand) => operand.origin.clone(),
+            None => env.builtin_name().origin.clone(),
+        };
+        let message = Message {
+            r#type: AnnotationType::Error,
+            title: format!("cannot change the working directory to {path:?}").into(),
+            annotations: vec![Annotation::new(
+                AnnotationType::Error,
+                errno.desc().into(),
+                &location,
+            )],
+        };
+        return print_failure_message(env, message).await;
+    }
+
+    let new_pwd = match command.mode {
+        Mode::Logical => path,
+        Mode::Physical => getcwd(env).unwrap_or(path),
+    };
+
+    let result = update_variables(env, old_pwd, new_pwd.clone()).await;
+    if result != Result::default() {
+        return result;
+    }
+
+    if origin.should_print() {
+        env.print(&format!("{new_pwd}\n")).await
+    } else {
+        Result::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use std::cell::RefCell;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
+    use yash_env::system::r#virtual::FileBody;
+    use yash_env::system::r#virtual::INode;
+    use yash_env::system::r#virtual::SystemState;
+    use yash_env::variable::Value;
+    use yash_env::VirtualSystem;
+    use yash_syntax::source::Location;
+
+    pub(super) fn create_dir(state: &RefCell<SystemState>, path: &str) {
+        let dir = INode {
+            body: FileBody::Directory {
+                files: Default::default(),
+            },
+            permissions: Default::default(),
+        };
+        let dir = Rc::new(RefCell::new(dir));
+        state.borrow_mut().file_system.save(path, dir).unwrap();
+    }
+
+    fn assign(env: &mut Env, name: &str, value: &str) {
+        env.variables
+            .assign(Global, name.to_string(), Variable::new(value))
+            .unwrap();
+    }
+
+    fn get(env: &Env, name: &str) -> Option<Value> {
+        env.variables.get(name).and_then(|v| v.value.clone())
+    }
+
+    fn cwd(system: &VirtualSystem) -> PathBuf {
+        system.current_process().getcwd().to_path_buf()
+    }
+
+    fn env_with_dirs() -> (Env, VirtualSystem) {
+        let mut system = VirtualSystem::new();
+        create_dir(&system.state, "/foo/bar/baz");
+        create_dir(&system.state, "/home/user");
+        system.current_process_mut().chdir(PathBuf::from("/"));
+        let mut env = Env::with_system(Box::new(system.clone()));
+        assign(&mut env, "PWD", "/");
+        (env, system)
+    }
+
+    fn cd_frame() -> Frame {
+        Frame::Builtin {
+            name: Field::dummy("cd"),
+            is_special: false,
+        }
+    }
+
+    #[test]
+    fn absolute_operand() {
+        let (mut env, system) = env_with_dirs();
+        let mut env = env.push_frame(cd_frame());
+        let args = Field::dummies(["/foo/bar"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(cwd(&system), Path::new("/foo/bar"));
+        assert_eq!(get(&env, "PWD"), Some(Value::scalar("/foo/bar")));
+        assert_eq!(get(&env, "OLDPWD"), Some(Value::scalar("/")));
+    }
+
+    #[test]
+    fn relative_operand() {
+        let (mut env, system) = env_with_dirs();
+        let mut env = env.push_frame(cd_frame());
+        let args = Field::dummies(["foo"]);
+        let _ = main(&mut env, args).now_or_never().unwrap();
+        let args = Field::dummies(["bar/./baz/.."]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(cwd(&system), Path::new("/foo/bar"));
+        assert_eq!(get(&env, "PWD"), Some(Value::scalar("/foo/bar")));
+        assert_eq!(get(&env, "OLDPWD"), Some(Value::scalar("/foo")));
+    }
+
+    #[test]
+    fn no_operand_goes_home() {
+        let (mut env, system) = env_with_dirs();
+        assign(&mut env, "HOME", "/home/user");
+        let mut env = env.push_frame(cd_frame());
+        let result = main(&mut env, vec![]).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(cwd(&system), Path::new("/home/user"));
+        assert_eq!(get(&env, "PWD"), Some(Value::scalar("/home/user")));
+    }
+
+    #[test]
+    fn no_operand_without_home() {
+        let (mut env, system) = env_with_dirs();
+        let mut env = env.push_frame(cd_frame());
+        let result = main(&mut env, vec![]).now_or_never().unwrap();
+        assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+        assert_eq!(cwd(&system), Path::new("/"));
+    }
+
+    #[test]
+    fn hyphen_operand_goes_to_oldpwd_and_prints_it() {
+        in_virtual_system(|mut env, state| async move {
+            create_dir(&state, "/foo/bar");
+            env.system.chdir(&CString::new("/").unwrap()).unwrap();
+            assign(&mut env, "PWD", "/");
+            assign(&mut env, "OLDPWD", "/foo/bar");
+            let mut env = env.push_frame(cd_frame());
+            let args = Field::dummies(["-"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::default());
+            assert_eq!(get(&env, "PWD"), Some(Value::scalar("/foo/bar")));
+            assert_eq!(get(&env, "OLDPWD"), Some(Value::scalar("/")));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "/foo/bar\n"));
+        })
+    }
+
+    #[test]
+    fn directory_found_in_cdpath_is_printed() {
+        in_virtual_system(|mut env, state| async move {
+            create_dir(&state, "/foo/bar");
+            env.system.chdir(&CString::new("/").unwrap()).unwrap();
+            assign(&mut env, "PWD", "/");
+            assign(&mut env, "CDPATH", "/none:/foo");
+            let mut env = env.push_frame(cd_frame());
+            let args = Field::dummies(["bar"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::default());
+            assert_eq!(get(&env, "PWD"), Some(Value::scalar("/foo/bar")));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "/foo/bar\n"));
+        })
+    }
+
+    #[test]
+    fn logical_and_physical_modes_with_symlink() {
+        let (mut env, system) = env_with_dirs();
+        let link = INode {
+            body: FileBody::Symlink {
+                target: "foo/bar".into(),
+            },
+            permissions: Default::default(),
+        };
+        let link = Rc::new(RefCell::new(link));
+        system
+            .state
+            .borrow_mut()
+            .file_system
+            .save("/link", link)
+            .unwrap();
+        let mut env = env.push_frame(cd_frame());
+
+        let args = Field::dummies(["/link"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(cwd(&system), Path::new("/foo/bar"));
+        assert_eq!(get(&env, "PWD"), Some(Value::scalar("/link")));
+
+        let args = Field::dummies(["-P", "/link"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(cwd(&system), Path::new("/foo/bar"));
+        assert_eq!(get(&env, "PWD"), Some(Value::scalar("/foo/bar")));
+        assert_eq!(get(&env, "OLDPWD"), Some(Value::scalar("/link")));
+    }
+
+    #[test]
+    fn non_existent_directory() {
+        let (mut env, system) = env_with_dirs();
+        let mut env = env.push_frame(cd_frame());
+        let args = Field::dummies(["/no/such/dir"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+        assert_eq!(cwd(&system), Path::new("/"));
+        assert_eq!(get(&env, "PWD"), Some(Value::scalar("/")));
+        assert_eq!(get(&env, "OLDPWD"), None);
+    }
+
+    #[test]
+    fn read_only_pwd() {
+        let (mut env, system) = env_with_dirs();
+        let pwd = Variable::new("/").make_read_only(Location::dummy("readonly"));
+        env.variables
+            .assign(Global, "PWD".to_string(), pwd)
+            .unwrap();
+        let mut env = env.push_frame(cd_frame());
+        let args = Field::dummies(["/foo"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+        assert_eq!(cwd(&system), Path::new("/foo"));
+        assert_eq!(get(&env, "OLDPWD"), Some(Value::scalar("/")));
+        assert_eq!(get(&env, "PWD"), Some(Value::scalar("/")));
+    }
+}
@@ -0,0 +1,118 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Lexical canonicalization of the target path
+//!
+//! This module implements the canonicalization of the target directory
+//! pathname performed in the logical mode of the cd built-in. The
+//! canonicalization removes `.` components, redundant slashes, and `..`
+//! components together with their preceding component. Symbolic links are not
+//! resolved.
+
+use thiserror::Error;
+
+/// Error in canonicalization
+///
+/// This error occurs if a component preceding a `..` component does not name
+/// an existing directory. The contained string is the pathname up to the
+/// offending component.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("{0}: not a directory")]
+pub struct NonExistingDirectory(pub String);
+
+fn join(absolute: bool, components: &[&str]) -> String {
+    match (absolute, components.is_empty()) {
+        (true, _) => format!("/{}", components.join("/")),
+        (false, true) => ".".to_string(),
+        (false, false) => components.join("/"),
+    }
+}
+
+/// Canonicalizes the pathname.
+///
+/// Before removing a `..` component and its preceding component, this
+/// function calls `is_dir` with the pathname up to the preceding component to
+/// check if it names a directory. If `is_dir` returns false, this function
+/// fails.
+pub fn canonicalize<F>(path: &str, mut is_dir: F) -> Result<String, NonExistingDirectory>
+where
+    F: FnMut(&str) -> bool,
+{
+    let absolute = path.starts_with('/');
+    let mut components = Vec::new();
+    for component in path.split('/') {
+        match component {
+            "" | "." => (),
+            ".." => match components.last() {
+                Some(&last) if last != ".." => {
+                    let prefix = join(absolute, &components);
+                    if !is_dir(&prefix) {
+                        return Err(NonExistingDirectory(prefix));
+                    }
+                    components.pop();
+                }
+                // The parent of the root directory is the root itself.
+                None if absolute => (),
+                _ => components.push(component),
+            },
+            _ => components.push(component),
+        }
+    }
+    Ok(join(absolute, &components))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dots_and_slashes_are_removed() {
+        let result = canonicalize("/foo/./bar//baz/.", |_| unreachable!());
+        assert_eq!(result.unwrap(), "/foo/bar/baz");
+
+        let result = canonicalize("///", |_| unreachable!());
+        assert_eq!(result.unwrap(), "/");
+    }
+
+    #[test]
+    fn dot_dot_is_removed_with_preceding_component() {
+        let mut checked = Vec::new();
+        let result = canonicalize("/foo/bar/../baz/..", |path| {
+            checked.push(path.to_string());
+            true
+        });
+        assert_eq!(result.unwrap(), "/foo");
+        assert_eq!(checked, ["/foo/bar", "/foo/baz"]);
+    }
+
+    #[test]
+    fn dot_dot_at_root() {
+        let result = canonicalize("/../foo", |_| unreachable!());
+        assert_eq!(result.unwrap(), "/foo");
+    }
+
+    #[test]
+    fn leading_dot_dot_in_relative_path() {
+        let result = canonicalize("../../foo/..", |_| true);
+        assert_eq!(result.unwrap(), "../..");
+    }
+
+    #[test]
+    fn non_directory_before_dot_dot() {
+        let result = canonicalize("/foo/file/../bar", |path| path != "/foo/file");
+        assert_eq!(result, Err(NonExistingDirectory("/foo/file".to_string())));
+    }
+}
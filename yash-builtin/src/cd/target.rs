@@ -0,0 +1,338 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Determining the target directory of the cd built-in
+//!
+//! The [`target`] function computes the pathname of the directory to change
+//! to from the operand, `$HOME`, `$OLDPWD`, `$CDPATH`, and `$PWD`.
+
+use super::canonicalize::canonicalize;
+use super::canonicalize::NonExistingDirectory;
+use super::Command;
+use super::Mode;
+use crate::common::BuiltinEnv;
+use std::borrow::Cow;
+use std::ffi::CString;
+use thiserror::Error;
+use yash_env::semantics::Field;
+use yash_env::system::AtFlags;
+use yash_env::system::SFlag;
+use yash_env::system::AT_FDCWD;
+use yash_env::variable::Value::Scalar;
+use yash_env::variable::Variable;
+use yash_env::Env;
+use yash_env::System;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::MessageBase;
+use yash_syntax::source::Location;
+
+/// How the target was determined
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Origin {
+    /// The target was taken from `$HOME` since the operand was omitted.
+    Home,
+    /// The target was taken from `$OLDPWD` since the operand was `-`.
+    Oldpwd,
+    /// The target was found by a non-empty entry of `$CDPATH`.
+    Cdpath,
+    /// The target was made from the operand without using `$CDPATH`.
+    Operand,
+}
+
+impl Origin {
+    /// Whether the built-in should print the new working directory.
+    #[must_use]
+    pub fn should_print(self) -> bool {
+        matches!(self, Origin::Oldpwd | Origin::Cdpath)
+    }
+}
+
+/// Error in determining the target
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum TargetError {
+    /// The operand is omitted and `$HOME` is not set or empty.
+    #[error("$HOME is not set")]
+    UnsetHome {
+        /// Location of the built-in name
+        location: Location,
+    },
+
+    /// The operand is `-` and `$OLDPWD` is not set or empty.
+    #[error("$OLDPWD is not set")]
+    UnsetOldpwd {
+        /// Location of the operand
+        location: Location,
+    },
+
+    /// The operand is an empty string.
+    #[error("empty operand")]
+    EmptyOperand(Field),
+
+    /// A component preceding `..` does not name a directory.
+    #[error("cannot canonicalize the directory pathname")]
+    NonExistingDirectory {
+        /// Pathname that does not name a directory
+        missing: NonExistingDirectory,
+        /// Location of the built-in name or the operand
+        location: Location,
+    },
+}
+
+impl MessageBase for TargetError {
+    fn message_title(&self) -> Cow<str> {
+        self.to_string().into()
+    }
+
+    fn main_annotation(&self) -> Annotation<'_> {
+        use TargetError::*;
+        let (label, location): (Cow<str>, _) = match self {
+            UnsetHome { location } => ("the operand is omitted".into(), location),
+            UnsetOldpwd { location } => ("the operand is `-`".into(), location),
+            EmptyOperand(operand) => ("the operand is empty".into(), &operand.origin),
+            NonExistingDirectory { missing, location } => (missing.to_string().into(), location),
+        };
+        Annotation::new(AnnotationType::Error, label, location)
+    }
+}
+
+/// Tests if the pathname names a directory.
+///
+/// Symbolic links are followed.
+pub fn is_dir(env: &Env, path: &str) -> bool {
+    let Ok(path) = CString::new(path) else {
+        return false;
+    };
+    match env.system.fstatat(AT_FDCWD, &path, AtFlags::empty()) {
+        Ok(stat) => SFlag::from_bits_truncate(stat.st_mode) & SFlag::S_IFMT == SFlag::S_IFDIR,
+        Err(_) => false,
+    }
+}
+
+/// Returns the non-empty scalar value of the variable.
+fn non_empty_scalar<'a>(env: &'a Env, name: &str) -> Option<&'a str> {
+    match env.variables.get(name) {
+        Some(Variable {
+            value: Some(Scalar(value)),
+            ..
+        }) if !value.is_empty() => Some(value),
+        _ => None,
+    }
+}
+
+/// Searches `$CDPATH` for the directory.
+///
+/// Returns the pathname of the directory found, and whether it was found by a
+/// non-empty entry.
+fn search_cdpath(env: &Env, operand: &str) -> Option<(String, Origin)> {
+    let cdpath = non_empty_scalar(env, "CDPATH")?;
+    cdpath.split(':').find_map(|dir| {
+        let (candidate, origin) = match dir {
+            "" => (format!("./{operand}"), Origin::Operand),
+            _ if dir.ends_with('/') => (format!("{dir}{operand}"), Origin::Cdpath),
+            _ => (format!("{dir}/{operand}"), Origin::Cdpath),
+        };
+        is_dir(env, &candidate).then_some((candidate, origin))
+    })
+}
+
+/// Tests if the first component of the pathname is `.` or `..`.
+fn starts_with_dot_or_dot_dot(path: &str) -> bool {
+    let first = path.split('/').next().unwrap_or_default();
+    first == "." || first == ".."
+}
+
+/// Computes the target directory pathname.
+///
+/// `pwd` is the current working directory pathname that is prepended to a
+/// relative target in the logical mode.
+pub fn target(env: &Env, command: &Command, pwd: &str) -> Result<(String, Origin), TargetError> {
+    let (operand, origin, location) = match &command.operand {
+        None => {
+            let location = env.builtin_name().origin.clone();
+            match non_empty_scalar(env, "HOME") {
+                Some(home) => (home, Origin::Home, location),
+                None => return Err(TargetError::UnsetHome { location }),
+            }
+        }
+        Some(operand) if operand.value == "-" => {
+            let location = operand.origin.clone();
+            match non_empty_scalar(env, "OLDPWD") {
+                Some(oldpwd) => (oldpwd, Origin::Oldpwd, location),
+                None => return Err(TargetError::UnsetOldpwd { location }),
+            }
+        }
+        Some(operand) if operand.value.is_empty() => {
+            return Err(TargetError::EmptyOperand(operand.clone()))
+        }
+        Some(operand) => (
+            operand.value.as_str(),
+            Origin::Operand,
+            operand.origin.clone(),
+        ),
+    };
+
+    let (path, origin) = if operand.starts_with('/') || starts_with_dot_or_dot_dot(operand) {
+        (operand.to_owned(), origin)
+    } else {
+        search_cdpath(env, operand).unwrap_or_else(|| (operand.to_owned(), origin))
+    };
+
+    if command.mode == Mode::Physical {
+        return Ok((path, origin));
+    }
+
+    let path = if path.starts_with('/') || pwd.is_empty() {
+        path
+    } else if pwd.ends_with('/') {
+        format!("{pwd}{path}")
+    } else {
+        format!("{pwd}/{path}")
+    };
+
+    match canonicalize(&path, |dir| is_dir(env, dir)) {
+        Ok(path) => Ok((path, origin)),
+        Err(missing) => Err(TargetError::NonExistingDirectory { missing, location }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cd::tests::create_dir;
+    use yash_env::stack::Frame;
+    use yash_env::variable::Scope::Global;
+    use yash_env::VirtualSystem;
+
+    fn env_with_dirs() -> Env {
+        let system = VirtualSystem::new();
+        create_dir(&system.state, "/foo/bar");
+        create_dir(&system.state, "/baz/bar");
+        Env::with_system(Box::new(system))
+    }
+
+    fn command(operand: Option<&str>) -> Command {
+        Command {
+            mode: Mode::Logical,
+            operand: operand.map(Field::dummy),
+        }
+    }
+
+    fn assign(env: &mut Env, name: &str, value: &str) {
+        env.variables
+            .assign(Global, name.to_string(), Variable::new(value))
+            .unwrap();
+    }
+
+    #[test]
+    fn home() {
+        let mut env = env_with_dirs();
+        assign(&mut env, "HOME", "/foo");
+        let mut env = env.push_frame(Frame::Builtin {
+            name: Field::dummy("cd"),
+            is_special: false,
+        });
+        let result = target(&env, &command(None), "/");
+        assert_eq!(result, Ok(("/foo".to_string(), Origin::Home)));
+
+        assign(&mut env, "HOME", "");
+        let result = target(&env, &command(None), "/");
+        assert_eq!(
+            result,
+            Err(TargetError::UnsetHome {
+                location: Location::dummy("cd")
+            })
+        );
+    }
+
+    #[test]
+    fn oldpwd() {
+        let mut env = env_with_dirs();
+        let result = target(&env, &command(Some("-")), "/");
+        assert_eq!(
+            result,
+            Err(TargetError::UnsetOldpwd {
+                location: Location::dummy("-")
+            })
+        );
+
+        assign(&mut env, "OLDPWD", "/foo/bar");
+        let result = target(&env, &command(Some("-")), "/");
+        assert_eq!(result, Ok(("/foo/bar".to_string(), Origin::Oldpwd)));
+    }
+
+    #[test]
+    fn empty_operand() {
+        let env = env_with_dirs();
+        let result = target(&env, &command(Some("")), "/");
+        assert_eq!(result, Err(TargetError::EmptyOperand(Field::dummy(""))));
+    }
+
+    #[test]
+    fn relative_operand_is_appended_to_pwd() {
+        let env = env_with_dirs();
+        let result = target(&env, &command(Some("bar/.")), "/foo");
+        assert_eq!(result, Ok(("/foo/bar".to_string(), Origin::Operand)));
+        let result = target(&env, &command(Some("../baz")), "/foo/");
+        assert_eq!(result, Ok(("/baz".to_string(), Origin::Operand)));
+    }
+
+    #[test]
+    fn cdpath_search() {
+        let mut env = env_with_dirs();
+        assign(&mut env, "CDPATH", "/none:/baz/:/foo");
+        let result = target(&env, &command(Some("bar")), "/");
+        assert_eq!(result, Ok(("/baz/bar".to_string(), Origin::Cdpath)));
+
+        // CDPATH is not used for operands starting with a dot
+        let result = target(&env, &command(Some("./bar")), "/foo");
+        assert_eq!(result, Ok(("/foo/bar".to_string(), Origin::Operand)));
+    }
+
+    #[test]
+    fn cdpath_empty_entry_is_current_directory() {
+        let mut env = env_with_dirs();
+        env.system.chdir(&CString::new("/foo").unwrap()).unwrap();
+        assign(&mut env, "CDPATH", ":/baz");
+        let result = target(&env, &command(Some("bar")), "/foo");
+        assert_eq!(result, Ok(("/foo/bar".to_string(), Origin::Operand)));
+    }
+
+    #[test]
+    fn physical_mode_does_not_canonicalize() {
+        let env = env_with_dirs();
+        let command = Command {
+            mode: Mode::Physical,
+            operand: Some(Field::dummy("./bar/..")),
+        };
+        let result = target(&env, &command, "/foo");
+        assert_eq!(result, Ok(("./bar/..".to_string(), Origin::Operand)));
+    }
+
+    #[test]
+    fn non_existing_directory_before_dot_dot() {
+        let env = env_with_dirs();
+        let result = target(&env, &command(Some("/foo/none/../bar")), "/");
+        assert_eq!(
+            result,
+            Err(TargetError::NonExistingDirectory {
+                missing: NonExistingDirectory("/foo/none".to_string()),
+                location: Location::dummy("/foo/none/../bar"),
+            })
+        );
+    }
+}
@@ -0,0 +1,156 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Command built-in
+//!
+//! The **`command`** built-in executes a utility bypassing shell functions, or
+//! describes how command names are resolved.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! command [-p] name [argument...]
+//! ```
+//!
+//! ```sh
+//! command -v|-V [-p] name...
+//! ```
+//!
+//! # Semantics
+//!
+//! Without the `-v` or `-V` option, the command built-in performs the
+//! [command search](yash_semantics::command_search) for the *name* operand,
+//! ignoring shell functions, and invokes the found built-in or external
+//! utility with the *argument*s.
+//!
+//! If the invoked utility is a special built-in, it is executed as if it were
+//! a regular built-in. That is, an error in the special built-in does not
+//! cause the shell to exit.
+//!
+//! With the `-v` or `-V` option, the built-in describes how each *name* would
+//! be interpreted as a command name. The description identifies the name as
+//! an alias, keyword, function, built-in, or external utility.
+//!
+//! # Options
+//!
+//! The **`-p`** (**`--path`**) option makes the built-in search the standard
+//! path instead of the current `$PATH` for the utility. The standard path is
+//! a system-dependent value that is expected to contain all the standard
+//! utilities.
+//!
+//! The **`-v`** (**`--identify`**) option prints a line for each *name*. For
+//! aliases, the line is a command that redefines the alias. For external
+//! utilities, the line is the absolute path to the utility. For the others,
+//! the line is the name itself.
+//!
+//! The **`-V`** (**`--verbose-identify`**) option is like `-v`, but prints a
+//! human-readable description of each *name*.
+//!
+//! If both `-v` and `-V` are specified, the last one is effective.
+//!
+//! # Operands
+//!
+//! The *name* operand is the name of the utility to be invoked or described.
+//! The following *argument*s are passed to the invoked utility.
+//!
+//! # Exit status
+//!
+//! Without `-v` or `-V`, the exit status is that of the invoked utility. If
+//! the utility is not found, the exit status is 127. If the utility is found
+//! but cannot be executed, the exit status is 126. If there is no *name*
+//! operand, the exit status is zero.
+//!
+//! With `-v` or `-V`, the exit status is zero if all the *name*s are found,
+//! and one otherwise.
+//!
+//! # Errors
+//!
+//! It is an error if the utility is not found.
+//!
+//! With `-V`, a name that is not found is reported as an error. With `-v`,
+//! it is silently ignored except for the exit status.
+//!
+//! # Portability
+//!
+//! POSIX requires the `-v` and `-V` options to accept only one *name*
+//! operand. Specifying more than one is an extension. The long options are
+//! not portable either.
+//!
+//! The output format of the `-V` option is not specified by POSIX.
+//!
+//! # Implementation notes
+//!
+//! The standard path is obtained by [`System::confstr_path`]. If it is not
+//! available, the current `$PATH` is used instead.
+//!
+//! [`System::confstr_path`]: yash_env::System::confstr_path
+
+use crate::common::print_error_message;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::Env;
+
+/// Parameters that affect the command search
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Search {
+    /// Whether the standard path is searched instead of `$PATH`
+    pub standard_path: bool,
+}
+
+/// Parameters for invoking a utility
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Invoke {
+    /// Command name and arguments
+    pub fields: Vec<Field>,
+    /// Parameters for searching the utility
+    pub search: Search,
+}
+
+/// Parameters for identifying commands
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Identify {
+    /// Command names to be identified
+    pub names: Vec<Field>,
+    /// Parameters for searching the utilities
+    pub search: Search,
+    /// Whether the `-V` option is specified
+    pub verbose: bool,
+}
+
+/// Parsed command line arguments of the command built-in
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Command {
+    /// Invoking a utility
+    Invoke(Invoke),
+    /// Identifying commands (`-v` or `-V`)
+    Identify(Identify),
+}
+
+pub mod identify;
+pub mod invoke;
+pub mod search;
+pub mod syntax;
+
+/// Entry point for executing the `command` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    match syntax::parse(env, args) {
+        Ok(Command::Invoke(invoke)) => invoke.execute(env).await,
+        Ok(Command::Identify(identify)) => identify.execute(env).await,
+        Err(error) => print_error_message(env, &error).await,
+    }
+}
@@ -0,0 +1,282 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Identifying how command names are resolved
+
+use super::search::SearchEnv;
+use super::Identify;
+use super::Search;
+use crate::common::not_found_message;
+use crate::common::print_error_message;
+use crate::common::Print;
+use std::path::PathBuf;
+use yash_env::builtin::Result;
+use yash_env::builtin::Type;
+use yash_env::semantics::ExitStatus;
+use yash_env::Env;
+use yash_env::System;
+use yash_quote::quoted;
+use yash_semantics::command_search::search;
+use yash_semantics::command_search::PathEnv;
+use yash_semantics::command_search::Target;
+use yash_syntax::parser::lex::Keyword;
+
+/// Category of a command name
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Category {
+    /// Alias with the replacement string
+    Alias(String),
+    /// Reserved word
+    Keyword,
+    /// Shell function
+    Function,
+    /// Built-in utility of the type
+    Builtin(Type),
+    /// External utility at the absolute path
+    External(String),
+}
+
+impl Category {
+    /// Returns the description of the name in this category.
+    ///
+    /// The result is for the `-V` option if `verbose` is true, and for the
+    /// `-v` option otherwise. The result does not end with a newline.
+    #[must_use]
+    pub fn describe(&self, name: &str, verbose: bool) -> String {
+        use Category::*;
+        if !verbose {
+            return match self {
+                Alias(replacement) => format!("alias {}={}", name, quoted(replacement)),
+                Keyword | Function | Builtin(_) => name.to_owned(),
+                External(path) => path.clone(),
+            };
+        }
+        match self {
+            Alias(replacement) => format!("{name}: alias for `{replacement}`"),
+            Keyword => format!("{name}: keyword"),
+            Function => format!("{name}: function"),
+            Builtin(Type::Special) => format!("{name}: special built-in"),
+            Builtin(Type::Intrinsic) => format!("{name}: intrinsic built-in"),
+            Builtin(Type::NonIntrinsic) => format!("{name}: non-intrinsic built-in"),
+            External(path) => format!("{name}: external utility at {path}"),
+        }
+    }
+}
+
+/// Determines the category of the command name.
+///
+/// Returns `None` if the name is not found.
+pub fn categorize(env: &mut Env, name: &str, search_params: &Search) -> Option<Category> {
+    if let Some(alias) = env.aliases.get(name) {
+        return Some(Category::Alias(alias.0.replacement.clone()));
+    }
+
+    if Keyword::try_from(name).is_ok() {
+        return Some(Category::Keyword);
+    }
+
+    let search_env = &mut SearchEnv::new(env, search_params, true);
+    match search(search_env, name)? {
+        Target::Builtin(builtin) => Some(Category::Builtin(builtin.r#type)),
+        Target::Function(_) => Some(Category::Function),
+        Target::External { path } => {
+            if !search_env.is_executable_file(&path) {
+                return None;
+            }
+            let path = PathBuf::from(path.into_string().ok()?);
+            let path = if path.is_absolute() {
+                path
+            } else {
+                let mut cwd = search_env.env.system.getcwd().ok()?;
+                cwd.push(path);
+                cwd
+            };
+            Some(Category::External(
+                path.into_os_string().into_string().ok()?,
+            ))
+        }
+    }
+}
+
+impl Identify {
+    /// Prints the descriptions of the command names.
+    pub async fn execute(self, env: &mut Env) -> Result {
+        let mut output = String::new();
+        let mut missing = Vec::new();
+        for name in &self.names {
+            match categorize(env, &name.value, &self.search) {
+                Some(category) => {
+                    output.push_str(&category.describe(&name.value, self.verbose));
+                    output.push('\n');
+                }
+                None => missing.push(name),
+            }
+        }
+
+        let result = env.print(&output).await;
+        if result.exit_status() != ExitStatus::SUCCESS || missing.is_empty() {
+            return result;
+        }
+
+        let mut result = Result::new(ExitStatus::FAILURE);
+        if self.verbose {
+            for name in missing {
+                let error = print_error_message(env, not_found_message("command", name)).await;
+                result = Result::with_exit_status_and_divert(ExitStatus::FAILURE, error.divert());
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use yash_env::function::HashEntry as FunctionEntry;
+    use yash_env::semantics::Field;
+    use yash_env::system::r#virtual::FileBody;
+    use yash_env::system::r#virtual::INode;
+    use yash_env::system::r#virtual::Mode;
+    use yash_env::variable::Scope;
+    use yash_env::variable::Variable;
+    use yash_syntax::alias::HashEntry as AliasEntry;
+    use yash_syntax::source::Location;
+
+    fn identify(names: &[&str], verbose: bool) -> Identify {
+        Identify {
+            names: Field::dummies(names.iter().copied()),
+            search: Search::default(),
+            verbose,
+        }
+    }
+
+    #[test]
+    fn categorizing_alias_and_keyword() {
+        let mut env = Env::new_virtual();
+        env.aliases.insert(AliasEntry::new(
+            "ll".into(),
+            "ls -l".into(),
+            false,
+            Location::dummy(""),
+        ));
+        let search = Search::default();
+        assert_eq!(
+            categorize(&mut env, "ll", &search),
+            Some(Category::Alias("ls -l".into()))
+        );
+        assert_eq!(categorize(&mut env, "if", &search), Some(Category::Keyword));
+        assert_eq!(categorize(&mut env, "foo", &search), None);
+    }
+
+    #[test]
+    fn categorizing_function_and_builtins() {
+        let mut env = Env::new_virtual();
+        env.builtins.extend(crate::BUILTINS.iter().cloned());
+        let body = "{ :; }".parse().unwrap();
+        let function = FunctionEntry::new("f".into(), Rc::new(body), Location::dummy(""), false);
+        env.functions.insert(function);
+        let search = Search::default();
+        assert_eq!(categorize(&mut env, "f", &search), Some(Category::Function));
+        assert_eq!(
+            categorize(&mut env, "exit", &search),
+            Some(Category::Builtin(Type::Special))
+        );
+        assert_eq!(
+            categorize(&mut env, "cd", &search),
+            Some(Category::Builtin(Type::Intrinsic))
+        );
+    }
+
+    #[test]
+    fn categorizing_external_utility() {
+        in_virtual_system(|mut env, state| async move {
+            let content = Rc::new(RefCell::new(INode {
+                body: FileBody::new([]),
+                permissions: Mode(0o755),
+            }));
+            state
+                .borrow_mut()
+                .file_system
+                .save("/bin/foo", content)
+                .unwrap();
+            env.variables
+                .assign(Scope::Global, "PATH".into(), Variable::new("/bin"))
+                .unwrap();
+            let search = Search::default();
+            assert_eq!(
+                categorize(&mut env, "foo", &search),
+                Some(Category::External("/bin/foo".into()))
+            );
+            assert_eq!(categorize(&mut env, "bar", &search), None);
+        })
+    }
+
+    #[test]
+    fn describing() {
+        let alias = Category::Alias("a 'b'".into());
+        assert_eq!(alias.describe("x", false), "alias x=\"a 'b'\"");
+        assert_eq!(alias.describe("x", true), "x: alias for `a 'b'`");
+        let external = Category::External("/bin/ls".into());
+        assert_eq!(external.describe("ls", false), "/bin/ls");
+        assert_eq!(
+            external.describe("ls", true),
+            "ls: external utility at /bin/ls"
+        );
+        let builtin = Category::Builtin(Type::Special);
+        assert_eq!(builtin.describe("exit", false), "exit");
+        assert_eq!(builtin.describe("exit", true), "exit: special built-in");
+    }
+
+    #[test]
+    fn printing_descriptions() {
+        in_virtual_system(|mut env, state| async move {
+            env.builtins.extend(crate::BUILTINS.iter().cloned());
+            let result = identify(&["exit", "while"], false).execute(&mut env).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "exit\nwhile\n"));
+        })
+    }
+
+    #[test]
+    fn missing_name_without_verbose() {
+        in_virtual_system(|mut env, state| async move {
+            let result = identify(&["foo"], false).execute(&mut env).await;
+            assert_eq!(result, Result::new(ExitStatus::FAILURE));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, ""));
+            assert_stderr(&state, |stderr| assert_eq!(stderr, ""));
+        })
+    }
+
+    #[test]
+    fn missing_name_with_verbose() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(yash_env::stack::Frame::Builtin {
+                name: Field::dummy("command"),
+                is_special: false,
+            });
+            let result = identify(&["foo"], true).execute(&mut env).await;
+            assert_eq!(result, Result::new(ExitStatus::FAILURE));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("foo"), "{stderr:?}")
+            });
+        })
+    }
+}
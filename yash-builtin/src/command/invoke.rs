@@ -0,0 +1,172 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Invoking a utility bypassing functions
+
+use super::search::SearchEnv;
+use super::Invoke;
+use std::ffi::CString;
+use yash_env::builtin::Result;
+use yash_env::stack::Frame;
+use yash_env::Env;
+use yash_semantics::command::simple_command::start_external_utility_in_subshell_and_wait;
+use yash_semantics::command_search::search;
+use yash_semantics::command_search::Target;
+
+impl Invoke {
+    /// Invokes the utility.
+    ///
+    /// A special built-in is executed as a regular built-in, that is, the
+    /// [`Frame::Builtin`] pushed for the built-in has `is_special` set to
+    /// false.
+    pub async fn execute(self, env: &mut Env) -> Result {
+        let Some(name) = self.fields.first() else {
+            return Result::default();
+        };
+
+        let target = search(&mut SearchEnv::new(env, &self.search, false), &name.value);
+        match target {
+            Some(Target::Builtin(builtin)) => {
+                let mut fields = self.fields;
+                let name = fields.remove(0);
+                let mut env = env.push_frame(Frame::Builtin {
+                    name,
+                    is_special: false,
+                });
+                (builtin.execute)(&mut env, fields).await
+            }
+
+            Some(Target::Function(_)) => unreachable!("functions should be ignored"),
+
+            Some(Target::External { path }) => {
+                let exit_status =
+                    start_external_utility_in_subshell_and_wait(env, path, self.fields).await;
+                Result::new(exit_status)
+            }
+
+            None => {
+                let path = CString::default();
+                let exit_status =
+                    start_external_utility_in_subshell_and_wait(env, path, self.fields).await;
+                Result::new(exit_status)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Search;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use std::ops::ControlFlow::{Break, Continue};
+    use std::rc::Rc;
+    use yash_env::builtin::Builtin;
+    use yash_env::builtin::Type::Special;
+    use yash_env::function::HashEntry;
+    use yash_env::semantics::Divert;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::semantics::Field;
+    use yash_syntax::source::Location;
+
+    fn is_special_builtin(
+        env: &mut Env,
+        _args: Vec<Field>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result> + '_>> {
+        let is_special = matches!(
+            env.stack.last(),
+            Some(Frame::Builtin {
+                is_special: true,
+                ..
+            })
+        );
+        Box::pin(async move { Result::new(ExitStatus(is_special as i32)) })
+    }
+
+    #[test]
+    fn no_operands() {
+        let mut env = Env::new_virtual();
+        let invoke = Invoke {
+            fields: vec![],
+            search: Search::default(),
+        };
+        let result = invoke.execute(&mut env).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+    }
+
+    #[test]
+    fn special_builtin_is_executed_as_regular() {
+        let mut env = Env::new_virtual();
+        env.builtins.insert(
+            "foo",
+            Builtin {
+                r#type: Special,
+                execute: is_special_builtin,
+            },
+        );
+        let invoke = Invoke {
+            fields: Field::dummies(["foo"]),
+            search: Search::default(),
+        };
+        let result = invoke.execute(&mut env).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus(0)));
+    }
+
+    #[test]
+    fn function_is_bypassed() {
+        let mut env = Env::new_virtual();
+        env.builtins.extend(crate::BUILTINS.iter().cloned());
+        let body = "{ return -n 42; }".parse().unwrap();
+        let function = HashEntry::new("exit".into(), Rc::new(body), Location::dummy(""), false);
+        env.functions.insert(function);
+        let invoke = Invoke {
+            fields: Field::dummies(["exit", "5"]),
+            search: Search::default(),
+        };
+        let result = invoke.execute(&mut env).now_or_never().unwrap();
+        assert_eq!(result.divert(), Break(Divert::Exit(Some(ExitStatus(5)))));
+    }
+
+    #[test]
+    fn error_in_special_builtin_is_not_fatal() {
+        in_virtual_system(|mut env, state| async move {
+            env.builtins.extend(crate::BUILTINS.iter().cloned());
+            let invoke = Invoke {
+                fields: Field::dummies(["set", "-@"]),
+                search: Search::default(),
+            };
+            let result = invoke.execute(&mut env).await;
+            assert_eq!(result.exit_status(), ExitStatus::ERROR);
+            assert_eq!(result.divert(), Continue(()));
+            assert_stderr(&state, |stderr| assert_ne!(stderr, ""));
+        })
+    }
+
+    #[test]
+    fn utility_not_found() {
+        in_virtual_system(|mut env, state| async move {
+            let invoke = Invoke {
+                fields: Field::dummies(["no_such_utility"]),
+                search: Search::default(),
+            };
+            let result = invoke.execute(&mut env).await;
+            assert_eq!(result, Result::new(ExitStatus::NOT_FOUND));
+            assert_stderr(&state, |stderr| assert!(stderr.contains("no_such_utility")));
+        })
+    }
+}
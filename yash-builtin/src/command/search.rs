@@ -0,0 +1,198 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Command search adapted for the command built-in
+//!
+//! This module provides [`SearchEnv`], a wrapper of [`Env`] that implements
+//! [`yash_semantics::command_search::SearchEnv`] so that the command search
+//! can optionally ignore functions and use the standard path.
+
+use super::Search;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::ffi::CString;
+use yash_env::builtin::Builtin;
+use yash_env::function::FunctionSet;
+use yash_env::variable::Variable;
+use yash_env::Env;
+use yash_env::System;
+use yash_semantics::command_search::PathEnv;
+
+/// Environment for the command search performed by the command built-in
+#[derive(Debug)]
+pub struct SearchEnv<'a> {
+    /// Wrapped environment
+    pub env: &'a mut Env,
+    /// Standard path that overrides `$PATH`
+    standard_path: Option<Variable>,
+    /// Empty function set that hides the real one
+    no_functions: Option<FunctionSet>,
+}
+
+impl<'a> SearchEnv<'a> {
+    /// Creates a new search environment.
+    ///
+    /// If `search.standard_path` is true, the environment uses the standard
+    /// path returned by [`System::confstr_path`] instead of `$PATH`. The
+    /// current `$PATH` is still used if the standard path is not available.
+    ///
+    /// If `include_functions` is false, the environment hides all functions.
+    pub fn new(env: &'a mut Env, search: &Search, include_functions: bool) -> Self {
+        let standard_path = if search.standard_path {
+            env.system
+                .confstr_path()
+                .ok()
+                .and_then(|path| path.into_string().ok())
+                .map(Variable::new)
+        } else {
+            None
+        };
+        let no_functions = if include_functions {
+            None
+        } else {
+            Some(FunctionSet::new())
+        };
+        SearchEnv {
+            env,
+            standard_path,
+            no_functions,
+        }
+    }
+}
+
+impl PathEnv for SearchEnv<'_> {
+    fn path(&self) -> Option<&Variable> {
+        match &self.standard_path {
+            Some(path) => Some(path),
+            None => self.env.path(),
+        }
+    }
+    fn is_executable_file(&self, path: &CStr) -> bool {
+        self.env.is_executable_file(path)
+    }
+    fn cached_path(&mut self, name: &str) -> Option<CString> {
+        match &self.standard_path {
+            Some(_) => None,
+            None => self.env.cached_path(name),
+        }
+    }
+    fn cache_path(&mut self, name: &str, path: &CStr) {
+        if self.standard_path.is_none() {
+            self.env.cache_path(name, path)
+        }
+    }
+}
+
+impl yash_semantics::command_search::SearchEnv for SearchEnv<'_> {
+    fn builtins(&self) -> &HashMap<&'static str, Builtin> {
+        &self.env.builtins
+    }
+    fn functions(&self) -> &FunctionSet {
+        match &self.no_functions {
+            Some(functions) => functions,
+            None => &self.env.functions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use yash_env::function::HashEntry;
+    use yash_env::variable::Scope;
+    use yash_env::VirtualSystem;
+    use yash_semantics::command_search::SearchEnv as _;
+    use yash_syntax::source::Location;
+    use yash_syntax::syntax::FullCompoundCommand;
+
+    fn define_function(env: &mut Env, name: &str) {
+        let body: FullCompoundCommand = "{ :; }".parse().unwrap();
+        let location = Location::dummy(name);
+        let function = HashEntry::new(name.to_string(), Rc::new(body), location, false);
+        env.functions.insert(function);
+    }
+
+    #[test]
+    fn functions_can_be_hidden() {
+        let mut env = Env::new_virtual();
+        define_function(&mut env, "foo");
+
+        let search_env = SearchEnv::new(&mut env, &Search::default(), true);
+        assert!(search_env.functions().contains("foo"));
+
+        let search_env = SearchEnv::new(&mut env, &Search::default(), false);
+        assert!(search_env.functions().is_empty());
+    }
+
+    #[test]
+    fn path_from_variable() {
+        let mut env = Env::new_virtual();
+        env.variables
+            .assign(Scope::Global, "PATH".to_string(), Variable::new("/foo"))
+            .unwrap();
+        let search_env = SearchEnv::new(&mut env, &Search::default(), true);
+        assert_eq!(search_env.path(), Some(&Variable::new("/foo")));
+    }
+
+    #[test]
+    fn standard_path() {
+        let system = VirtualSystem::new();
+        system.state.borrow_mut().path = "/bin:/usr/bin".into();
+        let mut env = Env::with_system(Box::new(system));
+        env.variables
+            .assign(Scope::Global, "PATH".to_string(), Variable::new("/foo"))
+            .unwrap();
+        let search = Search {
+            standard_path: true,
+        };
+        let search_env = SearchEnv::new(&mut env, &search, true);
+        assert_eq!(search_env.path(), Some(&Variable::new("/bin:/usr/bin")));
+    }
+
+    #[test]
+    fn standard_path_unavailable() {
+        let mut env = Env::new_virtual();
+        env.variables
+            .assign(Scope::Global, "PATH".to_string(), Variable::new("/foo"))
+            .unwrap();
+        let search = Search {
+            standard_path: true,
+        };
+        let search_env = SearchEnv::new(&mut env, &search, true);
+        assert_eq!(search_env.path(), Some(&Variable::new("/foo")));
+    }
+
+    #[test]
+    fn standard_path_bypasses_path_cache() {
+        let system = VirtualSystem::new();
+        system.state.borrow_mut().path = "/bin:/usr/bin".into();
+        let mut env = Env::with_system(Box::new(system));
+        let foo = CString::new("/foo/foo").unwrap();
+        env.cache_path("foo", &foo);
+        let search = Search {
+            standard_path: true,
+        };
+
+        let mut search_env = SearchEnv::new(&mut env, &search, true);
+        assert_eq!(search_env.cached_path("foo"), None);
+        search_env.cache_path("bar", &foo);
+        assert_eq!(env.path_cache.get("bar"), None);
+
+        let mut search_env = SearchEnv::new(&mut env, &Search::default(), true);
+        assert_eq!(search_env.cached_path("foo"), Some(foo));
+    }
+}
@@ -0,0 +1,141 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Command line argument parser for the command built-in
+
+use super::Command;
+use super::Identify;
+use super::Invoke;
+use super::Search;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Error;
+use crate::common::syntax::Mode;
+use crate::common::syntax::OptionSpec;
+use yash_env::semantics::Field;
+use yash_env::Env;
+
+/// Result of parsing command line arguments
+pub type Result = std::result::Result<Command, Error<'static>>;
+
+const OPTION_SPECS: &[OptionSpec] = &[
+    OptionSpec::new().short('p').long("path"),
+    OptionSpec::new().short('v').long("identify"),
+    OptionSpec::new().short('V').long("verbose-identify"),
+];
+
+/// Parses command line arguments for the command built-in.
+pub fn parse(env: &Env, args: Vec<Field>) -> Result {
+    let (options, operands) = parse_arguments(OPTION_SPECS, Mode::with_env(env), args)?;
+
+    let mut search = Search::default();
+    let mut identify = None;
+    for option in options {
+        match option.spec.get_short() {
+            Some('p') => search.standard_path = true,
+            Some('v') => identify = Some(false),
+            Some('V') => identify = Some(true),
+            _ => unreachable!("unhandled option: {:?}", option.spec),
+        }
+    }
+
+    Ok(match identify {
+        None => Command::Invoke(Invoke {
+            fields: operands,
+            search,
+        }),
+        Some(verbose) => Command::Identify(Identify {
+            names: operands,
+            search,
+            verbose,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invoke_without_options() {
+        let env = Env::new_virtual();
+        let args = Field::dummies(["foo", "-v", "bar"]);
+        let result = parse(&env, args.clone());
+        assert_eq!(
+            result,
+            Ok(Command::Invoke(Invoke {
+                fields: args,
+                search: Search::default(),
+            }))
+        );
+    }
+
+    #[test]
+    fn invoke_with_standard_path() {
+        let env = Env::new_virtual();
+        let args = Field::dummies(["-p", "foo"]);
+        let fields = args[1..].to_vec();
+        let result = parse(&env, args);
+        assert_eq!(
+            result,
+            Ok(Command::Invoke(Invoke {
+                fields,
+                search: Search {
+                    standard_path: true
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn identify() {
+        let env = Env::new_virtual();
+        let args = Field::dummies(["-v", "foo", "bar"]);
+        let names = args[1..].to_vec();
+        let result = parse(&env, args);
+        assert_eq!(
+            result,
+            Ok(Command::Identify(Identify {
+                names,
+                search: Search::default(),
+                verbose: false,
+            }))
+        );
+    }
+
+    #[test]
+    fn last_identify_option_wins() {
+        let env = Env::new_virtual();
+        let args = Field::dummies(["-vpV", "foo"]);
+        let names = args[1..].to_vec();
+        let result = parse(&env, args);
+        assert_eq!(
+            result,
+            Ok(Command::Identify(Identify {
+                names,
+                search: Search {
+                    standard_path: true
+                },
+                verbose: true,
+            }))
+        );
+
+        let args = Field::dummies(["-V", "-v", "foo"]);
+        let result = parse(&env, args);
+        assert_matches::assert_matches!(result, Ok(Command::Identify(identify)) => {
+            assert!(!identify.verbose);
+        });
+    }
+}
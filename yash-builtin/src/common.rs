@@ -34,11 +34,14 @@ use yash_env::stack::Frame;
 use yash_env::stack::Stack;
 use yash_env::system::Errno;
 use yash_env::SharedSystem;
+use yash_syntax::parser::lex::is_name_char;
 use yash_syntax::source::pretty::Annotation;
 use yash_syntax::source::pretty::AnnotationType;
 use yash_syntax::source::pretty::Message;
 use yash_syntax::source::Location;
 
+pub mod escape;
+pub mod job;
 pub mod syntax;
 
 /// Execution environment extension for examining the currently running
@@ -128,12 +131,22 @@ impl BuiltinEnv for yash_env::Env {
 pub trait Stdout {
     /// Prints a string to the standard output.
     async fn try_print(&mut self, text: &str) -> Result<(), Errno>;
+
+    /// Prints bytes to the standard output.
+    ///
+    /// Unlike [`try_print`](Self::try_print), this function can print bytes
+    /// that are not valid UTF-8.
+    async fn try_print_bytes(&mut self, bytes: &[u8]) -> Result<(), Errno>;
 }
 
 #[async_trait(?Send)]
 impl Stdout for SharedSystem {
     async fn try_print(&mut self, text: &str) -> Result<(), Errno> {
-        self.write_all(Fd::STDOUT, text.as_bytes()).await.map(drop)
+        self.try_print_bytes(text.as_bytes()).await
+    }
+
+    async fn try_print_bytes(&mut self, bytes: &[u8]) -> Result<(), Errno> {
+        self.write_all(Fd::STDOUT, bytes).await.map(drop)
     }
 }
 
@@ -143,6 +156,15 @@ impl Stdout for String {
         self.push_str(text);
         Ok(())
     }
+
+    /// Appends the bytes to the string.
+    ///
+    /// Since a string cannot contain invalid UTF-8, such bytes are replaced
+    /// with U+FFFD.
+    async fn try_print_bytes(&mut self, bytes: &[u8]) -> Result<(), Errno> {
+        self.push_str(&String::from_utf8_lossy(bytes));
+        Ok(())
+    }
 }
 
 /// Trait for types that can be cast to [`Stdout`].
@@ -221,6 +243,25 @@ pub trait Print {
     /// If an error occurs while printing, an error message is printed to the
     /// standard error and a non-zero exit status is returned.
     async fn print(&mut self, text: &str) -> yash_env::builtin::Result;
+
+    /// Prints bytes to the standard output.
+    ///
+    /// This function is the same as [`print`](Self::print) except that the
+    /// bytes are printed intact even if they are not valid UTF-8.
+    async fn print_bytes(&mut self, bytes: &[u8]) -> yash_env::builtin::Result;
+}
+
+/// Reports an error that occurred while printing to the standard output.
+async fn print_stdout_error<E>(env: &mut E, errno: Errno) -> yash_env::builtin::Result
+where
+    E: BuiltinEnv + AsStderr,
+{
+    let message = Message {
+        r#type: AnnotationType::Error,
+        title: format!("error printing results to stdout: {errno}").into(),
+        annotations: vec![],
+    };
+    print_failure_message(env, message).await
 }
 
 #[async_trait(?Send)]
@@ -228,14 +269,14 @@ impl<T: BuiltinEnv + AsStdout + AsStderr> Print for T {
     async fn print(&mut self, text: &str) -> yash_env::builtin::Result {
         match self.as_stdout().try_print(text).await {
             Ok(()) => yash_env::builtin::Result::default(),
-            Err(errno) => {
-                let message = Message {
-                    r#type: AnnotationType::Error,
-                    title: format!("error printing results to stdout: {errno}").into(),
-                    annotations: vec![],
-                };
-                print_failure_message(self, message).await
-            }
+            Err(errno) => print_stdout_error(self, errno).await,
+        }
+    }
+
+    async fn print_bytes(&mut self, bytes: &[u8]) -> yash_env::builtin::Result {
+        match self.as_stdout().try_print_bytes(bytes).await {
+            Ok(()) => yash_env::builtin::Result::default(),
+            Err(errno) => print_stdout_error(self, errno).await,
         }
     }
 }
@@ -339,6 +380,54 @@ where
     .await
 }
 
+/// Creates an error message for an operand that names a nonexistent item.
+///
+/// The `kind` describes what the operand is supposed to name, e.g., `"alias"`.
+/// The message is titled "_kind_ not found" and has an annotation pointing to
+/// the operand.
+#[must_use]
+pub fn not_found_message<'a>(kind: &str, operand: &'a Field) -> Message<'a> {
+    Message {
+        r#type: AnnotationType::Error,
+        title: format!("{kind} not found").into(),
+        annotations: vec![Annotation::new(
+            AnnotationType::Error,
+            format!("no such {kind} `{}`", operand.value).into(),
+            &operand.origin,
+        )],
+    }
+}
+
+/// Tests whether the string is a valid variable name.
+///
+/// A valid name is a non-empty string of [name
+/// characters](yash_syntax::parser::lex::is_name_char) that does not start
+/// with a digit.
+#[must_use]
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(is_name_char)
+}
+
+/// Creates an error message for an operand that contains an invalid variable
+/// name.
+///
+/// The message is titled "invalid variable name" and has an annotation
+/// pointing to the operand.
+#[must_use]
+pub fn invalid_name_message<'a>(name: &str, operand: &'a Field) -> Message<'a> {
+    Message {
+        r#type: AnnotationType::Error,
+        title: "invalid variable name".into(),
+        annotations: vec![Annotation::new(
+            AnnotationType::Error,
+            format!("`{name}` is not a valid variable name").into(),
+            &operand.origin,
+        )],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,6 +441,39 @@ mod tests {
         assert_eq!(stack.builtin_name().value, "my built-in");
     }
 
+    #[test]
+    fn not_found_message_with_kind() {
+        let operand = Field::dummy("foo");
+        let message = not_found_message("alias", &operand);
+        assert_eq!(message.title, "alias not found");
+        assert_eq!(message.annotations.len(), 1);
+        assert_eq!(message.annotations[0].label, "no such alias `foo`");
+        assert_eq!(*message.annotations[0].location, operand.origin);
+    }
+
+    #[test]
+    fn valid_and_invalid_names() {
+        assert!(is_valid_name("foo"));
+        assert!(is_valid_name("_a1"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("1x"));
+        assert!(!is_valid_name("a b"));
+        assert!(!is_valid_name("a-b"));
+    }
+
+    #[test]
+    fn invalid_name_message_for_operand() {
+        let operand = Field::dummy("1x=3");
+        let message = invalid_name_message("1x", &operand);
+        assert_eq!(message.title, "invalid variable name");
+        assert_eq!(message.annotations.len(), 1);
+        assert_eq!(
+            message.annotations[0].label,
+            "`1x` is not a valid variable name"
+        );
+        assert_eq!(*message.annotations[0].location, operand.origin);
+    }
+
     #[test]
     #[should_panic(expected = "a Frame::Builtin must be in the stack")]
     fn builtin_name_not_in_stack() {
@@ -0,0 +1,161 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Backslash escape sequences
+//!
+//! This module implements the escape sequences recognized by the echo and
+//! printf built-ins. The [`unescape`] function interprets escape sequences in
+//! a string and appends the resulting bytes to a buffer.
+//!
+//! The following escape sequences are recognized in both [styles](Style):
+//!
+//! - `\\`: backslash
+//! - `\a`: alert (bell)
+//! - `\b`: backspace
+//! - `\f`: form feed
+//! - `\n`: newline
+//! - `\r`: carriage return
+//! - `\t`: horizontal tab
+//! - `\v`: vertical tab
+//!
+//! A backslash followed by any other character is not an escape sequence; the
+//! backslash remains in the result.
+
+use std::ops::ControlFlow::{self, Break, Continue};
+
+/// Style of escape sequences
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Style {
+    /// Escape sequences in the format operand of the printf built-in
+    ///
+    /// In this style, `\ddd` is the byte of the one- to three-digit octal
+    /// number `ddd`.
+    Format,
+
+    /// Escape sequences in the operands of the echo built-in and the `%b`
+    /// conversion of the printf built-in
+    ///
+    /// In this style, `\0ddd` is the byte of the zero- to three-digit octal
+    /// number `ddd`, and `\c` terminates the output.
+    Operand,
+}
+
+/// Parses up to `max` octal digits at the beginning of the iterator.
+fn octal<I: Iterator<Item = char> + Clone>(chars: &mut I, max: usize) -> u8 {
+    let mut value: u32 = 0;
+    for _ in 0..max {
+        let mut lookahead = chars.clone();
+        match lookahead.next().and_then(|c| c.to_digit(8)) {
+            Some(digit) => {
+                value = value * 8 + digit;
+                *chars = lookahead;
+            }
+            None => break,
+        }
+    }
+    value as u8
+}
+
+/// Interprets escape sequences in the string.
+///
+/// The result is appended to `output`. If the string contains `\c` in the
+/// `Operand` style, this function stops processing there and returns `Break`.
+pub fn unescape(s: &str, style: Style, output: &mut Vec<u8>) -> ControlFlow<()> {
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buffer = [0; 4];
+            output.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+
+        let mut lookahead = chars.clone();
+        let byte = match lookahead.next() {
+            Some('\\') => b'\\',
+            Some('a') => b'\x07',
+            Some('b') => b'\x08',
+            Some('f') => b'\x0C',
+            Some('n') => b'\n',
+            Some('r') => b'\r',
+            Some('t') => b'\t',
+            Some('v') => b'\x0B',
+            Some('c') if style == Style::Operand => return Break(()),
+            Some('0') if style == Style::Operand => octal(&mut lookahead, 3),
+            Some('0'..='7') if style == Style::Format => {
+                lookahead = chars.clone();
+                octal(&mut lookahead, 3)
+            }
+            _ => {
+                output.push(b'\\');
+                continue;
+            }
+        };
+        output.push(byte);
+        chars = lookahead;
+    }
+    Continue(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unescaped(s: &str, style: Style) -> (Vec<u8>, ControlFlow<()>) {
+        let mut output = Vec::new();
+        let flow = unescape(s, style, &mut output);
+        (output, flow)
+    }
+
+    #[test]
+    fn no_escapes() {
+        let result = unescaped("foo bar", Style::Format);
+        assert_eq!(result, (b"foo bar".to_vec(), Continue(())));
+    }
+
+    #[test]
+    fn simple_escapes() {
+        for style in [Style::Format, Style::Operand] {
+            let result = unescaped(r"\\\a\b\f\n\r\t\v", style);
+            assert_eq!(result, (b"\\\x07\x08\x0C\n\r\t\x0B".to_vec(), Continue(())));
+        }
+    }
+
+    #[test]
+    fn unknown_escapes_are_kept() {
+        let result = unescaped(r"\x\c\", Style::Format);
+        assert_eq!(result, (br"\x\c\".to_vec(), Continue(())));
+        let result = unescaped(r"\x\1\", Style::Operand);
+        assert_eq!(result, (br"\x\1\".to_vec(), Continue(())));
+    }
+
+    #[test]
+    fn octal_escapes_in_format_style() {
+        let result = unescaped(r"\101\0\1234\08", Style::Format);
+        assert_eq!(result, (b"A\0S4\x008".to_vec(), Continue(())));
+    }
+
+    #[test]
+    fn octal_escapes_in_operand_style() {
+        let result = unescaped(r"\0101\0\01234\08", Style::Operand);
+        assert_eq!(result, (b"A\0S4\x008".to_vec(), Continue(())));
+    }
+
+    #[test]
+    fn stopping_at_backslash_c() {
+        let result = unescaped(r"foo\cbar", Style::Operand);
+        assert_eq!(result, (b"foo".to_vec(), Break(())));
+    }
+}
@@ -0,0 +1,136 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Job lookup for the fg and bg built-ins
+//!
+//! This module provides functions that find the job to be resumed by the
+//! [fg](crate::fg) and [bg](crate::bg) built-ins. On failure, the functions
+//! return a [`Message`] that can be printed with
+//! [`print_failure_message`](super::print_failure_message).
+
+use yash_env::job::id::parse;
+use yash_env::job::id::parse_tail;
+use yash_env::job::JobSet;
+use yash_env::semantics::Field;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+use yash_syntax::source::Location;
+
+/// Creates an error message for a job that cannot be resumed.
+pub fn resume_error_message(label: String, location: &Location) -> Message<'_> {
+    Message {
+        r#type: AnnotationType::Error,
+        title: "cannot resume job".into(),
+        annotations: vec![Annotation::new(
+            AnnotationType::Error,
+            label.into(),
+            location,
+        )],
+    }
+}
+
+/// Returns the index of the current job.
+///
+/// The `location` is used in the error message if there is no current job.
+pub fn current_job<'a>(jobs: &JobSet, location: &'a Location) -> Result<usize, Message<'a>> {
+    jobs.current_job()
+        .ok_or_else(|| resume_error_message("there is no job".to_string(), location))
+}
+
+/// Returns the index of the job specified by the job ID operand.
+///
+/// The leading `%` of the job ID may be omitted.
+pub fn find_job<'a>(jobs: &JobSet, operand: &'a Field) -> Result<usize, Message<'a>> {
+    let job_id = parse(&operand.value).unwrap_or_else(|_| parse_tail(&operand.value));
+    job_id.find(jobs).map_err(|error| {
+        let label = format!("{:?}: {}", operand.value, error);
+        resume_error_message(label, &operand.origin)
+    })
+}
+
+/// Checks if the job at the index is job-controlled.
+///
+/// The `location` is used in the error message if the job is not
+/// job-controlled.
+pub fn check_job_controlled<'a>(
+    jobs: &JobSet,
+    index: usize,
+    location: &'a Location,
+) -> Result<(), Message<'a>> {
+    let job = &jobs[index];
+    if job.job_controlled {
+        Ok(())
+    } else {
+        let label = format!("{:?}: not a job-controlled job", job.name);
+        Err(resume_error_message(label, location))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yash_env::job::Job;
+    use yash_env::job::Pid;
+
+    #[test]
+    fn current_job_not_found() {
+        let jobs = JobSet::default();
+        let location = Location::dummy("fg");
+        let message = current_job(&jobs, &location).unwrap_err();
+        assert_eq!(message.title, "cannot resume job");
+        assert_eq!(message.annotations[0].label, "there is no job");
+    }
+
+    #[test]
+    fn find_job_without_percent() {
+        let mut jobs = JobSet::default();
+        let mut job = Job::new(Pid::from_raw(10));
+        job.name = "sleep 1".to_string();
+        let index = jobs.add(job);
+
+        let operand = Field::dummy("sleep");
+        assert_eq!(find_job(&jobs, &operand).unwrap(), index);
+
+        let operand = Field::dummy("%sleep");
+        assert_eq!(find_job(&jobs, &operand).unwrap(), index);
+
+        let operand = Field::dummy("%foo");
+        let message = find_job(&jobs, &operand).unwrap_err();
+        assert_eq!(message.annotations[0].label, "\"%foo\": job not found");
+    }
+
+    #[test]
+    fn job_controlled_check() {
+        let mut jobs = JobSet::default();
+        let mut job = Job::new(Pid::from_raw(10));
+        job.name = "foo".to_string();
+        job.job_controlled = false;
+        let index = jobs.add(job);
+        let mut job = Job::new(Pid::from_raw(20));
+        job.job_controlled = true;
+        let index_2 = jobs.add(job);
+        let location = Location::dummy("fg");
+
+        let message = check_job_controlled(&jobs, index, &location).unwrap_err();
+        assert_eq!(
+            message.annotations[0].label,
+            "\"foo\": not a job-controlled job"
+        );
+
+        assert!(check_job_controlled(&jobs, index_2, &location).is_ok());
+    }
+}
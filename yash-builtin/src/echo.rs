@@ -0,0 +1,265 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Echo built-in
+//!
+//! The **`echo`** built-in prints its arguments.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! echo [argument...]
+//! ```
+//!
+//! # Semantics
+//!
+//! The echo built-in prints the arguments separated by a space and followed
+//! by a newline. How options and escape sequences in the arguments are
+//! handled depends on the [`Style`], which is selected by the `ECHO_STYLE`
+//! variable.
+//!
+//! # Options
+//!
+//! In the default XSI style, no options are recognized. All the arguments
+//! are treated as operands.
+//!
+//! In the BSD style, leading arguments that consist of a hyphen followed by
+//! one or more of the following letters are treated as options:
+//!
+//! - `n`: Do not print the trailing newline.
+//! - `e`: Interpret escape sequences in the operands.
+//! - `E`: Do not interpret escape sequences (default).
+//!
+//! If `e` and `E` are both specified, the last one wins. The `--` separator
+//! is not recognized.
+//!
+//! # Operands
+//!
+//! Operands are the strings to be printed. If escape sequences are
+//! interpreted, the following sequences are recognized:
+//!
+//! - `\\`: backslash
+//! - `\a`: alert (bell)
+//! - `\b`: backspace
+//! - `\c`: stops printing here (the trailing newline is not printed either)
+//! - `\f`: form feed
+//! - `\n`: newline
+//! - `\r`: carriage return
+//! - `\t`: horizontal tab
+//! - `\v`: vertical tab
+//! - `\0ddd`: byte of the zero- to three-digit octal number `ddd`
+//!
+//! # Exit status
+//!
+//! Zero unless there is an error writing to the standard output.
+//!
+//! # Errors
+//!
+//! None.
+//!
+//! # Portability
+//!
+//! The behavior of the echo built-in varies among shells. POSIX leaves the
+//! behavior unspecified if the first argument is `-n` or any argument
+//! contains a backslash. XSI-conformant systems require the XSI style. Use
+//! the [printf](crate::printf) built-in for portable output.
+//!
+//! # Implementation notes
+//!
+//! The `ECHO_STYLE` variable is examined each time the built-in runs. Its
+//! value is case-insensitive: `BSD` selects the BSD style, and any other
+//! value, including none, selects the XSI style.
+
+use crate::common::escape::unescape;
+use crate::common::escape::Style as EscapeStyle;
+use crate::common::Print;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::variable::Value::Scalar;
+use yash_env::Env;
+
+/// Name of the variable that selects the [`Style`]
+pub const ECHO_STYLE: &str = "ECHO_STYLE";
+
+/// Behavior of the echo built-in
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Style {
+    /// No options; escape sequences are always interpreted.
+    #[default]
+    Xsi,
+    /// The `-n`, `-e`, and `-E` options are recognized; escape sequences are
+    /// interpreted only if enabled by the `-e` option.
+    Bsd,
+}
+
+impl Style {
+    /// Returns the style selected by the `ECHO_STYLE` variable.
+    #[must_use]
+    pub fn from_env(env: &Env) -> Self {
+        match env.variables.get(ECHO_STYLE).and_then(|v| v.value.as_ref()) {
+            Some(Scalar(value)) if value.eq_ignore_ascii_case("bsd") => Style::Bsd,
+            _ => Style::Xsi,
+        }
+    }
+}
+
+/// Parsed options
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Options {
+    newline: bool,
+    escapes: bool,
+}
+
+/// Parses BSD-style options at the beginning of the arguments.
+///
+/// Returns the options and the number of arguments consumed.
+fn parse_bsd_options(args: &[Field]) -> (Options, usize) {
+    let mut options = Options {
+        newline: true,
+        escapes: false,
+    };
+    let mut count = 0;
+    for arg in args {
+        let Some(letters) = arg.value.strip_prefix('-') else {
+            break;
+        };
+        if letters.is_empty() || !letters.chars().all(|c| matches!(c, 'n' | 'e' | 'E')) {
+            break;
+        }
+        for c in letters.chars() {
+            match c {
+                'n' => options.newline = false,
+                'e' => options.escapes = true,
+                _ => options.escapes = false,
+            }
+        }
+        count += 1;
+    }
+    (options, count)
+}
+
+/// Entry point for executing the `echo` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (mut options, count) = match Style::from_env(env) {
+        Style::Xsi => (
+            Options {
+                newline: true,
+                escapes: true,
+            },
+            0,
+        ),
+        Style::Bsd => parse_bsd_options(&args),
+    };
+
+    let mut output = Vec::new();
+    for (i, arg) in args[count..].iter().enumerate() {
+        if i > 0 {
+            output.push(b' ');
+        }
+        if !options.escapes {
+            output.extend(arg.value.as_bytes());
+        } else if unescape(&arg.value, EscapeStyle::Operand, &mut output).is_break() {
+            options.newline = false;
+            break;
+        }
+    }
+    if options.newline {
+        output.push(b'\n');
+    }
+
+    env.print_bytes(&output).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stdout;
+    use crate::tests::assert_stdout_bytes;
+    use crate::tests::in_virtual_system;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::variable::Scope;
+    use yash_env::variable::Variable;
+
+    fn set_style(env: &mut Env, style: &str) {
+        env.variables
+            .assign(Scope::Global, ECHO_STYLE.to_string(), Variable::new(style))
+            .unwrap();
+    }
+
+    #[test]
+    fn no_arguments() {
+        in_virtual_system(|mut env, state| async move {
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "\n"));
+        })
+    }
+
+    #[test]
+    fn xsi_style() {
+        in_virtual_system(|mut env, state| async move {
+            let args = Field::dummies(["-n", "a\\tb", "\\0101\\c", "not printed"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "-n a\tb A"));
+        })
+    }
+
+    #[test]
+    fn non_utf8_bytes_are_printed_intact() {
+        in_virtual_system(|mut env, state| async move {
+            let args = Field::dummies(["\\0200\\0377"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout_bytes(&state, |stdout| assert_eq!(stdout, b"\x80\xFF\n"));
+        })
+    }
+
+    #[test]
+    fn bsd_style_without_options() {
+        in_virtual_system(|mut env, state| async move {
+            set_style(&mut env, "bsd");
+            let args = Field::dummies(["a\\tb", "-n"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "a\\tb -n\n"));
+        })
+    }
+
+    #[test]
+    fn bsd_style_with_options() {
+        in_virtual_system(|mut env, state| async move {
+            set_style(&mut env, "BSD");
+            let args = Field::dummies(["-n", "-eE", "-ne", "a\\tb", "-x"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "a\tb -x"));
+        })
+    }
+
+    #[test]
+    fn bsd_style_lone_hyphen_is_operand() {
+        in_virtual_system(|mut env, state| async move {
+            set_style(&mut env, "BSD");
+            let args = Field::dummies(["-", "-n"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "- -n\n"));
+        })
+    }
+}
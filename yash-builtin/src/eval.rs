@@ -0,0 +1,190 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Eval built-in
+//!
+//! The **`eval`** built-in evaluates the arguments as a shell command.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! eval [command...]
+//! ```
+//!
+//! # Semantics
+//!
+//! The eval built-in joins the operands with a space in between and parses
+//! the result as a shell script. The parsed commands are executed in the
+//! current shell environment.
+//!
+//! # Options
+//!
+//! None.
+//!
+//! (TODO: The `-i` option from yash 2 is not yet supported.)
+//!
+//! # Operands
+//!
+//! The operands are concatenated to form the command string to be executed.
+//!
+//! # Exit status
+//!
+//! The exit status of the eval built-in is that of the last command executed
+//! in the command string. If there are no operands or the command string
+//! contains no commands, the exit status is zero.
+//!
+//! # Errors
+//!
+//! A syntax error in the command string is reported with a location in the
+//! command string. Since the eval built-in is a special built-in, the error
+//! interrupts the shell if it is not interactive.
+//!
+//! # Portability
+//!
+//! POSIX does not require the eval built-in to conform to the Utility Syntax
+//! Guidelines, which means portable scripts cannot use any options or the `--`
+//! separator for the built-in.
+//!
+//! # Implementation notes
+//!
+//! The command string is parsed with a [`Lexer`] whose source is
+//! [`Source::Eval`]. While executing the command string, [`Frame::Eval`] is
+//! pushed to the stack.
+
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::stack::Frame;
+use yash_env::Env;
+use yash_semantics::ReadEvalLoop;
+use yash_syntax::parser::lex::Lexer;
+use yash_syntax::source::Source;
+
+/// Entry point for executing the `eval` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let Some(first) = args.first() else {
+        return Result::default();
+    };
+    let original = first.origin.clone();
+
+    let mut command = String::new();
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            command.push(' ');
+        }
+        command.push_str(&arg.value);
+    }
+
+    let mut lexer = Lexer::from_memory(&command, Source::Eval { original });
+    let mut env = env.push_frame(Frame::Eval);
+    let divert = ReadEvalLoop::new(&mut env, &mut lexer).run().await;
+    Result::with_exit_status_and_divert(env.exit_status, divert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use assert_matches::assert_matches;
+    use futures_util::FutureExt;
+    use std::ops::ControlFlow::Break;
+    use yash_env::semantics::Divert;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::variable::Value;
+
+    #[test]
+    fn no_operands() {
+        let mut env = Env::new_virtual();
+        env.exit_status = ExitStatus(42);
+        let result = main(&mut env, vec![]).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+    }
+
+    #[test]
+    fn empty_command_string() {
+        let mut env = Env::new_virtual();
+        env.exit_status = ExitStatus(42);
+        let args = Field::dummies(["", " "]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+    }
+
+    #[test]
+    fn operands_are_joined_with_space() {
+        let mut env = Env::new_virtual();
+        env.builtins.extend(crate::BUILTINS.iter().cloned());
+        let args = Field::dummies(["set", "a  b", "c"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+        assert_eq!(
+            env.variables.positional_params().value,
+            Some(Value::array(["a", "b", "c"]))
+        );
+    }
+
+    #[test]
+    fn exit_status_of_last_command() {
+        let mut env = Env::new_virtual();
+        env.builtins.extend(crate::BUILTINS.iter().cloned());
+        let args = Field::dummies(["return -n 3; return -n 7"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus(7)));
+    }
+
+    #[test]
+    fn commands_run_in_current_environment() {
+        let mut env = Env::new_virtual();
+        env.builtins.extend(crate::BUILTINS.iter().cloned());
+        let args = Field::dummies(["set foo bar"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+        assert_eq!(
+            env.variables.positional_params().value,
+            Some(Value::array(["foo", "bar"]))
+        );
+        assert_eq!(env.stack[..], []);
+    }
+
+    #[test]
+    fn divert_is_propagated() {
+        let mut env = Env::new_virtual();
+        env.builtins.extend(crate::BUILTINS.iter().cloned());
+        let args = Field::dummies(["return 5; echo not reached"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_matches!(result.divert(), Break(Divert::Return(Some(ExitStatus(5)))));
+    }
+
+    #[test]
+    fn syntax_error_points_to_eval_source() {
+        in_virtual_system(|mut env, state| async move {
+            let args = Field::dummies(["fi"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(
+                result.divert(),
+                Break(Divert::Interrupt(Some(ExitStatus::ERROR)))
+            );
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("<eval>:1:1"), "{stderr:?}");
+                assert!(
+                    stderr.contains("command passed to the eval built-in here"),
+                    "{stderr:?}"
+                );
+            });
+        })
+    }
+}
@@ -254,7 +254,7 @@ mod tests {
         let arguments = process.last_exec().as_ref().unwrap();
         assert_eq!(arguments.0, CString::new("/bin/echo").unwrap());
         assert_eq!(arguments.1, [CString::new("/bin/echo").unwrap()]);
-        assert_eq!(arguments.2, []);
+        assert_eq!(arguments.2, [] as [CString; 0]);
     }
 
     #[test]
@@ -0,0 +1,366 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Export built-in
+//!
+//! The **`export`** built-in exports variables to the environment.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! export [-p] [name[=value]...]
+//! ```
+//!
+//! # Semantics
+//!
+//! The export built-in (without the `-p` option) exports each of the specified
+//! variables. If a value is given to a name, the value is assigned to the
+//! variable before exporting. A name without a value exports the existing
+//! variable, or declares a new variable that has no value but will be exported
+//! once assigned a value.
+//!
+//! Exported variables are passed to external utilities as environment
+//! variables.
+//!
+//! # Options
+//!
+//! The **`-p`** (**`--print`**) option makes the built-in print the exported
+//! variables instead of exporting. If names are given, only the named variables
+//! are printed. Otherwise, all exported variables are printed.
+//!
+//! The output is a sequence of commands that would re-export the variables
+//! with the current values if executed. Values are quoted with
+//! [`yash_quote`].
+//!
+//! If there are no operands, the built-in behaves as if the `-p` option were
+//! specified.
+//!
+//! # Operands
+//!
+//! Operands are names of variables to be exported or printed. When exporting,
+//! a name may be followed by an equal sign and a value to be assigned.
+//!
+//! # Exit status
+//!
+//! Zero unless an error occurs.
+//!
+//! # Errors
+//!
+//! It is an error if an operand is not a valid variable name or assigns to a
+//! read-only variable. Since the export built-in is a special built-in, the
+//! error interrupts the shell if it is not interactive.
+//!
+//! # Portability
+//!
+//! The output format of `export -p` is unspecified in POSIX except that it
+//! must be re-inputtable. The `--print` form of the option is not portable.
+//! Exporting array variables is an extension to POSIX.
+
+use crate::common::invalid_name_message;
+use crate::common::is_valid_name;
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax::OptionSpec;
+use crate::common::Print;
+use std::fmt::Write;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::variable::ReadOnlyError;
+use yash_env::variable::Scope::Global;
+use yash_env::variable::Value::{Array, Scalar};
+use yash_env::variable::Variable;
+use yash_env::Env;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+
+const OPTION_SPECS: &[OptionSpec] = &[OptionSpec::new().short('p').long("print")];
+
+/// Appends a command that re-exports the variable to the output.
+fn print_variable(output: &mut String, name: &str, var: &Variable) {
+    match &var.value {
+        Some(value @ Scalar(_)) => writeln!(output, "export {}={}", name, value.quote()),
+        Some(value @ Array(_)) => {
+            writeln!(output, "{}={}\nexport {}", name, value.quote(), name)
+        }
+        None => writeln!(output, "export {name}"),
+    }
+    .unwrap()
+}
+
+/// Prints the exported variables.
+///
+/// If `names` is empty, all exported variables are printed.
+async fn print_variables(env: &mut Env, names: &[Field]) -> Result {
+    let mut output = String::new();
+    if names.is_empty() {
+        let mut vars: Vec<_> = env
+            .variables
+            .iter(Global)
+            .filter(|(_, var)| var.is_exported)
+            .collect();
+        // TODO apply current locale's collation
+        vars.sort_unstable_by_key(|&(name, _)| name);
+        for (name, var) in vars {
+            print_variable(&mut output, name, var);
+        }
+    } else {
+        for name in names {
+            if let Some(var) = env.variables.get(&name.value) {
+                if var.is_exported {
+                    print_variable(&mut output, &name.value, var);
+                }
+            }
+        }
+    }
+    env.print(&output).await
+}
+
+/// Converts the read-only error into a message.
+fn read_only_error_message<'a>(error: &'a ReadOnlyError, operand: &'a Field) -> Message<'a> {
+    Message {
+        r#type: AnnotationType::Error,
+        title: format!("cannot assign to read-only variable `{}`", error.name).into(),
+        annotations: vec![
+            Annotation::new(
+                AnnotationType::Error,
+                error.to_string().into(),
+                &operand.origin,
+            ),
+            Annotation::new(
+                AnnotationType::Info,
+                "the variable was made read-only here".into(),
+                &error.read_only_location,
+            ),
+        ],
+    }
+}
+
+/// Entry point for executing the `export` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (options, operands) = match parse_arguments(OPTION_SPECS, Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+
+    if !options.is_empty() || operands.is_empty() {
+        return print_variables(env, &operands).await;
+    }
+
+    let mut result = Result::default();
+    for operand in &operands {
+        let (name, value) = match operand.value.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (operand.value.as_str(), None),
+        };
+        if !is_valid_name(name) {
+            let message = invalid_name_message(name, operand);
+            result = print_failure_message(env, message).await;
+            continue;
+        }
+
+        let var = match value {
+            Some(value) => Variable::new(value).set_assigned_location(operand.origin.clone()),
+            None => match env.variables.get_mut(name) {
+                Some(var) => {
+                    var.is_exported = true;
+                    continue;
+                }
+                None => Variable::default(),
+            },
+        };
+        if let Err(error) = env.assign_variable(Global, name.to_owned(), var.export()) {
+            let message = read_only_error_message(&error, operand);
+            result = print_failure_message(env, message).await;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use std::ffi::CString;
+    use std::ops::ControlFlow::Break;
+    use yash_env::semantics::Divert;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
+    use yash_env::variable::Value;
+    use yash_syntax::source::Location;
+
+    #[test]
+    fn exporting_with_value() {
+        let mut env = Env::new_virtual();
+        let args = Field::dummies(["foo=bar baz"]);
+        let location = args[0].origin.clone();
+
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+
+        let v = env.variables.get("foo").unwrap();
+        assert_eq!(v.value, Some(Value::scalar("bar baz")));
+        assert!(v.is_exported);
+        assert_eq!(v.last_assigned_location, Some(location));
+    }
+
+    #[test]
+    fn exporting_existing_variable() {
+        let mut env = Env::new_virtual();
+        let var = Variable::new("value").make_read_only(Location::dummy("readonly"));
+        env.variables
+            .assign(Global, "foo".to_string(), var.clone())
+            .unwrap();
+
+        let args = Field::dummies(["foo"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(env.variables.get("foo"), Some(&var.export()));
+    }
+
+    #[test]
+    fn exporting_new_variable_without_value() {
+        let mut env = Env::new_virtual();
+        let args = Field::dummies(["foo"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(
+            env.variables.get("foo"),
+            Some(&Variable::default().export())
+        );
+        assert!(env.variables.env_c_strings().is_empty());
+    }
+
+    #[test]
+    fn assigning_to_read_only_variable() {
+        in_virtual_system(|mut env, state| async move {
+            let var = Variable::new("value").make_read_only(Location::dummy("readonly"));
+            env.variables
+                .assign(Global, "foo".to_string(), var.clone())
+                .unwrap();
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("export"),
+                is_special: true,
+            });
+
+            let args = Field::dummies(["foo=new", "bar=baz"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_eq!(result.divert(), Break(Divert::Interrupt(None)));
+            assert_eq!(env.variables.get("foo"), Some(&var));
+            assert!(env.variables.get("bar").unwrap().is_exported);
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("read-only"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn exporting_path_clears_path_cache() {
+        let mut env = Env::new_virtual();
+        let ls = CString::new("/bin/ls").unwrap();
+        env.path_cache.insert("ls".to_string(), ls);
+
+        let args = Field::dummies(["PATH=/bin"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(env.path_cache.get("ls"), None);
+    }
+
+    #[test]
+    fn rejecting_invalid_names() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("export"),
+                is_special: true,
+            });
+
+            let args = Field::dummies(["=x", "1=x", "a b=c", "ok=1"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_eq!(result.divert(), Break(Divert::Interrupt(None)));
+            assert_eq!(env.variables.get(""), None);
+            assert_eq!(env.variables.get("1"), None);
+            assert_eq!(env.variables.get("a b"), None);
+            assert!(env.variables.get("ok").unwrap().is_exported);
+            assert_stderr(&state, |stderr| {
+                assert_eq!(stderr.matches("invalid variable name").count(), 3);
+                assert!(stderr.contains("`a b`"), "{stderr:?}");
+            });
+        })
+    }
+
+    #[test]
+    fn printing_all_exported_variables() {
+        in_virtual_system(|mut env, state| async move {
+            env.variables
+                .assign(Global, "foo".to_string(), Variable::new("1").export())
+                .unwrap();
+            env.variables
+                .assign(Global, "bar".to_string(), Variable::new("it's").export())
+                .unwrap();
+            env.variables
+                .assign(Global, "baz".to_string(), Variable::new("not exported"))
+                .unwrap();
+            env.variables
+                .assign(Global, "qux".to_string(), Variable::default().export())
+                .unwrap();
+            env.variables
+                .assign(
+                    Global,
+                    "a".to_string(),
+                    Variable::new_array(["1", "2"]).export(),
+                )
+                .unwrap();
+
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| {
+                assert_eq!(
+                    stdout,
+                    "a=(1 2)\nexport a\nexport bar=\"it's\"\nexport foo=1\nexport qux\n"
+                )
+            });
+        })
+    }
+
+    #[test]
+    fn printing_named_variables() {
+        in_virtual_system(|mut env, state| async move {
+            env.variables
+                .assign(Global, "foo".to_string(), Variable::new("1").export())
+                .unwrap();
+            env.variables
+                .assign(Global, "bar".to_string(), Variable::new("2").export())
+                .unwrap();
+            env.variables
+                .assign(Global, "baz".to_string(), Variable::new("3"))
+                .unwrap();
+
+            let args = Field::dummies(["-p", "foo", "baz", "none"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "export foo=1\n"));
+        })
+    }
+}
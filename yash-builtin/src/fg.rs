@@ -0,0 +1,309 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Fg built-in
+//!
+//! The **`fg`** built-in resumes a suspended job in the foreground.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! fg [job_id]
+//! ```
+//!
+//! # Semantics
+//!
+//! The built-in brings the specified job to the foreground and resumes its
+//! execution by sending the `SIGCONT` signal to it. The built-in then waits
+//! for the job to finish (or suspend again).
+//!
+//! The name of the job is printed to the standard output before the job is
+//! resumed.
+//!
+//! If the resumed job finishes, it is removed from the job list. If the job
+//! gets suspended again, it is set as the current job.
+//!
+//! # Options
+//!
+//! None.
+//!
+//! # Operands
+//!
+//! Operand *job_id* specifies which job to resume. See the module
+//! documentation of [`yash_env::job::id`] for the format of job IDs. If
+//! omitted, the built-in resumes the [current
+//! job](yash_env::job::JobSet::current_job).
+//!
+//! # Exit status
+//!
+//! The built-in returns the exit status of the resumed job. If the job is
+//! suspended again, the exit status is that of the signal that suspended the
+//! job.
+//!
+//! If the built-in fails to resume the job, the exit status is non-zero.
+//!
+//! # Errors
+//!
+//! This built-in fails if the specified job is not found or is not
+//! job-controlled, that is, not running in its own process group.
+//!
+//! # Portability
+//!
+//! Many implementations allow omitting the leading `%` from job IDs, though
+//! it is not required by POSIX.
+//!
+//! # Implementation notes
+//!
+//! The built-in uses [`tcsetpgrp_with_block`](SystemEx::tcsetpgrp_with_block)
+//! to pass the control of the terminal to the job and to take it back after
+//! the job is finished or suspended.
+
+use crate::common::job::check_job_controlled;
+use crate::common::job::current_job;
+use crate::common::job::find_job;
+use crate::common::job::resume_error_message;
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax_error;
+use crate::common::BuiltinEnv;
+use crate::common::Print;
+use yash_env::builtin::Result;
+use yash_env::job::Pid;
+use yash_env::job::WaitStatus;
+use yash_env::job::WaitStatusEx;
+use yash_env::semantics::ExitStatus;
+use yash_env::semantics::Field;
+use yash_env::system::Errno;
+use yash_env::system::SystemEx;
+use yash_env::trap::Signal;
+use yash_env::Env;
+use yash_env::System;
+
+/// Resumes the job at the specified index in the foreground.
+///
+/// This function sends `SIGCONT` to the job and waits for the job to finish or
+/// suspend. The job must be job-controlled. If the job has already finished,
+/// this function does not send the signal.
+///
+/// Returns the exit status of the job. If the job has finished, it is removed
+/// from the job list.
+async fn resume_job_by_index(
+    env: &mut Env,
+    index: usize,
+) -> std::result::Result<ExitStatus, Errno> {
+    let job = &env.jobs[index];
+    let pgid = job.pid;
+
+    if !job.status.is_finished() {
+        let tty = env.get_tty().ok();
+        if let Some(tty) = tty {
+            env.system.tcsetpgrp_with_block(tty, pgid)?;
+        }
+
+        let result = resume_and_wait(env, pgid).await;
+
+        if let Some(tty) = tty {
+            env.system.tcsetpgrp_with_block(tty, env.main_pgid).ok();
+        }
+        result?;
+    }
+
+    let status = env.jobs[index].status;
+    if status.is_finished() {
+        env.jobs.remove(index);
+    }
+    Ok(status.try_into().unwrap_or_default())
+}
+
+/// Sends `SIGCONT` to the process group and waits for it to finish or suspend.
+async fn resume_and_wait(env: &mut Env, pgid: Pid) -> std::result::Result<(), Errno> {
+    env.system
+        .kill(Pid::from_raw(-pgid.as_raw()), Some(Signal::SIGCONT))?;
+    loop {
+        let status = env.wait_for_subshell(pgid).await?;
+        if status.is_finished() || matches!(status, WaitStatus::Stopped(..)) {
+            return Ok(());
+        }
+    }
+}
+
+/// Entry point for executing the `fg` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (_options, operands) = match parse_arguments(&[], Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+
+    let (index, location) = match operands.as_slice() {
+        [] => {
+            let location = env.builtin_name().origin.clone();
+            let index = match current_job(&env.jobs, &location) {
+                Ok(index) => index,
+                Err(message) => return print_failure_message(env, message).await,
+            };
+            (index, location)
+        }
+        [operand] => match find_job(&env.jobs, operand) {
+            Ok(index) => (index, operand.origin.clone()),
+            Err(message) => return print_failure_message(env, message).await,
+        },
+        [_, operand, ..] => {
+            return syntax_error(env, "too many operands", &operand.origin).await;
+        }
+    };
+
+    if let Err(message) = check_job_controlled(&env.jobs, index, &location) {
+        return print_failure_message(env, message).await;
+    }
+
+    let job = &env.jobs[index];
+    let line = format!("{}\n", job.name);
+    let result = env.print(&line).await;
+    if !result.exit_status().is_successful() {
+        return result;
+    }
+
+    match resume_job_by_index(env, index).await {
+        Ok(exit_status) => Result::new(exit_status),
+        Err(errno) => {
+            let label = errno.desc().to_string();
+            print_failure_message(env, resume_error_message(label, &location)).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use crate::tests::stub_tty;
+    use crate::tests::suspended_job;
+    use yash_env::option::Option::Monitor;
+    use yash_env::option::State::On;
+    use yash_env::stack::Frame;
+
+    #[test]
+    fn resuming_current_job() {
+        in_virtual_system(|mut env, state| async move {
+            env.options.set(Monitor, On);
+            stub_tty(&state);
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("fg"),
+                is_special: false,
+            });
+            let index = suspended_job(&mut env, "my job", ExitStatus(42)).await;
+            assert_eq!(env.jobs.current_job(), Some(index));
+
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::new(ExitStatus(42)));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "my job\n"));
+            assert_eq!(env.jobs.get(index), None);
+        })
+    }
+
+    #[test]
+    fn resuming_job_by_operand() {
+        in_virtual_system(|mut env, state| async move {
+            env.options.set(Monitor, On);
+            stub_tty(&state);
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("fg"),
+                is_special: false,
+            });
+            let first = suspended_job(&mut env, "first", ExitStatus(1)).await;
+            let second = suspended_job(&mut env, "second", ExitStatus(2)).await;
+
+            let result = main(&mut env, Field::dummies(["%first"])).await;
+            assert_eq!(result, Result::new(ExitStatus(1)));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "first\n"));
+            assert_eq!(env.jobs.get(first), None);
+            assert_eq!(env.jobs.current_job(), Some(second));
+        })
+    }
+
+    #[test]
+    fn terminal_is_returned_to_shell() {
+        in_virtual_system(|mut env, state| async move {
+            env.options.set(Monitor, On);
+            stub_tty(&state);
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("fg"),
+                is_special: false,
+            });
+            suspended_job(&mut env, "job", ExitStatus(0)).await;
+
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_eq!(state.borrow().foreground, Some(env.main_pgid));
+        })
+    }
+
+    #[test]
+    fn no_job() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("fg"),
+                is_special: false,
+            });
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("no job"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn job_not_found() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("fg"),
+                is_special: false,
+            });
+            let result = main(&mut env, Field::dummies(["%foo"])).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("%foo"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn job_not_job_controlled() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("fg"),
+                is_special: false,
+            });
+            let mut job = yash_env::job::Job::new(Pid::from_raw(123));
+            job.name = "foo".to_string();
+            env.jobs.add(job);
+
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_stdout(&state, |stdout| assert_eq!(stdout, ""));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("job-controlled"), "{stderr:?}")
+            });
+        })
+    }
+}
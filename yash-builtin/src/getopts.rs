@@ -0,0 +1,478 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Getopts built-in
+//!
+//! The **`getopts`** built-in parses command line options.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! getopts option_string variable_name [argument…]
+//! ```
+//!
+//! # Semantics
+//!
+//! The getopts built-in parses one option in the *argument*s each time it is
+//! invoked. It is typically used in a `while` loop to process all the options
+//! given to a script or function:
+//!
+//! ```sh
+//! while getopts ab:c opt; do
+//!     case $opt in
+//!         (a) a_flag=true ;;
+//!         (b) b_value=$OPTARG ;;
+//!         (c) c_flag=true ;;
+//!         (*) exit 2 ;;
+//!     esac
+//! done
+//! shift $((OPTIND - 1))
+//! ```
+//!
+//! If an option is found, the built-in assigns the option character to the
+//! variable named *variable_name*. If the option takes an argument, the
+//! argument is assigned to the `OPTARG` variable; otherwise, `OPTARG` is
+//! unset.
+//!
+//! Options can be grouped in one argument as in `-abc`. An option argument
+//! can be given in the same argument as the option as in `-bvalue` or in the
+//! next argument as in `-b value`. Parsing stops at the first argument that
+//! does not start with `-`, an argument consisting of a single `-`, or the
+//! `--` separator.
+//!
+//! The position of the next option to parse is kept in the `OPTIND` variable.
+//! It is the index of the next argument to parse, where `1` is the first
+//! argument. When the built-in has parsed an option in the middle of a group
+//! like `-abc`, the value has the form of `index:offset` to remember how many
+//! options in the group have been parsed. When all the options have been
+//! parsed, `OPTIND` is the index of the first operand. To start parsing a new
+//! set of arguments, assign `1` to `OPTIND`.
+//!
+//! # Options
+//!
+//! None.
+//!
+//! # Operands
+//!
+//! The *option_string* operand specifies the valid option characters. An
+//! option character followed by a colon (`:`) takes an argument. If the
+//! option string starts with a colon, the built-in works in the silent mode
+//! as described below.
+//!
+//! The *variable_name* operand is the name of the variable to which the
+//! parsed option is assigned.
+//!
+//! The *argument*s are the arguments to parse. If there are no *argument*s,
+//! the positional parameters are parsed instead. In a function, those are
+//! the positional parameters of the function.
+//!
+//! # Errors
+//!
+//! If an option not contained in the option string is found, `?` is assigned
+//! to the variable. In the silent mode, the option character is assigned to
+//! `OPTARG`. Otherwise, `OPTARG` is unset and an error message is printed.
+//!
+//! If an option that takes an argument is found but the argument is missing,
+//! the variable and `OPTARG` are assigned `:` and the option character,
+//! respectively, in the silent mode. Otherwise, `?` is assigned to the
+//! variable, `OPTARG` is unset, and an error message is printed.
+//!
+//! These errors do not affect the exit status.
+//!
+//! It is an error if the *variable_name* is not a valid variable name, the
+//! value of `OPTIND` is not valid, or a variable to be assigned is read-only.
+//!
+//! # Exit status
+//!
+//! Zero if an option is found (even if it is invalid), one if there are no
+//! more options, and two on error.
+//!
+//! # Portability
+//!
+//! POSIX requires the value of `OPTIND` to be an integer. The `index:offset`
+//! form is specific to this implementation. Other shells remember the offset
+//! in an internal state hidden from the user.
+//!
+//! The behavior is unspecified if `OPTIND` is modified to a value other than
+//! `1` or the arguments are changed between invocations.
+
+use crate::common::invalid_name_message;
+use crate::common::is_valid_name;
+use crate::common::print_error_message;
+use crate::common::print_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax_error;
+use crate::common::BuiltinEnv;
+use std::borrow::Cow;
+use thiserror::Error;
+use yash_env::builtin::Result;
+use yash_env::semantics::ExitStatus;
+use yash_env::semantics::Field;
+use yash_env::variable::ReadOnlyError;
+use yash_env::variable::Scope::Global;
+use yash_env::variable::UnsetError;
+use yash_env::variable::Value::{Array, Scalar};
+use yash_env::variable::Variable;
+use yash_env::Env;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+use yash_syntax::source::pretty::MessageBase;
+use yash_syntax::source::Location;
+
+pub mod model;
+
+use model::Outcome;
+use model::Position;
+
+/// Name of the variable that keeps the parsing position
+pub const OPTIND: &str = "OPTIND";
+
+/// Name of the variable to which option arguments are assigned
+pub const OPTARG: &str = "OPTARG";
+
+/// Error in the getopts built-in
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum Error {
+    /// The value of `$OPTIND` is not a valid position.
+    #[error("invalid value of OPTIND")]
+    InvalidPosition {
+        /// Value of the variable
+        value: String,
+        /// Location of the built-in invocation
+        location: Location,
+    },
+
+    /// A variable to be assigned is read-only.
+    #[error("cannot assign to read-only variable `{}`", .0.name)]
+    ReadOnly(Box<ReadOnlyError>),
+
+    /// `$OPTARG` cannot be unset because it is read-only.
+    #[error("cannot unset read-only variable `{}`", .0.name)]
+    Unset(UnsetError, Location),
+}
+
+impl MessageBase for Error {
+    fn message_title(&self) -> Cow<str> {
+        self.to_string().into()
+    }
+
+    fn main_annotation(&self) -> Annotation<'_> {
+        match self {
+            Error::InvalidPosition { value, location } => Annotation::new(
+                AnnotationType::Error,
+                format!("{value:?} is not a valid position").into(),
+                location,
+            ),
+            Error::ReadOnly(error) => Annotation::new(
+                AnnotationType::Error,
+                error.to_string().into(),
+                error.new_value.last_assigned_location.as_ref().unwrap(),
+            ),
+            Error::Unset(error, location) => {
+                Annotation::new(AnnotationType::Error, error.to_string().into(), location)
+            }
+        }
+    }
+
+    fn additional_annotations<'a, T: Extend<Annotation<'a>>>(&'a self, results: &mut T) {
+        let read_only_location = match self {
+            Error::InvalidPosition { .. } => return,
+            Error::ReadOnly(error) => &error.read_only_location,
+            Error::Unset(error, _) => &error.read_only_location,
+        };
+        results.extend(std::iter::once(Annotation::new(
+            AnnotationType::Info,
+            "the variable was made read-only here".into(),
+            read_only_location,
+        )));
+    }
+}
+
+fn assign(
+    env: &mut Env,
+    name: &str,
+    value: String,
+    location: &Location,
+) -> std::result::Result<(), Error> {
+    let variable = Variable::new(value).set_assigned_location(location.clone());
+    match env.assign_variable(Global, name.to_owned(), variable) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(Error::ReadOnly(Box::new(error))),
+    }
+}
+
+fn unset_optarg(env: &mut Env, location: &Location) -> std::result::Result<(), Error> {
+    match env.variables.unset(Global, OPTARG) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(Error::Unset(error, location.clone())),
+    }
+}
+
+/// Returns the current position stored in `$OPTIND`.
+///
+/// If the variable is not set, the initial position is returned.
+fn current_position(env: &Env, location: &Location) -> std::result::Result<Position, Error> {
+    match env.variables.get(OPTIND) {
+        Some(Variable {
+            value: Some(Scalar(value)),
+            ..
+        }) => value.parse().map_err(|_| Error::InvalidPosition {
+            value: value.clone(),
+            location: location.clone(),
+        }),
+        _ => Ok(Position::default()),
+    }
+}
+
+fn option_error_message<'a>(label: String, option_string: &'a Field) -> Message<'a> {
+    Message {
+        r#type: AnnotationType::Error,
+        title: "invalid command line option".into(),
+        annotations: vec![Annotation::new(
+            AnnotationType::Error,
+            label.into(),
+            &option_string.origin,
+        )],
+    }
+}
+
+/// Entry point for executing the `getopts` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let mut operands = match parse_arguments(&[], Mode::with_env(env), args) {
+        Ok((_options, operands)) => operands,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+    if operands.len() < 2 {
+        let location = env.builtin_name().origin.clone();
+        let label = "the option string and variable name are required";
+        return syntax_error(env, label, &location).await;
+    }
+    let arguments = operands.split_off(2);
+    let name = operands.pop().unwrap();
+    let option_string = operands.pop().unwrap();
+    if !is_valid_name(&name.value) {
+        let message = invalid_name_message(&name.value, &name);
+        return print_error_message(env, message).await;
+    }
+
+    let arguments: Vec<String> = if arguments.is_empty() {
+        match &env.variables.positional_params().value {
+            Some(Array(values)) => values.clone(),
+            _ => Vec::new(),
+        }
+    } else {
+        arguments.into_iter().map(|field| field.value).collect()
+    };
+
+    let location = env.builtin_name().origin.clone();
+    let position = match current_position(env, &location) {
+        Ok(position) => position,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+    let (outcome, next_position) = model::next(&option_string.value, &arguments, position);
+    let silent = option_string.value.starts_with(':');
+
+    let (result, option, argument) = match outcome {
+        Outcome::Option { option, argument } => (Result::default(), option, argument),
+        Outcome::UnknownOption(option) if silent => {
+            (Result::default(), '?', Some(option.to_string()))
+        }
+        Outcome::UnknownOption(option) => {
+            let label = format!("-{option}: not in the option string");
+            let message = option_error_message(label, &option_string);
+            let divert = print_message(env, message).await;
+            let result = Result::with_exit_status_and_divert(ExitStatus::SUCCESS, divert);
+            (result, '?', None)
+        }
+        Outcome::MissingArgument(option) if silent => {
+            (Result::default(), ':', Some(option.to_string()))
+        }
+        Outcome::MissingArgument(option) => {
+            let label = format!("-{option}: the option requires an argument");
+            let message = option_error_message(label, &option_string);
+            let divert = print_message(env, message).await;
+            let result = Result::with_exit_status_and_divert(ExitStatus::SUCCESS, divert);
+            (result, '?', None)
+        }
+        Outcome::End => (Result::new(ExitStatus::FAILURE), '?', None),
+    };
+
+    let assignments = assign(env, &name.value, option.to_string(), &location)
+        .and_then(|()| match argument {
+            Some(argument) => assign(env, OPTARG, argument, &location),
+            None => unset_optarg(env, &location),
+        })
+        .and_then(|()| assign(env, OPTIND, next_position.to_string(), &location));
+    match assignments {
+        Ok(()) => result,
+        Err(error) => print_error_message(env, &error).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use yash_env::stack::Frame;
+    use yash_env::variable::Value;
+
+    fn value_of(env: &Env, name: &str) -> Option<Value> {
+        env.variables.get(name).and_then(|v| v.value.clone())
+    }
+
+    fn getopts(env: &mut Env, args: &[&str]) -> Result {
+        let mut env = env.push_frame(Frame::Builtin {
+            name: Field::dummy("getopts"),
+            is_special: false,
+        });
+        main(&mut env, Field::dummies(args.iter().copied()))
+            .now_or_never()
+            .unwrap()
+    }
+
+    #[test]
+    fn parsing_operands_until_end() {
+        let mut env = Env::new_virtual();
+        let args = ["ab:", "opt", "-a", "-bfoo", "operand"];
+
+        let result = getopts(&mut env, &args);
+        assert_eq!(result, Result::default());
+        assert_eq!(value_of(&env, "opt"), Some(Value::scalar("a")));
+        assert_eq!(value_of(&env, OPTARG), None);
+        assert_eq!(value_of(&env, OPTIND), Some(Value::scalar("2")));
+
+        let result = getopts(&mut env, &args);
+        assert_eq!(result, Result::default());
+        assert_eq!(value_of(&env, "opt"), Some(Value::scalar("b")));
+        assert_eq!(value_of(&env, OPTARG), Some(Value::scalar("foo")));
+        assert_eq!(value_of(&env, OPTIND), Some(Value::scalar("3")));
+
+        let result = getopts(&mut env, &args);
+        assert_eq!(result, Result::new(ExitStatus::FAILURE));
+        assert_eq!(value_of(&env, "opt"), Some(Value::scalar("?")));
+        assert_eq!(value_of(&env, OPTARG), None);
+        assert_eq!(value_of(&env, OPTIND), Some(Value::scalar("3")));
+    }
+
+    #[test]
+    fn parsing_grouped_options_in_positional_parameters() {
+        let mut env = Env::new_virtual();
+        env.variables.positional_params_mut().value = Some(Value::array(["-xy", "-z"]));
+
+        let result = getopts(&mut env, &["xyz", "opt"]);
+        assert_eq!(result, Result::default());
+        assert_eq!(value_of(&env, "opt"), Some(Value::scalar("x")));
+        assert_eq!(value_of(&env, OPTIND), Some(Value::scalar("1:1")));
+
+        let result = getopts(&mut env, &["xyz", "opt"]);
+        assert_eq!(result, Result::default());
+        assert_eq!(value_of(&env, "opt"), Some(Value::scalar("y")));
+        assert_eq!(value_of(&env, OPTIND), Some(Value::scalar("2")));
+
+        let result = getopts(&mut env, &["xyz", "opt"]);
+        assert_eq!(result, Result::default());
+        assert_eq!(value_of(&env, "opt"), Some(Value::scalar("z")));
+        assert_eq!(value_of(&env, OPTIND), Some(Value::scalar("3")));
+    }
+
+    #[test]
+    fn unknown_option_in_silent_mode() {
+        let mut env = Env::new_virtual();
+        let result = getopts(&mut env, &[":a", "opt", "-x"]);
+        assert_eq!(result, Result::default());
+        assert_eq!(value_of(&env, "opt"), Some(Value::scalar("?")));
+        assert_eq!(value_of(&env, OPTARG), Some(Value::scalar("x")));
+    }
+
+    #[test]
+    fn unknown_option_in_normal_mode() {
+        in_virtual_system(|mut env, state| async move {
+            let result = getopts(&mut env, &["a", "opt", "-x"]);
+            assert_eq!(result, Result::default());
+            assert_eq!(value_of(&env, "opt"), Some(Value::scalar("?")));
+            assert_eq!(value_of(&env, OPTARG), None);
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("-x"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn missing_argument_in_silent_mode() {
+        let mut env = Env::new_virtual();
+        let result = getopts(&mut env, &[":a:", "opt", "-a"]);
+        assert_eq!(result, Result::default());
+        assert_eq!(value_of(&env, "opt"), Some(Value::scalar(":")));
+        assert_eq!(value_of(&env, OPTARG), Some(Value::scalar("a")));
+    }
+
+    #[test]
+    fn missing_argument_in_normal_mode() {
+        in_virtual_system(|mut env, state| async move {
+            let result = getopts(&mut env, &["a:", "opt", "-a"]);
+            assert_eq!(result, Result::default());
+            assert_eq!(value_of(&env, "opt"), Some(Value::scalar("?")));
+            assert_eq!(value_of(&env, OPTARG), None);
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("-a"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn invalid_optind() {
+        in_virtual_system(|mut env, state| async move {
+            env.variables
+                .assign(Global, OPTIND.to_string(), Variable::new("foo"))
+                .unwrap();
+            let result = getopts(&mut env, &["a", "opt", "-a"]);
+            assert_eq!(result.exit_status(), ExitStatus::ERROR);
+            assert_eq!(value_of(&env, "opt"), None);
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("OPTIND"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn invalid_variable_name() {
+        in_virtual_system(|mut env, state| async move {
+            for name in ["1x", "a b", ""] {
+                let result = getopts(&mut env, &["ab", name, "-a"]);
+                assert_eq!(result.exit_status(), ExitStatus::ERROR);
+                assert_eq!(value_of(&env, name), None);
+            }
+            assert_eq!(value_of(&env, OPTIND), None);
+            assert_stderr(&state, |stderr| {
+                assert_eq!(stderr.matches("invalid variable name").count(), 3);
+            });
+        })
+    }
+
+    #[test]
+    fn missing_operands() {
+        in_virtual_system(|mut env, _state| async move {
+            let result = getopts(&mut env, &["a"]);
+            assert_eq!(result.exit_status(), ExitStatus::ERROR);
+        })
+    }
+}
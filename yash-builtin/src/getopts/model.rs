@@ -0,0 +1,344 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Core of the option parsing performed by the getopts built-in
+//!
+//! This module is independent of the shell environment. The [`next`] function
+//! examines the arguments at the given [`Position`] and returns the
+//! [`Outcome`] and the position from which the next invocation should
+//! continue.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Position of the next option to parse
+///
+/// The position is saved in the `$OPTIND` variable between invocations of the
+/// getopts built-in. An `index` refers to an argument, where `1` is the first
+/// argument. An `offset` is the number of option characters already parsed in
+/// the argument, which is zero unless the previous invocation parsed an option
+/// in the middle of a group of options like `-abc`.
+///
+/// The string representation of a position is `index` if the `offset` is zero
+/// and `index:offset` otherwise.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Position {
+    /// Index of the argument (starting from 1)
+    pub index: usize,
+    /// Number of option characters already parsed in the argument
+    pub offset: usize,
+}
+
+impl Default for Position {
+    /// Returns the initial position, that is, the start of the first argument.
+    fn default() -> Self {
+        Position {
+            index: 1,
+            offset: 0,
+        }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.offset == 0 {
+            self.index.fmt(f)
+        } else {
+            write!(f, "{}:{}", self.index, self.offset)
+        }
+    }
+}
+
+/// Error in parsing a position
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ParsePositionError;
+
+impl FromStr for Position {
+    type Err = ParsePositionError;
+
+    /// Parses a position in the form of `index` or `index:offset`.
+    ///
+    /// The index must be positive.
+    fn from_str(s: &str) -> Result<Self, ParsePositionError> {
+        let (index, offset) = match s.split_once(':') {
+            Some((index, offset)) => (index, offset.parse().map_err(|_| ParsePositionError)?),
+            None => (s, 0),
+        };
+        match index.parse() {
+            Ok(index) if index > 0 => Ok(Position { index, offset }),
+            _ => Err(ParsePositionError),
+        }
+    }
+}
+
+/// Result of parsing an option
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Outcome {
+    /// A valid option was found.
+    Option {
+        /// Option character
+        option: char,
+        /// Option argument, if the option takes one
+        argument: Option<String>,
+    },
+    /// An option not contained in the option string was found.
+    UnknownOption(char),
+    /// An option that takes an argument was found, but the argument is missing.
+    MissingArgument(char),
+    /// There are no more options to parse.
+    End,
+}
+
+/// Returns whether the option character is in the option string and, if so,
+/// whether the option takes an argument.
+fn find_option(option_string: &str, option: char) -> Option<bool> {
+    if option == ':' {
+        return None;
+    }
+    let i = option_string.find(option)?;
+    let rest = &option_string[i + option.len_utf8()..];
+    Some(rest.starts_with(':'))
+}
+
+/// Parses the option at the given position.
+///
+/// The `option_string` specifies the valid option characters. A character
+/// followed by a `:` is an option that takes an argument. A leading `:` of the
+/// option string is ignored.
+///
+/// Returns the outcome and the position to continue with. When the result is
+/// [`Outcome::End`], the position is that of the first operand.
+pub fn next(option_string: &str, args: &[String], position: Position) -> (Outcome, Position) {
+    let spec = option_string.strip_prefix(':').unwrap_or(option_string);
+    let Position { index, offset } = position;
+    let next_arg = Position {
+        index: index + 1,
+        offset: 0,
+    };
+
+    let Some(arg) = args.get(index - 1) else {
+        return (Outcome::End, position);
+    };
+    if offset == 0 {
+        if arg == "--" {
+            return (Outcome::End, next_arg);
+        }
+        if arg == "-" || !arg.starts_with('-') {
+            return (Outcome::End, position);
+        }
+    }
+
+    let mut chars = arg.chars();
+    chars.by_ref().take(1 + offset).for_each(drop);
+    let Some(option) = chars.next() else {
+        // The previous invocation has consumed the whole argument.
+        return next(option_string, args, next_arg);
+    };
+    let rest = chars.as_str();
+    let next_position = if rest.is_empty() {
+        next_arg
+    } else {
+        Position {
+            index,
+            offset: offset + 1,
+        }
+    };
+
+    match find_option(spec, option) {
+        None => (Outcome::UnknownOption(option), next_position),
+        Some(false) => {
+            let argument = None;
+            (Outcome::Option { option, argument }, next_position)
+        }
+        Some(true) if !rest.is_empty() => {
+            let argument = Some(rest.to_owned());
+            (Outcome::Option { option, argument }, next_arg)
+        }
+        Some(true) => match args.get(index) {
+            Some(argument) => {
+                let argument = Some(argument.clone());
+                let position = Position {
+                    index: index + 2,
+                    offset: 0,
+                };
+                (Outcome::Option { option, argument }, position)
+            }
+            None => (Outcome::MissingArgument(option), next_arg),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|&arg| arg.to_owned()).collect()
+    }
+
+    fn position(index: usize, offset: usize) -> Position {
+        Position { index, offset }
+    }
+
+    #[test]
+    fn position_round_trip() {
+        assert_eq!("1".parse(), Ok(position(1, 0)));
+        assert_eq!("3:2".parse(), Ok(position(3, 2)));
+        assert_eq!("0".parse::<Position>(), Err(ParsePositionError));
+        assert_eq!("x".parse::<Position>(), Err(ParsePositionError));
+        assert_eq!("2:".parse::<Position>(), Err(ParsePositionError));
+        assert_eq!(position(1, 0).to_string(), "1");
+        assert_eq!(position(3, 2).to_string(), "3:2");
+    }
+
+    #[test]
+    fn separate_options() {
+        let args = args(&["-a", "-b", "operand"]);
+        let (outcome, pos) = next("ab", &args, Position::default());
+        let argument = None;
+        assert_eq!(
+            outcome,
+            Outcome::Option {
+                option: 'a',
+                argument
+            }
+        );
+        assert_eq!(pos, position(2, 0));
+        let (outcome, pos) = next("ab", &args, pos);
+        let argument = None;
+        assert_eq!(
+            outcome,
+            Outcome::Option {
+                option: 'b',
+                argument
+            }
+        );
+        assert_eq!(pos, position(3, 0));
+        let (outcome, pos) = next("ab", &args, pos);
+        assert_eq!(outcome, Outcome::End);
+        assert_eq!(pos, position(3, 0));
+    }
+
+    #[test]
+    fn grouped_options() {
+        let args = args(&["-ab"]);
+        let (outcome, pos) = next("ab", &args, Position::default());
+        let argument = None;
+        assert_eq!(
+            outcome,
+            Outcome::Option {
+                option: 'a',
+                argument
+            }
+        );
+        assert_eq!(pos, position(1, 1));
+        let (outcome, pos) = next("ab", &args, pos);
+        let argument = None;
+        assert_eq!(
+            outcome,
+            Outcome::Option {
+                option: 'b',
+                argument
+            }
+        );
+        assert_eq!(pos, position(2, 0));
+        let (outcome, pos) = next("ab", &args, pos);
+        assert_eq!(outcome, Outcome::End);
+        assert_eq!(pos, position(2, 0));
+    }
+
+    #[test]
+    fn option_arguments() {
+        let args = args(&["-afoo", "-a", "bar", "-ba", "baz"]);
+        let (outcome, pos) = next("a:b", &args, Position::default());
+        let argument = Some("foo".to_owned());
+        assert_eq!(
+            outcome,
+            Outcome::Option {
+                option: 'a',
+                argument
+            }
+        );
+        assert_eq!(pos, position(2, 0));
+        let (outcome, pos) = next("a:b", &args, pos);
+        let argument = Some("bar".to_owned());
+        assert_eq!(
+            outcome,
+            Outcome::Option {
+                option: 'a',
+                argument
+            }
+        );
+        assert_eq!(pos, position(4, 0));
+        let (outcome, pos) = next("a:b", &args, pos);
+        let argument = None;
+        assert_eq!(
+            outcome,
+            Outcome::Option {
+                option: 'b',
+                argument
+            }
+        );
+        assert_eq!(pos, position(4, 1));
+        let (outcome, pos) = next("a:b", &args, pos);
+        let argument = Some("baz".to_owned());
+        assert_eq!(
+            outcome,
+            Outcome::Option {
+                option: 'a',
+                argument
+            }
+        );
+        assert_eq!(pos, position(6, 0));
+    }
+
+    #[test]
+    fn unknown_option() {
+        let args = args(&["-xa"]);
+        let (outcome, pos) = next(":a", &args, Position::default());
+        assert_eq!(outcome, Outcome::UnknownOption('x'));
+        assert_eq!(pos, position(1, 1));
+
+        let args = self::args(&["-:"]);
+        let (outcome, _) = next("a:", &args, Position::default());
+        assert_eq!(outcome, Outcome::UnknownOption(':'));
+    }
+
+    #[test]
+    fn missing_argument() {
+        let args = args(&["-a"]);
+        let (outcome, pos) = next("a:", &args, Position::default());
+        assert_eq!(outcome, Outcome::MissingArgument('a'));
+        assert_eq!(pos, position(2, 0));
+    }
+
+    #[test]
+    fn end_of_options() {
+        let args = args(&["--", "-a"]);
+        let (outcome, pos) = next("a", &args, Position::default());
+        assert_eq!(outcome, Outcome::End);
+        assert_eq!(pos, position(2, 0));
+
+        let args = self::args(&["-", "-a"]);
+        let (outcome, pos) = next("a", &args, Position::default());
+        assert_eq!(outcome, Outcome::End);
+        assert_eq!(pos, position(1, 0));
+
+        let (outcome, pos) = next("a", &[], Position::default());
+        assert_eq!(outcome, Outcome::End);
+        assert_eq!(pos, position(1, 0));
+    }
+}
@@ -0,0 +1,269 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Hash built-in
+//!
+//! The **`hash`** built-in caches or forgets the paths of external utilities.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! hash [name...]
+//! ```
+//!
+//! ```sh
+//! hash -r [name...]
+//! ```
+//!
+//! # Semantics
+//!
+//! Without the `-r` option, the hash built-in searches the `$PATH` for the
+//! utilities specified by the operands and caches their paths. Any paths
+//! already cached for the names are discarded before the search. Names that
+//! contain a slash are ignored. If there are no operands, the built-in prints
+//! the cached paths to the standard output, one per line.
+//!
+//! With the `-r` option, the built-in removes the paths cached for the
+//! operands. If there are no operands, all the cached paths are removed.
+//!
+//! The cache is also cleared when `$PATH` is assigned to, even if the value does
+//! not change.
+//!
+//! # Options
+//!
+//! The **`-r`** (**`--remove`**) option makes the built-in forget cached paths
+//! instead of caching them.
+//!
+//! # Operands
+//!
+//! Operands are names of utilities.
+//!
+//! # Exit status
+//!
+//! Zero unless an error occurs.
+//!
+//! # Errors
+//!
+//! It is an error if a utility named by an operand is not found. The other
+//! operands are still processed.
+//!
+//! # Portability
+//!
+//! POSIX only defines the `-r` option without operands. The output format is
+//! not specified by POSIX. The `--remove` form of the option is not portable.
+//!
+//! # Implementation notes
+//!
+//! The paths are cached in [`Env::path_cache`]. The search is done by
+//! [`search`], so operands that name built-ins or functions are not cached
+//! unless they are non-intrinsic built-ins that require an executable file.
+
+use crate::common::not_found_message;
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax::OptionSpec;
+use crate::common::Print;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::Env;
+use yash_semantics::command_search::search;
+use yash_semantics::command_search::validate_path_cache;
+
+const OPTION_SPECS: &[OptionSpec] = &[OptionSpec::new().short('r').long("remove")];
+
+/// Prints the cached paths.
+async fn print(env: &mut Env) -> Result {
+    validate_path_cache(env);
+    let output = env
+        .path_cache
+        .iter()
+        .map(|(_, path)| format!("{}\n", path.to_string_lossy()))
+        .collect::<String>();
+    env.print(&output).await
+}
+
+/// Caches the paths of the named utilities.
+async fn cache(env: &mut Env, operands: Vec<Field>) -> Result {
+    let mut result = Result::default();
+    for operand in operands {
+        if operand.value.contains('/') {
+            continue;
+        }
+        validate_path_cache(env);
+        env.path_cache.remove(&operand.value);
+        if search(env, &operand.value).is_none() {
+            result = print_failure_message(env, not_found_message("command", &operand)).await;
+        }
+    }
+    result
+}
+
+/// Entry point for executing the `hash` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (options, operands) = match parse_arguments(OPTION_SPECS, Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+
+    if options.is_empty() {
+        if operands.is_empty() {
+            print(env).await
+        } else {
+            cache(env, operands).await
+        }
+    } else {
+        if operands.is_empty() {
+            env.path_cache.clear();
+        } else {
+            for operand in operands {
+                env.path_cache.remove(&operand.value);
+            }
+        }
+        Result::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use std::cell::RefCell;
+    use std::ffi::CString;
+    use std::rc::Rc;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
+    use yash_env::system::r#virtual::INode;
+    use yash_env::system::r#virtual::SystemState;
+    use yash_env::variable::Scope;
+    use yash_env::variable::Variable;
+
+    fn prepare(env: &mut Env, state: &RefCell<SystemState>, paths: &[&str]) {
+        for path in paths {
+            let mut content = INode::default();
+            content.permissions.0 |= 0o100;
+            let content = Rc::new(RefCell::new(content));
+            state.borrow_mut().file_system.save(path, content).unwrap();
+        }
+        env.variables
+            .assign(
+                Scope::Global,
+                "PATH".to_string(),
+                Variable::new("/usr/bin:/bin"),
+            )
+            .unwrap();
+    }
+
+    fn cache_entry(env: &mut Env, name: &str, path: &str) {
+        validate_path_cache(env);
+        env.path_cache
+            .insert(name.to_string(), CString::new(path).unwrap());
+    }
+
+    #[test]
+    fn caching_paths() {
+        in_virtual_system(|mut env, state| async move {
+            prepare(&mut env, &state, &["/bin/ls", "/usr/bin/cat"]);
+
+            let args = Field::dummies(["ls", "cat"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::default());
+            assert_eq!(env.path_cache.get("ls").unwrap().to_bytes(), b"/bin/ls");
+            assert_eq!(
+                env.path_cache.get("cat").unwrap().to_bytes(),
+                b"/usr/bin/cat"
+            );
+        })
+    }
+
+    #[test]
+    fn caching_discards_stale_path() {
+        in_virtual_system(|mut env, state| async move {
+            prepare(&mut env, &state, &["/bin/ls", "/usr/bin/ls"]);
+            cache_entry(&mut env, "ls", "/bin/ls");
+
+            let result = main(&mut env, Field::dummies(["ls"])).await;
+            assert_eq!(result, Result::default());
+            assert_eq!(env.path_cache.get("ls").unwrap().to_bytes(), b"/usr/bin/ls");
+        })
+    }
+
+    #[test]
+    fn caching_unknown_command() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("hash"),
+                is_special: false,
+            });
+            prepare(&mut env, &state, &["/bin/ls"]);
+
+            let args = Field::dummies(["foo", "ls"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::FAILURE));
+            assert!(env.path_cache.get("ls").is_some());
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("`foo`"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn printing_cached_paths() {
+        in_virtual_system(|mut env, state| async move {
+            prepare(&mut env, &state, &[]);
+            cache_entry(&mut env, "sh", "/bin/sh");
+            cache_entry(&mut env, "cat", "/usr/bin/cat");
+
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| {
+                assert_eq!(stdout, "/usr/bin/cat\n/bin/sh\n")
+            });
+        })
+    }
+
+    #[test]
+    fn removing_named_paths() {
+        let mut env = Env::new_virtual();
+        cache_entry(&mut env, "sh", "/bin/sh");
+        cache_entry(&mut env, "cat", "/usr/bin/cat");
+
+        let args = Field::dummies(["-r", "cat", "foo"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert!(env.path_cache.get("cat").is_none());
+        assert!(env.path_cache.get("sh").is_some());
+    }
+
+    #[test]
+    fn removing_all_paths() {
+        let mut env = Env::new_virtual();
+        cache_entry(&mut env, "sh", "/bin/sh");
+        cache_entry(&mut env, "cat", "/usr/bin/cat");
+
+        let result = main(&mut env, Field::dummies(["-r"]))
+            .now_or_never()
+            .unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(env.path_cache.iter().count(), 0);
+    }
+}
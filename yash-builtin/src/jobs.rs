@@ -46,7 +46,24 @@
 //!
 //! ## Filtering
 //!
-//! TODO `-n`, `-r`, `-s`, `-t`
+//! By default, the built-in prints all jobs (or all jobs specified by the
+//! operands). The following options limit the jobs to be printed.
+//!
+//! The **`-n`** (**`--new`**) option limits the results to jobs whose status
+//! has changed since the last report.
+//!
+//! The **`-r`** (**`--running`**) option limits the results to running jobs.
+//!
+//! The **`-s`** (**`--stopped`**) option limits the results to stopped jobs.
+//!
+//! The **`-t`** (**`--terminated`**) option limits the results to finished
+//! jobs. Like any other finished jobs, the reported jobs are removed from the
+//! current execution environment.
+//!
+//! If more than one of the `-r`, `-s`, and `-t` options is specified, jobs
+//! matching any of them are printed. The `-n` option can be combined with the
+//! others to further limit the results. The filtering options can be used
+//! together with the format options.
 //!
 //! # Operands
 //!
@@ -102,14 +119,47 @@ use yash_syntax::source::pretty::Message;
 
 const OPTIONS: &[OptionSpec] = &[
     OptionSpec::new().short('l').long("verbose"),
+    OptionSpec::new().short('n').long("new"),
     OptionSpec::new().short('p').long("pgid-only"),
+    OptionSpec::new().short('r').long("running"),
+    OptionSpec::new().short('s').long("stopped"),
+    OptionSpec::new().short('t').long("terminated"),
 ];
 
+/// Criteria for choosing jobs to report
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Filter {
+    new_only: bool,
+    running: bool,
+    stopped: bool,
+    terminated: bool,
+}
+
+impl Filter {
+    /// Tests whether the job should be reported.
+    fn accepts(&self, job: &Job) -> bool {
+        if self.new_only && !job.status_changed {
+            return false;
+        }
+        if !self.running && !self.stopped && !self.terminated {
+            return true;
+        }
+        if job.status.is_finished() {
+            self.terminated
+        } else if job.is_suspended() {
+            self.stopped
+        } else {
+            self.running
+        }
+    }
+}
+
 struct Accumulator {
     current_job_index: Option<usize>,
     previous_job_index: Option<usize>,
     alternate_format: bool,
     pgid_only: bool,
+    filter: Filter,
     print: String,
     indices_reported: Vec<usize>,
 }
@@ -117,11 +167,18 @@ struct Accumulator {
 impl Accumulator {
     /// Processes one job.
     ///
+    /// If the job is not accepted by `self.filter`, this function does
+    /// nothing. Otherwise:
+    ///
     /// 1. Formats a job report in `self.print` so it can be printed later.
     /// 1. Clears the `status_changed` flag of the job.
     /// 1. Remembers the job index in `self.indices_reported` so the job can be
     ///    removed later.
     fn report(&mut self, index: usize, job: &Job) {
+        if !self.filter.accepts(job) {
+            return;
+        }
+
         use yash_env::job::fmt::{Marker, Report};
         let report = Report {
             index,
@@ -172,6 +229,7 @@ pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
         previous_job_index: env.jobs.previous_job(),
         alternate_format: false,
         pgid_only: false,
+        filter: Filter::default(),
         print: String::new(),
         indices_reported: Vec::new(),
     };
@@ -180,7 +238,11 @@ pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
     for option in options {
         match option.spec.get_short() {
             Some('l') => accumulator.alternate_format = true,
+            Some('n') => accumulator.filter.new_only = true,
             Some('p') => accumulator.pgid_only = true,
+            Some('r') => accumulator.filter.running = true,
+            Some('s') => accumulator.filter.stopped = true,
+            Some('t') => accumulator.filter.terminated = true,
             _ => unreachable!("unhandled option: {:?}", option),
         }
     }
@@ -602,4 +664,117 @@ mod tests {
         assert_eq!(result, Result::new(ExitStatus::SUCCESS));
         assert_stdout(&state, |stdout| assert_eq!(stdout, "72\n"));
     }
+
+    fn add_jobs_in_various_states(env: &mut Env) {
+        let mut job = Job::new(Pid::from_raw(11));
+        job.name = "echo running".to_string();
+        env.jobs.add(job);
+
+        let mut job = Job::new(Pid::from_raw(12));
+        job.status = WaitStatus::Stopped(Pid::from_raw(12), Signal::SIGTSTP);
+        job.name = "echo stopped".to_string();
+        env.jobs.add(job);
+
+        let mut job = Job::new(Pid::from_raw(13));
+        job.status = WaitStatus::Exited(Pid::from_raw(13), 0);
+        job.name = "echo exited".to_string();
+        env.jobs.add(job);
+
+        let mut job = Job::new(Pid::from_raw(14));
+        job.status_changed = false;
+        job.name = "echo reported".to_string();
+        env.jobs.add(job);
+    }
+
+    #[test]
+    fn n_option() {
+        let system = Box::new(VirtualSystem::new());
+        let state = Rc::clone(&system.state);
+        let mut env = Env::with_system(system);
+        add_jobs_in_various_states(&mut env);
+
+        let args = Field::dummies(["-np"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+        assert_stdout(&state, |stdout| assert_eq!(stdout, "11\n12\n13\n"));
+    }
+
+    #[test]
+    fn r_option() {
+        let system = Box::new(VirtualSystem::new());
+        let state = Rc::clone(&system.state);
+        let mut env = Env::with_system(system);
+        add_jobs_in_various_states(&mut env);
+
+        let args = Field::dummies(["-r"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+        assert_stdout(&state, |stdout| {
+            assert_eq!(
+                stdout,
+                "[1] - Running              echo running\n[4]   Running              echo reported\n"
+            )
+        });
+    }
+
+    #[test]
+    fn s_option() {
+        let system = Box::new(VirtualSystem::new());
+        let state = Rc::clone(&system.state);
+        let mut env = Env::with_system(system);
+        add_jobs_in_various_states(&mut env);
+
+        let args = Field::dummies(["-s", "-l"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+        assert_stdout(&state, |stdout| {
+            assert_eq!(stdout, "[2] +    12 Stopped(SIGTSTP)     echo stopped\n")
+        });
+    }
+
+    #[test]
+    fn t_option_removes_reported_jobs() {
+        let system = Box::new(VirtualSystem::new());
+        let state = Rc::clone(&system.state);
+        let mut env = Env::with_system(system);
+        add_jobs_in_various_states(&mut env);
+
+        let args = Field::dummies(["-t"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+        assert_stdout(&state, |stdout| {
+            assert_eq!(stdout, "[3]   Done                 echo exited\n")
+        });
+        assert!(env.jobs.get(0).is_some());
+        assert!(env.jobs.get(1).is_some());
+        assert!(env.jobs.get(2).is_none());
+        assert!(env.jobs.get(3).is_some());
+    }
+
+    #[test]
+    fn combining_r_and_s_options() {
+        let system = Box::new(VirtualSystem::new());
+        let state = Rc::clone(&system.state);
+        let mut env = Env::with_system(system);
+        add_jobs_in_various_states(&mut env);
+
+        let args = Field::dummies(["-rsp"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+        assert_stdout(&state, |stdout| assert_eq!(stdout, "11\n12\n14\n"));
+        assert!(env.jobs.get(2).is_some());
+    }
+
+    #[test]
+    fn filter_applies_to_operands() {
+        let system = Box::new(VirtualSystem::new());
+        let state = Rc::clone(&system.state);
+        let mut env = Env::with_system(system);
+        add_jobs_in_various_states(&mut env);
+
+        let args = Field::dummies(["-pr", "%1", "%2"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+        assert_stdout(&state, |stdout| assert_eq!(stdout, "11\n"));
+    }
 }
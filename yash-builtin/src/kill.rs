@@ -0,0 +1,136 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Kill built-in
+//!
+//! The **`kill`** built-in sends a signal to processes.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! kill [-s signal|-signal] target...
+//! ```
+//!
+//! ```sh
+//! kill -l [exit_status|signal...]
+//! ```
+//!
+//! # Semantics
+//!
+//! Without the `-l` option, the built-in sends a signal to the processes
+//! specified by the *target* operands.
+//!
+//! With the `-l` option, the built-in prints the names of signals. If no
+//! operands are given, all the signal names are printed. Otherwise, each
+//! operand is converted: an exit status or signal number is printed as the
+//! corresponding signal name, and a signal name as the corresponding signal
+//! number.
+//!
+//! # Options
+//!
+//! The **`-s`** option specifies the signal to send. The option-argument is a
+//! signal name (without the `SIG` prefix) or number. The signal name is
+//! case-insensitive and may have the `SIG` prefix. Signal number `0` means
+//! the null signal, which does not actually send anything but checks whether
+//! the targets exist.
+//!
+//! The signal can also be specified in the form of **`-signal`**, where
+//! *signal* is a signal name or number, such as `-TERM` and `-9`.
+//!
+//! If no signal is specified, `SIGTERM` is sent.
+//!
+//! The **`-l`** option selects the listing mode.
+//!
+//! # Operands
+//!
+//! A *target* is either a process ID or a [job ID](yash_env::job::id). A
+//! process ID is a decimal integer:
+//!
+//! - A positive integer specifies the process with that ID.
+//! - `0` specifies all processes in the same process group as the shell.
+//! - `-1` specifies all processes the shell can send the signal to.
+//! - A negative integer specifies the process group whose ID is the absolute
+//!   value of the integer.
+//!
+//! A job ID starts with `%`. If the job is job-controlled, the signal is sent
+//! to the process group of the job. Otherwise, the signal is sent to the
+//! process of the job.
+//!
+//! Note that a negative process ID must be preceded by `--` so that it is not
+//! taken as a signal specification.
+//!
+//! # Exit status
+//!
+//! Zero if the signal was successfully sent to all the targets (or all the
+//! operands of `-l` were converted), and non-zero otherwise.
+//!
+//! # Errors
+//!
+//! It is an error if:
+//!
+//! - the signal name or number is invalid,
+//! - a target is not a valid process ID or job ID,
+//! - a job specified by a job ID is not found, or
+//! - the signal cannot be sent to a target.
+//!
+//! An error for a target does not prevent the signal from being sent to the
+//! other targets.
+//!
+//! # Portability
+//!
+//! POSIX requires the signal name to be specified in uppercase without the
+//! `SIG` prefix. Lowercase names and the prefix are extensions. POSIX only
+//! requires the `-signal` form to accept a few specific signal numbers.
+//!
+//! POSIX allows the `-l` option to take at most one operand.
+
+use crate::common::print_error_message;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::trap::Signal;
+use yash_env::Env;
+
+/// Parsed command line arguments of the kill built-in
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Command {
+    /// Sending a signal
+    Send {
+        /// Signal to send, or `None` for the null signal
+        signal: Option<Signal>,
+        /// Targets to send the signal to
+        targets: Vec<Field>,
+    },
+    /// Printing signal names or numbers (`-l`)
+    Print {
+        /// Exit statuses or signals to convert
+        signals: Vec<Field>,
+    },
+}
+
+pub mod print;
+pub mod send;
+pub mod syntax;
+
+/// Entry point for executing the `kill` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    match syntax::parse(env, args) {
+        Ok(Command::Send { signal, targets }) => send::send(env, signal, &targets).await,
+        Ok(Command::Print { signals }) => print::print(env, &signals).await,
+        Err(error) => print_error_message(env, &error).await,
+    }
+}
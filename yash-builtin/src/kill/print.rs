@@ -0,0 +1,161 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Printing signal names and numbers
+
+use super::syntax::parse_signal;
+use crate::common::print_message;
+use crate::common::Print;
+use std::borrow::Cow;
+use thiserror::Error;
+use yash_env::builtin::Result;
+use yash_env::semantics::ExitStatus;
+use yash_env::semantics::Field;
+use yash_env::trap::Signal;
+use yash_env::Env;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::MessageBase;
+
+/// Error in converting an operand of `kill -l`
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("invalid signal")]
+pub struct Error(pub Field);
+
+impl MessageBase for Error {
+    fn message_title(&self) -> Cow<str> {
+        self.to_string().into()
+    }
+
+    fn main_annotation(&self) -> Annotation<'_> {
+        let label = format!("{:?}: not an exit status or signal", self.0.value);
+        Annotation::new(AnnotationType::Error, label.into(), &self.0.origin)
+    }
+}
+
+/// Returns the name of the signal without the `SIG` prefix.
+fn signal_name(signal: Signal) -> &'static str {
+    let name = signal.as_str();
+    name.strip_prefix("SIG").unwrap_or(name)
+}
+
+/// Converts an operand of `kill -l`.
+///
+/// An integer operand is taken as an exit status or signal number and
+/// converted to the signal name. Any other operand is taken as a signal name
+/// and converted to the signal number.
+pub fn convert(operand: &Field) -> std::result::Result<String, Error> {
+    if let Ok(number) = operand.value.parse() {
+        return match Signal::try_from(ExitStatus(number)) {
+            Ok(signal) => Ok(signal_name(signal).to_string()),
+            Err(_) => Err(Error(operand.clone())),
+        };
+    }
+
+    match parse_signal(&operand.value) {
+        Some(Some(signal)) => Ok((signal as i32).to_string()),
+        _ => Err(Error(operand.clone())),
+    }
+}
+
+/// Prints the signal names or numbers.
+///
+/// If `signals` is empty, the names of all the signals are printed.
+/// Otherwise, each operand is converted by [`convert`].
+pub async fn print(env: &mut Env, signals: &[Field]) -> Result {
+    let mut output = String::new();
+    let mut result = Result::default();
+
+    if signals.is_empty() {
+        for signal in Signal::iterator() {
+            output.push_str(signal_name(signal));
+            output.push('\n');
+        }
+    } else {
+        for operand in signals {
+            match convert(operand) {
+                Ok(converted) => {
+                    output.push_str(&converted);
+                    output.push('\n');
+                }
+                Err(error) => {
+                    let divert = print_message(env, &error).await;
+                    result = Result::with_exit_status_and_divert(ExitStatus::FAILURE, divert);
+                }
+            }
+        }
+    }
+
+    let print_result = env.print(&output).await;
+    if print_result.exit_status().is_successful() {
+        result
+    } else {
+        print_result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use yash_env::stack::Frame;
+
+    #[test]
+    fn converting_exit_statuses_and_numbers() {
+        assert_eq!(convert(&Field::dummy("2")), Ok("INT".to_string()));
+        assert_eq!(convert(&Field::dummy("130")), Ok("INT".to_string()));
+        assert_eq!(convert(&Field::dummy("393")), Ok("KILL".to_string()));
+        let operand = Field::dummy("-1");
+        assert_eq!(convert(&operand), Err(Error(operand)));
+    }
+
+    #[test]
+    fn converting_names() {
+        assert_eq!(convert(&Field::dummy("INT")), Ok("2".to_string()));
+        assert_eq!(convert(&Field::dummy("sigkill")), Ok("9".to_string()));
+        let operand = Field::dummy("FOO");
+        assert_eq!(convert(&operand), Err(Error(operand)));
+    }
+
+    #[test]
+    fn printing_all_signals() {
+        in_virtual_system(|mut env, state| async move {
+            let result = print(&mut env, &[]).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| {
+                let names: Vec<&str> = stdout.lines().collect();
+                assert!(names.contains(&"HUP"), "{stdout:?}");
+                assert!(names.contains(&"TERM"), "{stdout:?}");
+                assert_eq!(names.len(), Signal::iterator().count());
+            });
+        })
+    }
+
+    #[test]
+    fn printing_converted_operands() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("kill"),
+                is_special: false,
+            });
+            let signals = Field::dummies(["137", "foo", "QUIT"]);
+            let result = print(&mut env, &signals).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "KILL\n3\n"));
+        })
+    }
+}
@@ -0,0 +1,207 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Sending signals to targets
+
+use crate::common::print_message;
+use std::borrow::Cow;
+use thiserror::Error;
+use yash_env::builtin::Result;
+use yash_env::job::id::parse;
+use yash_env::job::id::FindError;
+use yash_env::job::JobSet;
+use yash_env::job::Pid;
+use yash_env::semantics::ExitStatus;
+use yash_env::semantics::Field;
+use yash_env::system::Errno;
+use yash_env::trap::Signal;
+use yash_env::Env;
+use yash_env::System;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::MessageBase;
+
+/// Error in sending a signal to a target
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The target is neither a process ID nor a job ID.
+    #[error("invalid process ID")]
+    InvalidTarget(Field),
+
+    /// The job specified by the job ID cannot be found.
+    #[error("cannot find the job")]
+    JobNotFound(Field, FindError),
+
+    /// The system call failed.
+    #[error("cannot send the signal")]
+    SystemError(Field, Errno),
+}
+
+impl MessageBase for Error {
+    fn message_title(&self) -> Cow<str> {
+        self.to_string().into()
+    }
+
+    fn main_annotation(&self) -> Annotation<'_> {
+        let (label, target) = match self {
+            Error::InvalidTarget(target) => (
+                format!("{:?}: not a process ID or job ID", target.value),
+                target,
+            ),
+            Error::JobNotFound(target, error) => (format!("{:?}: {}", target.value, error), target),
+            Error::SystemError(target, errno) => {
+                (format!("{:?}: {}", target.value, errno.desc()), target)
+            }
+        };
+        Annotation::new(AnnotationType::Error, label.into(), &target.origin)
+    }
+}
+
+/// Resolves the target operand to the process ID passed to the `kill` system
+/// call.
+///
+/// A job ID is resolved to the negated process group ID of the job if the job
+/// is job-controlled, and to the process ID of the job otherwise.
+pub fn resolve_target(jobs: &JobSet, target: &Field) -> std::result::Result<Pid, Error> {
+    if let Ok(job_id) = parse(&target.value) {
+        let index = job_id
+            .find(jobs)
+            .map_err(|error| Error::JobNotFound(target.clone(), error))?;
+        let job = &jobs[index];
+        return Ok(if job.job_controlled {
+            Pid::from_raw(-job.pid.as_raw())
+        } else {
+            job.pid
+        });
+    }
+
+    match target.value.parse() {
+        Ok(raw_pid) => Ok(Pid::from_raw(raw_pid)),
+        Err(_) => Err(Error::InvalidTarget(target.clone())),
+    }
+}
+
+/// Sends the signal to the target.
+pub fn send_one(
+    env: &mut Env,
+    signal: Option<Signal>,
+    target: &Field,
+) -> std::result::Result<(), Error> {
+    let pid = resolve_target(&env.jobs, target)?;
+    env.system
+        .kill(pid, signal)
+        .map_err(|errno| Error::SystemError(target.clone(), errno))
+}
+
+/// Sends the signal to the targets.
+///
+/// Errors are reported for each target that failed. The exit status is
+/// [`ExitStatus::FAILURE`] if any target failed.
+pub async fn send(env: &mut Env, signal: Option<Signal>, targets: &[Field]) -> Result {
+    let mut result = Result::default();
+    for target in targets {
+        if let Err(error) = send_one(env, signal, target) {
+            let divert = print_message(env, &error).await;
+            result = Result::with_exit_status_and_divert(ExitStatus::FAILURE, divert);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use yash_env::job::Job;
+    use yash_env::stack::Frame;
+    use yash_env::system::r#virtual::Process;
+    use yash_env::system::r#virtual::ProcessState;
+
+    #[test]
+    fn resolving_process_ids() {
+        let jobs = JobSet::default();
+        let target = Field::dummy("123");
+        assert_eq!(resolve_target(&jobs, &target), Ok(Pid::from_raw(123)));
+        let target = Field::dummy("-45");
+        assert_eq!(resolve_target(&jobs, &target), Ok(Pid::from_raw(-45)));
+        let target = Field::dummy("foo");
+        assert_eq!(
+            resolve_target(&jobs, &target),
+            Err(Error::InvalidTarget(target))
+        );
+    }
+
+    #[test]
+    fn resolving_job_ids() {
+        let mut jobs = JobSet::default();
+        let mut job = Job::new(Pid::from_raw(10));
+        job.name = "sleep".to_string();
+        jobs.add(job);
+        let mut job = Job::new(Pid::from_raw(20));
+        job.job_controlled = true;
+        job.name = "cat".to_string();
+        jobs.add(job);
+
+        let target = Field::dummy("%sleep");
+        assert_eq!(resolve_target(&jobs, &target), Ok(Pid::from_raw(10)));
+        let target = Field::dummy("%2");
+        assert_eq!(resolve_target(&jobs, &target), Ok(Pid::from_raw(-20)));
+        let target = Field::dummy("%foo");
+        assert_eq!(
+            resolve_target(&jobs, &target),
+            Err(Error::JobNotFound(target, FindError::NotFound))
+        );
+    }
+
+    #[test]
+    fn sending_signal_to_process() {
+        in_virtual_system(|mut env, state| async move {
+            let pid = Pid::from_raw(100);
+            let process = Process::with_parent_and_group(env.main_pid, pid);
+            state.borrow_mut().processes.insert(pid, process);
+
+            let result = send(&mut env, Some(Signal::SIGKILL), &Field::dummies(["100"])).await;
+            assert_eq!(result, Result::default());
+            assert_eq!(
+                state.borrow().processes[&pid].state(),
+                ProcessState::Signaled(Signal::SIGKILL)
+            );
+        })
+    }
+
+    #[test]
+    fn error_for_one_target_does_not_stop_others() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("kill"),
+                is_special: false,
+            });
+            let pid = Pid::from_raw(100);
+            let process = Process::with_parent_and_group(env.main_pid, pid);
+            state.borrow_mut().processes.insert(pid, process);
+
+            let targets = Field::dummies(["%1", "100"]);
+            let result = send(&mut env, Some(Signal::SIGKILL), &targets).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert!(!state.borrow().processes[&pid].state().is_alive());
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("%1"), "{stderr:?}")
+            });
+        })
+    }
+}
@@ -0,0 +1,299 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Command line argument parser for the kill built-in
+//!
+//! The kill built-in has a special syntax that the [common
+//! parser](crate::common::syntax) does not support: a signal name or number
+//! can be specified as an option like `-TERM` or `-9`. This module implements
+//! a dedicated parser.
+
+use super::Command;
+use crate::common::BuiltinEnv;
+use std::borrow::Cow;
+use thiserror::Error;
+use yash_env::semantics::Field;
+use yash_env::trap::Signal;
+use yash_env::Env;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::MessageBase;
+use yash_syntax::source::Location;
+
+/// Error in parsing command line arguments
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The `-s` option is not followed by a signal.
+    #[error("missing option argument")]
+    MissingSignal(Field),
+
+    /// The signal name or number is not valid.
+    #[error("invalid signal")]
+    InvalidSignal(Field),
+
+    /// The `-l` and `-s` options are specified together.
+    #[error("conflicting options")]
+    ConflictingOptions(Field),
+
+    /// No target is specified.
+    #[error("missing operand")]
+    MissingOperand {
+        /// Location of the built-in name
+        location: Location,
+    },
+}
+
+impl MessageBase for Error {
+    fn message_title(&self) -> Cow<str> {
+        self.to_string().into()
+    }
+
+    fn main_annotation(&self) -> Annotation<'_> {
+        use Error::*;
+        let (label, location) = match self {
+            MissingSignal(option) => (
+                format!("option {:?} requires a signal", option.value).into(),
+                &option.origin,
+            ),
+            InvalidSignal(signal) => (
+                format!("{:?} is not a valid signal", signal.value).into(),
+                &signal.origin,
+            ),
+            ConflictingOptions(option) => (
+                format!("{:?} cannot be used with -l", option.value).into(),
+                &option.origin,
+            ),
+            MissingOperand { location } => ("no target process specified".into(), location),
+        };
+        Annotation::new(AnnotationType::Error, label, location)
+    }
+}
+
+/// Result of parsing command line arguments
+pub type Result = std::result::Result<Command, Error>;
+
+/// Parses a signal name or number.
+///
+/// The name is case-insensitive and may have the `SIG` prefix. Number `0`
+/// represents the null signal, for which this function returns `Some(None)`.
+/// Returns `None` if the string is not a valid signal.
+#[must_use]
+pub fn parse_signal(s: &str) -> Option<Option<Signal>> {
+    if let Ok(number) = s.parse::<i32>() {
+        return match number {
+            0 => Some(None),
+            _ => Signal::try_from(number).ok().map(Some),
+        };
+    }
+
+    let upper = s.to_ascii_uppercase();
+    let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+    if name.is_empty() {
+        return None;
+    }
+    format!("SIG{name}").parse().ok().map(Some)
+}
+
+/// Parses command line arguments for the kill built-in.
+pub fn parse(env: &Env, args: Vec<Field>) -> Result {
+    let mut args = args.into_iter().peekable();
+    let mut signal = Some(Signal::SIGTERM);
+    let mut list = false;
+
+    while let Some(arg) = args.next_if(|arg| arg.value.starts_with('-') && arg.value != "-") {
+        if arg.value == "--" {
+            break;
+        }
+        if arg.value == "-l" {
+            list = true;
+            continue;
+        }
+
+        let spec = match arg.value.as_str() {
+            "-s" => match args.next() {
+                Some(spec) => spec,
+                None => return Err(Error::MissingSignal(arg)),
+            },
+            value => {
+                // "-sNAME" is the -s option with an attached argument unless
+                // "sNAME" is a signal name by itself (like "sigint" or "stop").
+                let tail = &value[1..];
+                let name = match tail.strip_prefix('s') {
+                    Some(name) if parse_signal(tail).is_none() => name,
+                    _ => tail,
+                };
+                Field {
+                    value: name.to_owned(),
+                    origin: arg.origin.clone(),
+                }
+            }
+        };
+
+        if list {
+            return Err(Error::ConflictingOptions(arg));
+        }
+        signal = match parse_signal(&spec.value) {
+            Some(signal) => signal,
+            None => return Err(Error::InvalidSignal(spec)),
+        };
+
+        // Only one signal can be specified.
+        args.next_if(|arg| arg.value == "--");
+        break;
+    }
+
+    let operands: Vec<Field> = args.collect();
+    if list {
+        return Ok(Command::Print { signals: operands });
+    }
+    if operands.is_empty() {
+        let location = env.builtin_name().origin.clone();
+        return Err(Error::MissingOperand { location });
+    }
+    Ok(Command::Send {
+        signal,
+        targets: operands,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yash_env::stack::Frame;
+
+    #[test]
+    fn parsing_signals() {
+        assert_eq!(parse_signal("TERM"), Some(Some(Signal::SIGTERM)));
+        assert_eq!(parse_signal("int"), Some(Some(Signal::SIGINT)));
+        assert_eq!(parse_signal("SIGHUP"), Some(Some(Signal::SIGHUP)));
+        assert_eq!(parse_signal("9"), Some(Some(Signal::SIGKILL)));
+        assert_eq!(parse_signal("0"), Some(None));
+        assert_eq!(parse_signal("SIG"), None);
+        assert_eq!(parse_signal("FOO"), None);
+        assert_eq!(parse_signal("-1"), None);
+    }
+
+    #[test]
+    fn default_signal() {
+        let env = Env::new_virtual();
+        let args = Field::dummies(["123", "%1"]);
+        let result = parse(&env, args.clone());
+        assert_eq!(
+            result,
+            Ok(Command::Send {
+                signal: Some(Signal::SIGTERM),
+                targets: args
+            })
+        );
+    }
+
+    #[test]
+    fn signal_option_forms() {
+        let env = Env::new_virtual();
+        for args in [
+            ["-s", "INT", "1"].as_slice(),
+            &["-sINT", "1"],
+            &["-INT", "1"],
+            &["-2", "1"],
+            &["-s", "sigint", "--", "1"],
+        ] {
+            let result = parse(&env, Field::dummies(args.iter().copied()));
+            assert_eq!(
+                result,
+                Ok(Command::Send {
+                    signal: Some(Signal::SIGINT),
+                    targets: Field::dummies(["1"])
+                }),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn negative_process_id_after_signal() {
+        let env = Env::new_virtual();
+        let result = parse(&env, Field::dummies(["-9", "-1"]));
+        assert_eq!(
+            result,
+            Ok(Command::Send {
+                signal: Some(Signal::SIGKILL),
+                targets: Field::dummies(["-1"])
+            })
+        );
+    }
+
+    #[test]
+    fn null_signal() {
+        let env = Env::new_virtual();
+        let result = parse(&env, Field::dummies(["-0", "1"]));
+        assert_eq!(
+            result,
+            Ok(Command::Send {
+                signal: None,
+                targets: Field::dummies(["1"])
+            })
+        );
+    }
+
+    #[test]
+    fn list() {
+        let env = Env::new_virtual();
+        let result = parse(&env, Field::dummies(["-l"]));
+        assert_eq!(result, Ok(Command::Print { signals: vec![] }));
+
+        let result = parse(&env, Field::dummies(["-l", "--", "130", "INT"]));
+        assert_eq!(
+            result,
+            Ok(Command::Print {
+                signals: Field::dummies(["130", "INT"])
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_signal() {
+        let env = Env::new_virtual();
+        let result = parse(&env, Field::dummies(["-s", "FOO", "1"]));
+        assert_eq!(result, Err(Error::InvalidSignal(Field::dummy("FOO"))));
+
+        let result = parse(&env, Field::dummies(["-s"]));
+        assert_eq!(result, Err(Error::MissingSignal(Field::dummy("-s"))));
+    }
+
+    #[test]
+    fn conflicting_options() {
+        let env = Env::new_virtual();
+        let result = parse(&env, Field::dummies(["-l", "-s", "INT"]));
+        assert_eq!(result, Err(Error::ConflictingOptions(Field::dummy("-s"))));
+    }
+
+    #[test]
+    fn missing_operand() {
+        let mut env = Env::new_virtual();
+        let env = env.push_frame(Frame::Builtin {
+            name: Field::dummy("kill"),
+            is_special: false,
+        });
+        let result = parse(&env, Field::dummies(["-9"]));
+        assert_eq!(
+            result,
+            Err(Error::MissingOperand {
+                location: Location::dummy("kill")
+            })
+        );
+    }
+}
@@ -24,38 +24,95 @@
 //! crate, which is enabled by default. If you disable the `yash-semantics`
 //! feature, the following built-ins will be unavailable:
 //!
+//! - `.` (`source`)
+//! - `[` (`test`)
+//! - `command`
+//! - `eval`
 //! - `exec`
+//! - `hash`
+//! - `read`
+//! - `test`
 
 pub mod alias;
+pub mod bg;
 pub mod r#break;
+pub mod cd;
+#[cfg(feature = "yash-semantics")]
+pub mod command;
 pub mod common;
 pub mod r#continue;
+pub mod echo;
+#[cfg(feature = "yash-semantics")]
+pub mod eval;
 #[cfg(feature = "yash-semantics")]
 pub mod exec;
 pub mod exit;
+pub mod export;
+pub mod fg;
+pub mod getopts;
+#[cfg(feature = "yash-semantics")]
+pub mod hash;
 pub mod jobs;
+pub mod kill;
+pub mod printf;
 pub mod pwd;
+#[cfg(feature = "yash-semantics")]
+pub mod read;
 pub mod readonly;
 pub mod r#return;
 pub mod set;
+pub mod shift;
+#[cfg(feature = "yash-semantics")]
+pub mod source;
+#[cfg(feature = "yash-semantics")]
+pub mod test;
+pub mod times;
 pub mod trap;
+pub mod typeset;
+pub mod ulimit;
+pub mod umask;
+pub mod unalias;
+pub mod unset;
 pub mod wait;
 
 #[doc(no_inline)]
 pub use yash_env::builtin::*;
 
 use std::future::ready;
-use Type::{Intrinsic, Special};
+use Type::{Intrinsic, NonIntrinsic, Special};
 
 /// Array of all the implemented built-in utilities.
 ///
 /// The array items are ordered alphabetically.
 pub const BUILTINS: &[(&str, Builtin)] = &[
+    #[cfg(feature = "yash-semantics")]
+    (
+        ".",
+        Builtin {
+            r#type: Special,
+            execute: |env, args| Box::pin(source::main(env, args)),
+        },
+    ),
+    #[cfg(feature = "yash-semantics")]
+    (
+        "[",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(test::main(env, args)),
+        },
+    ),
     (
         "alias",
         Builtin {
             r#type: Intrinsic,
-            execute: |env, args| Box::pin(ready(alias::main(env, args))),
+            execute: |env, args| Box::pin(alias::main(env, args)),
+        },
+    ),
+    (
+        "bg",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(bg::main(env, args)),
         },
     ),
     (
@@ -65,6 +122,21 @@ pub const BUILTINS: &[(&str, Builtin)] = &[
             execute: |env, args| Box::pin(r#break::main(env, args)),
         },
     ),
+    (
+        "cd",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(cd::main(env, args)),
+        },
+    ),
+    #[cfg(feature = "yash-semantics")]
+    (
+        "command",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(command::main(env, args)),
+        },
+    ),
     (
         "continue",
         Builtin {
@@ -72,6 +144,21 @@ pub const BUILTINS: &[(&str, Builtin)] = &[
             execute: |env, args| Box::pin(r#continue::main(env, args)),
         },
     ),
+    (
+        "echo",
+        Builtin {
+            r#type: NonIntrinsic,
+            execute: |env, args| Box::pin(echo::main(env, args)),
+        },
+    ),
+    #[cfg(feature = "yash-semantics")]
+    (
+        "eval",
+        Builtin {
+            r#type: Special,
+            execute: |env, args| Box::pin(eval::main(env, args)),
+        },
+    ),
     #[cfg(feature = "yash-semantics")]
     (
         "exec",
@@ -87,6 +174,35 @@ pub const BUILTINS: &[(&str, Builtin)] = &[
             execute: |env, args| Box::pin(exit::main(env, args)),
         },
     ),
+    (
+        "export",
+        Builtin {
+            r#type: Special,
+            execute: |env, args| Box::pin(export::main(env, args)),
+        },
+    ),
+    (
+        "fg",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(fg::main(env, args)),
+        },
+    ),
+    (
+        "getopts",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(getopts::main(env, args)),
+        },
+    ),
+    #[cfg(feature = "yash-semantics")]
+    (
+        "hash",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(hash::main(env, args)),
+        },
+    ),
     (
         "jobs",
         Builtin {
@@ -94,6 +210,27 @@ pub const BUILTINS: &[(&str, Builtin)] = &[
             execute: |env, args| Box::pin(jobs::main(env, args)),
         },
     ),
+    (
+        "kill",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(kill::main(env, args)),
+        },
+    ),
+    (
+        "local",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(typeset::main(env, args)),
+        },
+    ),
+    (
+        "printf",
+        Builtin {
+            r#type: NonIntrinsic,
+            execute: |env, args| Box::pin(printf::main(env, args)),
+        },
+    ),
     (
         "pwd",
         Builtin {
@@ -101,6 +238,14 @@ pub const BUILTINS: &[(&str, Builtin)] = &[
             execute: |env, args| Box::pin(pwd::main(env, args)),
         },
     ),
+    #[cfg(feature = "yash-semantics")]
+    (
+        "read",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(read::main(env, args)),
+        },
+    ),
     (
         "readonly",
         Builtin {
@@ -122,6 +267,36 @@ pub const BUILTINS: &[(&str, Builtin)] = &[
             execute: |env, args| Box::pin(set::main(env, args)),
         },
     ),
+    (
+        "shift",
+        Builtin {
+            r#type: Special,
+            execute: |env, args| Box::pin(shift::main(env, args)),
+        },
+    ),
+    #[cfg(feature = "yash-semantics")]
+    (
+        "source",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(source::main(env, args)),
+        },
+    ),
+    #[cfg(feature = "yash-semantics")]
+    (
+        "test",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(test::main(env, args)),
+        },
+    ),
+    (
+        "times",
+        Builtin {
+            r#type: Special,
+            execute: |env, args| Box::pin(times::main(env, args)),
+        },
+    ),
     (
         "trap",
         Builtin {
@@ -129,6 +304,41 @@ pub const BUILTINS: &[(&str, Builtin)] = &[
             execute: |env, args| Box::pin(trap::main(env, args)),
         },
     ),
+    (
+        "typeset",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(typeset::main(env, args)),
+        },
+    ),
+    (
+        "ulimit",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(ulimit::main(env, args)),
+        },
+    ),
+    (
+        "umask",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(umask::main(env, args)),
+        },
+    ),
+    (
+        "unalias",
+        Builtin {
+            r#type: Intrinsic,
+            execute: |env, args| Box::pin(unalias::main(env, args)),
+        },
+    ),
+    (
+        "unset",
+        Builtin {
+            r#type: Special,
+            execute: |env, args| Box::pin(unset::main(env, args)),
+        },
+    ),
     (
         "wait",
         Builtin {
@@ -146,12 +356,24 @@ pub(crate) mod tests {
     use std::cell::Cell;
     use std::cell::RefCell;
     use std::future::Future;
+    use std::ops::ControlFlow::Continue;
     use std::pin::Pin;
     use std::rc::Rc;
     use std::str::from_utf8;
+    use yash_env::job::Job;
+    use yash_env::job::Pid;
+    use yash_env::job::WaitStatus;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::subshell::JobControl;
+    use yash_env::subshell::Subshell;
     use yash_env::system::r#virtual::FileBody;
+    use yash_env::system::r#virtual::INode;
     use yash_env::system::r#virtual::SystemState;
+    use yash_env::system::SignalHandling;
+    use yash_env::trap::Signal;
+    use yash_env::trap::SignalSystem;
     use yash_env::Env;
+    use yash_env::System;
     use yash_env::VirtualSystem;
 
     #[derive(Clone, Debug)]
@@ -212,6 +434,21 @@ pub(crate) mod tests {
         })
     }
 
+    /// Helper function for asserting on the raw content of /dev/stdout.
+    ///
+    /// Unlike [`assert_stdout`], this function does not require the content
+    /// to be valid UTF-8.
+    pub fn assert_stdout_bytes<F, T>(state: &RefCell<SystemState>, f: F) -> T
+    where
+        F: FnOnce(&[u8]) -> T,
+    {
+        let stdout = state.borrow().file_system.get("/dev/stdout").unwrap();
+        let stdout = stdout.borrow();
+        assert_matches!(&stdout.body, FileBody::Regular { content, .. } => {
+            f(content)
+        })
+    }
+
     /// Helper function for asserting on the content of /dev/stderr.
     pub fn assert_stderr<F, T>(state: &RefCell<SystemState>, f: F) -> T
     where
@@ -224,6 +461,48 @@ pub(crate) mod tests {
         })
     }
 
+    /// Helper function for creating a dummy `/dev/tty`.
+    pub fn stub_tty(state: &RefCell<SystemState>) {
+        state
+            .borrow_mut()
+            .file_system
+            .save("/dev/tty", Rc::new(RefCell::new(INode::new([]))))
+            .unwrap();
+    }
+
+    /// Starts a job-controlled subshell that suspends itself.
+    ///
+    /// The subshell is added to `env.jobs` with the given name. When resumed,
+    /// the subshell exits with the given exit status. This function returns
+    /// the index of the job.
+    ///
+    /// The `Monitor` option must be enabled and `/dev/tty` must exist in the
+    /// virtual system.
+    pub async fn suspended_job(env: &mut Env, name: &str, exit_status: ExitStatus) -> usize {
+        let subshell = Subshell::new(move |env, _job_control| {
+            Box::pin(async move {
+                env.system
+                    .set_signal_handling(Signal::SIGCONT, SignalHandling::Catch)
+                    .unwrap();
+                env.system
+                    .kill(Pid::from_raw(0), Some(Signal::SIGSTOP))
+                    .unwrap();
+                env.wait_for_signal(Signal::SIGCONT).await;
+                env.exit_status = exit_status;
+                Continue(())
+            })
+        })
+        .job_control(JobControl::Foreground);
+        let status = subshell.start_and_wait(env).await.unwrap();
+        assert_matches!(status, WaitStatus::Stopped(_, Signal::SIGSTOP));
+
+        let mut job = Job::new(status.pid().unwrap());
+        job.job_controlled = true;
+        job.status = status;
+        job.name = name.to_string();
+        env.jobs.add(job)
+    }
+
     #[test]
     fn builtins_are_sorted() {
         super::BUILTINS
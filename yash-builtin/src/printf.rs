@@ -0,0 +1,231 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Printf built-in
+//!
+//! The **`printf`** built-in prints a formatted string.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! printf format [argument...]
+//! ```
+//!
+//! # Semantics
+//!
+//! The printf built-in prints the *format* operand, replacing each conversion
+//! specification with the next *argument* formatted as the specification
+//! directs.
+//!
+//! If there are more arguments than conversion specifications in the format,
+//! the format is reused until all the arguments are consumed. If the format
+//! runs out of arguments, the remaining conversion specifications are given
+//! zero or an empty string.
+//!
+//! # Options
+//!
+//! None.
+//!
+//! # Operands
+//!
+//! The *format* operand may contain the following escape sequences:
+//!
+//! - `\\`: backslash
+//! - `\a`: alert (bell)
+//! - `\b`: backspace
+//! - `\f`: form feed
+//! - `\n`: newline
+//! - `\r`: carriage return
+//! - `\t`: horizontal tab
+//! - `\v`: vertical tab
+//! - `\ddd`: byte of the one- to three-digit octal number `ddd`
+//!
+//! A conversion specification starts with `%` and is followed by optional
+//! flags (`-`, `+`, space, `#`, and `0`), an optional field width, an optional
+//! precision (`.` followed by digits), and a conversion specifier:
+//!
+//! - `d`, `i`: signed decimal integer
+//! - `o`: unsigned octal integer
+//! - `u`: unsigned decimal integer
+//! - `x`, `X`: unsigned hexadecimal integer
+//! - `f`, `F`: floating-point number in the fixed-point notation
+//! - `e`, `E`: floating-point number in the exponential notation
+//! - `g`, `G`: floating-point number in the shorter of the above notations
+//! - `c`: first character of the argument
+//! - `s`: argument as is
+//! - `b`: argument with escape sequences interpreted like in the
+//!   [echo](crate::echo) built-in
+//! - `%`: literal `%` (no argument is consumed)
+//!
+//! An argument for a numeric conversion may be a decimal, octal (starting
+//! with `0`), or hexadecimal (starting with `0x`) integer, optionally preceded
+//! by a sign. Floating-point conversions also accept decimal fractions and
+//! exponents. If an argument starts with a single or double quote, its value
+//! is the character code of the following character.
+//!
+//! If the `b` conversion encounters `\c` in the argument, the built-in stops
+//! printing anything after that.
+//!
+//! # Exit status
+//!
+//! Zero if successful, one if an argument for a numeric conversion is not a
+//! valid number, and two if the format contains an invalid conversion
+//! specification.
+//!
+//! # Errors
+//!
+//! If an argument for a numeric conversion is not a valid number, an error
+//! message is printed and the part of the argument that could be parsed is
+//! used for the conversion. The built-in continues formatting the remaining
+//! arguments.
+//!
+//! An invalid conversion specification stops the formatting. The output
+//! produced before the specification is still printed.
+//!
+//! # Portability
+//!
+//! POSIX leaves unspecified the behavior of the `F`, `e`, `E`, `f`, `g`, and
+//! `G` conversions; they are supported as in the C `printf` function. The
+//! length modifiers of the C `printf` function (`h`, `l`, etc.) and the `*`
+//! width and precision are not supported.
+//!
+//! # Implementation notes
+//!
+//! Formatting is done in the [`format`] module. Arguments for integer
+//! conversions are handled as 64-bit integers.
+
+use crate::common::print_error_message;
+use crate::common::print_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax_error;
+use crate::common::BuiltinEnv;
+use crate::common::Print;
+use yash_env::builtin::Result;
+use yash_env::semantics::ExitStatus;
+use yash_env::semantics::Field;
+use yash_env::Env;
+
+pub mod format;
+
+/// Entry point for executing the `printf` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (_options, operands) = match parse_arguments(&[], Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+    let Some((format, args)) = operands.split_first() else {
+        let location = env.builtin_name().origin.clone();
+        return syntax_error(env, "format operand missing", &location).await;
+    };
+
+    let output = format::printf(format, args);
+    let mut result = env.print_bytes(&output.bytes).await;
+    if result.exit_status() != ExitStatus::SUCCESS {
+        return result;
+    }
+
+    for error in &output.errors {
+        let divert = print_message(env, error).await;
+        let exit_status = if error.is_fatal() {
+            ExitStatus::ERROR
+        } else {
+            ExitStatus::FAILURE
+        };
+        result = Result::with_exit_status_and_divert(exit_status, divert);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::assert_stdout;
+    use crate::tests::assert_stdout_bytes;
+    use crate::tests::in_virtual_system;
+    use yash_env::stack::Frame;
+
+    #[test]
+    fn printing_formatted_string() {
+        in_virtual_system(|mut env, state| async move {
+            let args = Field::dummies(["%s=%d\\n", "a", "1", "b", "2"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "a=1\nb=2\n"));
+        })
+    }
+
+    #[test]
+    fn non_utf8_bytes_are_printed_intact() {
+        in_virtual_system(|mut env, state| async move {
+            let args = Field::dummies(["\\377%b", "\\0200"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+            assert_stdout_bytes(&state, |stdout| assert_eq!(stdout, b"\xFF\x80"));
+        })
+    }
+
+    #[test]
+    fn invalid_number() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("printf"),
+                is_special: false,
+            });
+            let args = Field::dummies(["%d,%d", "1x", "2"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "1,2"));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("1x"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn invalid_conversion() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("printf"),
+                is_special: false,
+            });
+            let args = Field::dummies(["foo%y"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result.exit_status(), ExitStatus::ERROR);
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "foo"));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("%y"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn missing_format() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("printf"),
+                is_special: false,
+            });
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result.exit_status(), ExitStatus::ERROR);
+            assert_stdout(&state, |stdout| assert_eq!(stdout, ""));
+            assert_stderr(&state, |stderr| assert_ne!(stderr, ""));
+        })
+    }
+}
@@ -0,0 +1,699 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Format string interpretation
+//!
+//! This module implements the core of the printf built-in. The [`printf`]
+//! function applies the format operand to the argument operands and produces
+//! the [`Output`].
+
+use crate::common::escape::unescape;
+use crate::common::escape::Style;
+use std::borrow::Cow;
+use std::ops::ControlFlow::{self, Break};
+use thiserror::Error;
+use yash_env::semantics::Field;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::MessageBase;
+
+/// Error in formatting
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The format operand contains an invalid conversion specification.
+    ///
+    /// This error stops the formatting.
+    #[error("invalid conversion specification")]
+    InvalidConversion {
+        /// Invalid conversion specification
+        spec: String,
+        /// Format operand containing the specification
+        format: Field,
+    },
+
+    /// An operand for a numeric conversion is not a valid number.
+    ///
+    /// The formatting continues with the value that was parsed from the
+    /// beginning of the operand.
+    #[error("invalid number")]
+    InvalidNumber(Field),
+
+    /// An operand for a numeric conversion is too large.
+    ///
+    /// The formatting continues with the value clamped to the representable
+    /// range.
+    #[error("number out of range")]
+    OutOfRange(Field),
+}
+
+impl Error {
+    /// Returns true if this error stops the formatting.
+    #[must_use]
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Error::InvalidConversion { .. })
+    }
+}
+
+impl MessageBase for Error {
+    fn message_title(&self) -> Cow<str> {
+        self.to_string().into()
+    }
+
+    fn main_annotation(&self) -> Annotation<'_> {
+        let (label, location) = match self {
+            Error::InvalidConversion { spec, format } => (
+                format!("{spec:?}: invalid conversion specification"),
+                &format.origin,
+            ),
+            Error::InvalidNumber(operand) => (
+                format!("{:?}: not a valid number", operand.value),
+                &operand.origin,
+            ),
+            Error::OutOfRange(operand) => (
+                format!("{:?}: number out of range", operand.value),
+                &operand.origin,
+            ),
+        };
+        Annotation::new(AnnotationType::Error, label.into(), location)
+    }
+}
+
+/// Result of [`printf`]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Output {
+    /// Formatted bytes
+    pub bytes: Vec<u8>,
+    /// Errors that occurred in formatting
+    ///
+    /// If the last error is [fatal](Error::is_fatal), the formatting stopped
+    /// there.
+    pub errors: Vec<Error>,
+}
+
+/// Flags in a conversion specification
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Flags {
+    minus: bool,
+    plus: bool,
+    space: bool,
+    hash: bool,
+    zero: bool,
+}
+
+/// Conversion specification
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Spec {
+    flags: Flags,
+    width: Option<usize>,
+    precision: Option<usize>,
+    conversion: char,
+}
+
+/// Parses a number of decimal digits at the beginning of the iterator.
+fn decimal<I: Iterator<Item = char> + Clone>(chars: &mut I) -> Option<usize> {
+    let mut value = None;
+    loop {
+        let mut lookahead = chars.clone();
+        match lookahead.next().and_then(|c| c.to_digit(10)) {
+            Some(digit) => {
+                let old = value.unwrap_or(0_usize);
+                value = Some(old.saturating_mul(10).saturating_add(digit as usize));
+                *chars = lookahead;
+            }
+            None => return value,
+        }
+    }
+}
+
+/// Parses a conversion specification after the initial `%`.
+///
+/// Returns `None` if the format ends before the conversion specifier.
+fn parse_spec<I: Iterator<Item = char> + Clone>(chars: &mut I) -> Option<Spec> {
+    let mut flags = Flags::default();
+    loop {
+        let mut lookahead = chars.clone();
+        match lookahead.next()? {
+            '-' => flags.minus = true,
+            '+' => flags.plus = true,
+            ' ' => flags.space = true,
+            '#' => flags.hash = true,
+            '0' => flags.zero = true,
+            _ => break,
+        }
+        *chars = lookahead;
+    }
+
+    let width = decimal(chars);
+
+    let mut lookahead = chars.clone();
+    let precision = if lookahead.next() == Some('.') {
+        *chars = lookahead;
+        Some(decimal(chars).unwrap_or(0))
+    } else {
+        None
+    };
+
+    let conversion = chars.next()?;
+    Some(Spec {
+        flags,
+        width,
+        precision,
+        conversion,
+    })
+}
+
+/// Result of parsing a numeric operand
+struct Number<T> {
+    value: T,
+    error: Option<Error>,
+}
+
+/// Parses an integer operand like `strtol`.
+///
+/// The result is a pair of the sign (true for negative) and the magnitude.
+fn parse_integer(operand: &Field) -> Number<(bool, u64)> {
+    let s = operand.value.trim_start();
+
+    if let Some(rest) = s.strip_prefix(['\'', '"']) {
+        let value = rest.chars().next().map_or(0, |c| c as u64);
+        return Number {
+            value: (false, value),
+            error: None,
+        };
+    }
+
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+
+    let (radix, digits) = if let Some(rest) = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_hexdigit()))
+    {
+        (16, rest)
+    } else if s.starts_with('0') {
+        (8, s)
+    } else {
+        (10, s)
+    };
+
+    let mut magnitude = 0_u64;
+    let mut overflow = false;
+    let mut length = 0;
+    for c in digits.chars() {
+        let Some(digit) = c.to_digit(radix) else {
+            break;
+        };
+        match magnitude
+            .checked_mul(radix.into())
+            .and_then(|m| m.checked_add(digit.into()))
+        {
+            Some(m) => magnitude = m,
+            None => overflow = true,
+        }
+        length += c.len_utf8();
+    }
+
+    let error = if (length == 0 && !operand.value.is_empty()) || length < digits.len() {
+        Some(Error::InvalidNumber(operand.clone()))
+    } else if overflow {
+        magnitude = u64::MAX;
+        Some(Error::OutOfRange(operand.clone()))
+    } else {
+        None
+    };
+    Number {
+        value: (negative, magnitude),
+        error,
+    }
+}
+
+/// Parses a floating-point operand like `strtod`.
+fn parse_float(operand: &Field) -> Number<f64> {
+    let s = operand.value.trim_start();
+
+    if let Some(rest) = s.strip_prefix(['\'', '"']) {
+        let value = rest.chars().next().map_or(0, |c| c as u32);
+        return Number {
+            value: value.into(),
+            error: None,
+        };
+    }
+    if s.is_empty() {
+        return Number {
+            value: 0.0,
+            error: None,
+        };
+    }
+    if let Ok(value) = s.parse() {
+        return Number { value, error: None };
+    }
+
+    // Fall back on the integer syntax to accept hexadecimal numbers.
+    let Number {
+        value: (negative, magnitude),
+        error,
+    } = parse_integer(operand);
+    let value = magnitude as f64;
+    let value = if negative { -value } else { value };
+    Number { value, error }
+}
+
+/// Removes trailing zeros in the fractional part of a decimal number.
+fn strip_trailing_zeros(number: &mut String) {
+    if number.contains('.') {
+        let length = number.trim_end_matches('0').trim_end_matches('.').len();
+        number.truncate(length);
+    }
+}
+
+/// Formats a finite non-negative number in the `%f` style.
+fn fixed(value: f64, precision: usize, hash: bool) -> String {
+    let mut result = format!("{value:.precision$}");
+    if hash && precision == 0 {
+        result.push('.');
+    }
+    result
+}
+
+/// Formats a finite non-negative number in the `%e` style.
+///
+/// If `strip` is true, trailing zeros in the fractional part are removed.
+fn exponential(value: f64, precision: usize, hash: bool, strip: bool) -> String {
+    let formatted = format!("{value:.precision$e}");
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let mut mantissa = mantissa.to_owned();
+    if strip {
+        strip_trailing_zeros(&mut mantissa);
+    }
+    if hash && !mantissa.contains('.') {
+        mantissa.push('.');
+    }
+    let exponent: i32 = exponent.parse().unwrap();
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{mantissa}e{sign}{:02}", exponent.abs())
+}
+
+/// Formats a finite non-negative number in the `%g` style.
+fn general(value: f64, precision: usize, hash: bool) -> String {
+    let precision = precision.max(1);
+    let formatted = format!("{value:.0$e}", precision - 1);
+    let (_, exponent) = formatted.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    if -4 <= exponent && exponent < precision as i32 {
+        let mut result = fixed(value, (precision as i32 - 1 - exponent) as usize, hash);
+        if !hash {
+            strip_trailing_zeros(&mut result);
+        }
+        result
+    } else {
+        exponential(value, precision - 1, hash, !hash)
+    }
+}
+
+/// State of formatting
+struct Formatter<'a> {
+    args: &'a [Field],
+    consumed: bool,
+    output: Output,
+}
+
+impl Formatter<'_> {
+    /// Takes the next argument operand, if any.
+    fn next_arg(&mut self) -> Option<&Field> {
+        let (first, rest) = self.args.split_first()?;
+        self.args = rest;
+        self.consumed = true;
+        Some(first)
+    }
+
+    /// Appends a padded string to the output.
+    ///
+    /// The `prefix` is placed before the zero padding, if any.
+    fn pad(&mut self, spec: &Spec, prefix: &str, body: &str, zero_pad: bool) {
+        self.pad_bytes(spec, prefix, body.as_bytes(), zero_pad)
+    }
+
+    /// Appends a padded byte string to the output.
+    ///
+    /// This function is the same as [`pad`](Self::pad) except that the body
+    /// may contain bytes that are not valid UTF-8. Each invalid byte sequence
+    /// counts as one character in computing the padding width.
+    fn pad_bytes(&mut self, spec: &Spec, prefix: &str, body: &[u8], zero_pad: bool) {
+        let body_length = String::from_utf8_lossy(body).chars().count();
+        let length = prefix.chars().count() + body_length;
+        let fill = spec.width.unwrap_or(0).saturating_sub(length);
+        let bytes = &mut self.output.bytes;
+        if spec.flags.minus {
+            bytes.extend(prefix.as_bytes());
+            bytes.extend(body);
+            bytes.extend(std::iter::repeat(b' ').take(fill));
+        } else if zero_pad {
+            bytes.extend(prefix.as_bytes());
+            bytes.extend(std::iter::repeat(b'0').take(fill));
+            bytes.extend(body);
+        } else {
+            bytes.extend(std::iter::repeat(b' ').take(fill));
+            bytes.extend(prefix.as_bytes());
+            bytes.extend(body);
+        }
+    }
+
+    /// Returns the sign prefix for a signed conversion.
+    fn sign(spec: &Spec, negative: bool) -> &'static str {
+        if negative {
+            "-"
+        } else if spec.flags.plus {
+            "+"
+        } else if spec.flags.space {
+            " "
+        } else {
+            ""
+        }
+    }
+
+    /// Performs a `%s` or `%c` conversion.
+    fn string(&mut self, spec: &Spec) {
+        let value = self.next_arg().map_or("", |arg| &arg.value);
+        let value = match spec.conversion {
+            'c' => value.chars().take(1).collect::<String>(),
+            _ => match spec.precision {
+                Some(precision) => value.chars().take(precision).collect(),
+                None => value.to_owned(),
+            },
+        };
+        self.pad(spec, "", &value, false);
+    }
+
+    /// Performs a `%b` conversion.
+    fn escaped_string(&mut self, spec: &Spec) -> ControlFlow<()> {
+        let value = self.next_arg().map_or("", |arg| &arg.value);
+        let mut bytes = Vec::new();
+        let result = unescape(value, Style::Operand, &mut bytes);
+        if let Some(precision) = spec.precision {
+            bytes.truncate(precision);
+        }
+        self.pad_bytes(spec, "", &bytes, false);
+        result
+    }
+
+    /// Performs an integer conversion.
+    fn integer(&mut self, spec: &Spec) {
+        let (negative, magnitude) = match self.next_arg().cloned() {
+            None => (false, 0),
+            Some(arg) => {
+                let number = parse_integer(&arg);
+                self.output.errors.extend(number.error);
+                number.value
+            }
+        };
+
+        let signed = matches!(spec.conversion, 'd' | 'i');
+        let (negative, magnitude) = if signed {
+            let limit = i64::MAX as u64 + negative as u64;
+            (negative && magnitude != 0, magnitude.min(limit))
+        } else if negative {
+            (false, magnitude.wrapping_neg())
+        } else {
+            (false, magnitude)
+        };
+
+        let mut digits = match spec.conversion {
+            'o' => format!("{magnitude:o}"),
+            'x' => format!("{magnitude:x}"),
+            'X' => format!("{magnitude:X}"),
+            _ => magnitude.to_string(),
+        };
+        match spec.precision {
+            Some(0) if magnitude == 0 => digits.clear(),
+            Some(precision) if digits.len() < precision => {
+                digits.insert_str(0, &"0".repeat(precision - digits.len()));
+            }
+            _ => {}
+        }
+        if spec.flags.hash && spec.conversion == 'o' && !digits.starts_with('0') {
+            digits.insert(0, '0');
+        }
+
+        let prefix = match spec.conversion {
+            'd' | 'i' => Self::sign(spec, negative),
+            'x' if spec.flags.hash && magnitude != 0 => "0x",
+            'X' if spec.flags.hash && magnitude != 0 => "0X",
+            _ => "",
+        };
+        let zero_pad = spec.flags.zero && spec.precision.is_none();
+        self.pad(spec, prefix, &digits, zero_pad);
+    }
+
+    /// Performs a floating-point conversion.
+    fn float(&mut self, spec: &Spec) {
+        let value = match self.next_arg().cloned() {
+            None => 0.0,
+            Some(arg) => {
+                let number = parse_float(&arg);
+                self.output.errors.extend(number.error);
+                number.value
+            }
+        };
+
+        let negative = value.is_sign_negative() && !value.is_nan();
+        let magnitude = value.abs();
+        let precision = spec.precision.unwrap_or(6);
+        let hash = spec.flags.hash;
+        let body = if magnitude.is_infinite() {
+            "inf".to_owned()
+        } else if magnitude.is_nan() {
+            "nan".to_owned()
+        } else {
+            match spec.conversion.to_ascii_lowercase() {
+                'e' => exponential(magnitude, precision, hash, false),
+                'g' => general(magnitude, precision, hash),
+                _ => fixed(magnitude, precision, hash),
+            }
+        };
+        let body = if spec.conversion.is_ascii_uppercase() {
+            body.to_ascii_uppercase()
+        } else {
+            body
+        };
+
+        let zero_pad = spec.flags.zero && magnitude.is_finite();
+        self.pad(spec, Self::sign(spec, negative), &body, zero_pad);
+    }
+
+    /// Applies the format once.
+    ///
+    /// Returns `Break` if the formatting should stop.
+    fn format_once(&mut self, format: &Field) -> ControlFlow<()> {
+        let mut chars = format.value.chars();
+        let mut literal = String::new();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                literal.push(c);
+                continue;
+            }
+
+            unescape(&literal, Style::Format, &mut self.output.bytes)?;
+            literal.clear();
+
+            let start = chars.as_str();
+            let Some(spec) = parse_spec(&mut chars) else {
+                self.output.errors.push(Error::InvalidConversion {
+                    spec: format!("%{start}"),
+                    format: format.clone(),
+                });
+                return Break(());
+            };
+            match spec.conversion {
+                '%' => self.output.bytes.push(b'%'),
+                's' | 'c' => self.string(&spec),
+                'b' => self.escaped_string(&spec)?,
+                'd' | 'i' | 'o' | 'u' | 'x' | 'X' => self.integer(&spec),
+                'f' | 'F' | 'e' | 'E' | 'g' | 'G' => self.float(&spec),
+                _ => {
+                    let length = start.len() - chars.as_str().len();
+                    self.output.errors.push(Error::InvalidConversion {
+                        spec: format!("%{}", &start[..length]),
+                        format: format.clone(),
+                    });
+                    return Break(());
+                }
+            }
+        }
+        unescape(&literal, Style::Format, &mut self.output.bytes)
+    }
+}
+
+/// Applies the format to the arguments.
+///
+/// The format is reused as long as there are remaining arguments that have not
+/// been consumed. If the format runs out of arguments, conversions get zero or
+/// an empty string as the default value.
+pub fn printf(format: &Field, args: &[Field]) -> Output {
+    let mut formatter = Formatter {
+        args,
+        consumed: false,
+        output: Output::default(),
+    };
+    loop {
+        formatter.consumed = false;
+        if formatter.format_once(format).is_break() {
+            break;
+        }
+        if formatter.args.is_empty() || !formatter.consumed {
+            break;
+        }
+    }
+    formatter.output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(format: &str, args: &[&str]) -> (String, Vec<Error>) {
+        let format = Field::dummy(format);
+        let args = Field::dummies(args.iter().copied());
+        let output = printf(&format, &args);
+        (String::from_utf8(output.bytes).unwrap(), output.errors)
+    }
+
+    fn formatted(f: &str, args: &[&str]) -> String {
+        let (result, errors) = format(f, args);
+        assert_eq!(errors, [], "{f:?} {args:?}");
+        result
+    }
+
+    #[test]
+    fn literal_and_escapes() {
+        assert_eq!(formatted("foo\\tbar\\n", &[]), "foo\tbar\n");
+        assert_eq!(formatted("\\045d%%", &[]), "%d%");
+    }
+
+    #[test]
+    fn string_conversions() {
+        assert_eq!(formatted("[%s]", &["foo"]), "[foo]");
+        assert_eq!(formatted("[%5s]", &["foo"]), "[  foo]");
+        assert_eq!(formatted("[%-5s]", &["foo"]), "[foo  ]");
+        assert_eq!(formatted("[%.2s]", &["foo"]), "[fo]");
+        assert_eq!(formatted("[%c]", &["foo"]), "[f]");
+        assert_eq!(formatted("[%s|%c]", &[]), "[|]");
+    }
+
+    #[test]
+    fn b_conversion() {
+        assert_eq!(formatted("[%b]", &["a\\tb\\0101"]), "[a\tbA]");
+        assert_eq!(formatted("%s%b%s", &["x", "y\\cz", "w"]), "xy");
+    }
+
+    #[test]
+    fn b_conversion_with_non_utf8_bytes() {
+        let format = Field::dummy("[%3b]");
+        let args = Field::dummies(["\\0377"]);
+        let output = printf(&format, &args);
+        assert_eq!(output.bytes, b"[  \xFF]");
+        assert_eq!(output.errors, []);
+    }
+
+    #[test]
+    fn integer_conversions() {
+        assert_eq!(formatted("%d %i", &["42", "-7"]), "42 -7");
+        assert_eq!(formatted("%d %d %d", &["0x1F", "010", "'A"]), "31 8 65");
+        assert_eq!(
+            formatted("[%5d|%-5d|%05d]", &["1", "2", "-3"]),
+            "[    1|2    |-0003]"
+        );
+        assert_eq!(formatted("[%+d|% d|%.3d]", &["1", "2", "3"]), "[+1| 2|003]");
+        assert_eq!(
+            formatted("%o %x %X %u", &["8", "255", "255", "7"]),
+            "10 ff FF 7"
+        );
+        assert_eq!(formatted("%#o %#x %#X", &["8", "255", "0"]), "010 0xff 0");
+        assert_eq!(formatted("%u", &["-1"]), "18446744073709551615");
+        assert_eq!(formatted("[%.0d]", &["0"]), "[]");
+        assert_eq!(formatted("%d", &[]), "0");
+    }
+
+    #[test]
+    fn float_conversions() {
+        assert_eq!(formatted("%f", &["1.5"]), "1.500000");
+        assert_eq!(formatted("%.2f|%8.3f", &["3.14159", "-2"]), "3.14|  -2.000");
+        assert_eq!(formatted("%e", &["12345.678"]), "1.234568e+04");
+        assert_eq!(formatted("%.1E", &["0.00012"]), "1.2E-04");
+        assert_eq!(
+            formatted("%g %g %g", &["100000", "1000000", "0.0001"]),
+            "100000 1e+06 0.0001"
+        );
+        assert_eq!(formatted("%g %G", &["1.5", "0.00001"]), "1.5 1E-05");
+        assert_eq!(formatted("%#g", &["1"]), "1.00000");
+        assert_eq!(formatted("%f %F", &["inf", "-inf"]), "inf -INF");
+        assert_eq!(formatted("%.0f %#.0f", &["2", "2"]), "2 2.");
+        assert_eq!(formatted("%f", &["0x10"]), "16.000000");
+    }
+
+    #[test]
+    fn format_is_reused_for_remaining_arguments() {
+        assert_eq!(formatted("%s,", &["a", "b", "c"]), "a,b,c,");
+        assert_eq!(formatted("%s-%s\\n", &["1", "2", "3"]), "1-2\n3-\n");
+        assert_eq!(formatted("x\\n", &["a", "b"]), "x\n");
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        let (result, errors) = format("%d|%d|%f", &["12abc", "x", "1.5x"]);
+        assert_eq!(result, "12|0|1.000000");
+        assert_eq!(
+            errors,
+            [
+                Error::InvalidNumber(Field::dummy("12abc")),
+                Error::InvalidNumber(Field::dummy("x")),
+                Error::InvalidNumber(Field::dummy("1.5x")),
+            ]
+        );
+    }
+
+    #[test]
+    fn out_of_range_number() {
+        let (result, errors) = format("%d", &["99999999999999999999"]);
+        assert_eq!(result, i64::MAX.to_string());
+        assert_eq!(
+            errors,
+            [Error::OutOfRange(Field::dummy("99999999999999999999"))]
+        );
+    }
+
+    #[test]
+    fn invalid_conversion() {
+        let (result, errors) = format("a%kb", &["x"]);
+        assert_eq!(result, "a");
+        assert_eq!(
+            errors,
+            [Error::InvalidConversion {
+                spec: "%k".to_string(),
+                format: Field::dummy("a%kb"),
+            }]
+        );
+
+        let (result, errors) = format("a%5", &[]);
+        assert_eq!(result, "a");
+        assert_matches::assert_matches!(&errors[..], [Error::InvalidConversion { spec, .. }] => {
+            assert_eq!(spec, "%5");
+        });
+    }
+}
@@ -64,8 +64,8 @@
 //! POSIX allows the built-in to apply the `-P` option if the `-L` option is
 //! specified and `$PWD` is longer than PATH_MAX.
 //!
-//! The shell sets `$PWD` on the startup and modifies it in the cd built-in <!--
-//! TBD: link to crate::cd -->. If `$PWD` is modified or unset otherwise, the
+//! The shell sets `$PWD` on the startup and modifies it in the [cd
+//! built-in](crate::cd). If `$PWD` is modified or unset otherwise, the
 //! behavior of the cd and pwd built-ins is unspecified.
 //!
 //! # Implementation notes
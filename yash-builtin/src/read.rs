@@ -0,0 +1,272 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Read built-in
+//!
+//! The **`read`** built-in reads a line from the standard input and assigns
+//! it to variables.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! read [-r] name...
+//! ```
+//!
+//! # Semantics
+//!
+//! The built-in reads a line from the standard input and divides it into
+//! fields with the same rules as the [field
+//! splitting](yash_semantics::expansion::split) using the current value of
+//! `$IFS`. The fields are assigned to the variables named by the operands in
+//! order. If there are more fields than variables, the last variable receives
+//! all the remaining fields, including the separators between them but
+//! excluding trailing IFS whitespaces. If there are fewer fields than
+//! variables, the remaining variables are assigned empty strings.
+//!
+//! Unless the `-r` option is specified, a backslash in the input quotes the
+//! following character, which is then not regarded as a field separator. The
+//! backslash itself is removed from the result. A backslash followed by a
+//! newline is a line continuation: the built-in continues to read the next
+//! line, removing the backslash and newline.
+//!
+//! # Options
+//!
+//! The **`-r`** (**`--raw-mode`**) option disables the special meaning of
+//! backslashes.
+//!
+//! # Operands
+//!
+//! One or more operands are required. They are the names of the variables to
+//! which the fields are assigned.
+//!
+//! # Exit status
+//!
+//! The exit status is zero if a line was read successfully, or one if the end
+//! of input was reached before a newline. Even in the latter case, the
+//! variables are assigned the (possibly empty) input. The exit status is two
+//! on an error.
+//!
+//! # Errors
+//!
+//! It is an error if the standard input cannot be read or any of the variables
+//! is read-only. Variables other than read-only ones are still assigned.
+//!
+//! # Portability
+//!
+//! The long option `--raw-mode` is not portable.
+//!
+//! # Implementation notes
+//!
+//! The built-in reads the input one byte at a time so that it does not consume
+//! bytes after the newline. This ensures that the remaining input is available
+//! to subsequent commands even if the standard input is not seekable.
+
+use crate::common::print_error_message;
+use crate::common::print_message;
+use crate::common::BuiltinEnv;
+use yash_env::builtin::Result;
+use yash_env::semantics::ExitStatus;
+use yash_env::semantics::Field;
+use yash_env::Env;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+
+/// Parsed command line arguments of the read built-in
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Command {
+    /// Whether the `-r` option is specified
+    pub is_raw: bool,
+    /// Names of the variables to be assigned
+    pub variables: Vec<Field>,
+}
+
+pub mod assigning;
+pub mod input;
+pub mod syntax;
+
+/// Entry point for executing the `read` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let command = match syntax::parse(env, args) {
+        Ok(command) => command,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+
+    let (text, newline) = match input::read(env, command.is_raw).await {
+        Ok(input) => input,
+        Err(errno) => {
+            let location = env.builtin_name().origin.clone();
+            let message = Message {
+                r#type: AnnotationType::Error,
+                title: "cannot read the standard input".into(),
+                annotations: vec![Annotation::new(
+                    AnnotationType::Error,
+                    errno.desc().into(),
+                    &location,
+                )],
+            };
+            return print_error_message(env, message).await;
+        }
+    };
+
+    let errors = assigning::assign(env, &text, command.variables);
+    let mut divert = std::ops::ControlFlow::Continue(());
+    for error in &errors {
+        let message = Message {
+            r#type: AnnotationType::Error,
+            title: format!("cannot assign to read-only variable `{}`", error.name).into(),
+            annotations: vec![
+                Annotation::new(
+                    AnnotationType::Error,
+                    error.to_string().into(),
+                    error.new_value.last_assigned_location.as_ref().unwrap(),
+                ),
+                Annotation::new(
+                    AnnotationType::Info,
+                    "the variable was made read-only here".into(),
+                    &error.read_only_location,
+                ),
+            ],
+        };
+        divert = print_message(env, message).await;
+    }
+
+    let exit_status = if !errors.is_empty() {
+        ExitStatus::ERROR
+    } else if newline {
+        ExitStatus::SUCCESS
+    } else {
+        ExitStatus::FAILURE
+    };
+    Result::with_exit_status_and_divert(exit_status, divert)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use std::cell::RefCell;
+    use yash_env::stack::Frame;
+    use yash_env::system::r#virtual::FileBody;
+    use yash_env::system::r#virtual::SystemState;
+    use yash_env::variable::Scope::Global;
+    use yash_env::variable::Value;
+    use yash_env::variable::Variable;
+    use yash_syntax::source::Location;
+
+    pub(crate) fn set_stdin<B: Into<Vec<u8>>>(state: &RefCell<SystemState>, bytes: B) {
+        state
+            .borrow_mut()
+            .file_system
+            .get("/dev/stdin")
+            .unwrap()
+            .borrow_mut()
+            .body = FileBody::new(bytes);
+    }
+
+    fn get(env: &Env, name: &str) -> Option<Value> {
+        env.variables.get(name).and_then(|v| v.value.clone())
+    }
+
+    #[test]
+    fn reading_lines_into_variables() {
+        in_virtual_system(|mut env, state| async move {
+            set_stdin(&state, "  foo  bar  baz  \n1\\ 2 3\n");
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("read"),
+                is_special: false,
+            });
+
+            let args = Field::dummies(["a", "b"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::default());
+            assert_eq!(get(&env, "a"), Some(Value::scalar("foo")));
+            assert_eq!(get(&env, "b"), Some(Value::scalar("bar  baz")));
+
+            let args = Field::dummies(["a", "b", "c"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::default());
+            assert_eq!(get(&env, "a"), Some(Value::scalar("1 2")));
+            assert_eq!(get(&env, "b"), Some(Value::scalar("3")));
+            assert_eq!(get(&env, "c"), Some(Value::scalar("")));
+        })
+    }
+
+    #[test]
+    fn end_of_input_without_newline() {
+        in_virtual_system(|mut env, state| async move {
+            set_stdin(&state, "foo");
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("read"),
+                is_special: false,
+            });
+
+            let args = Field::dummies(["a"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::FAILURE));
+            assert_eq!(get(&env, "a"), Some(Value::scalar("foo")));
+
+            let args = Field::dummies(["a"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::FAILURE));
+            assert_eq!(get(&env, "a"), Some(Value::scalar("")));
+        })
+    }
+
+    #[test]
+    fn raw_mode_keeps_backslashes() {
+        in_virtual_system(|mut env, state| async move {
+            set_stdin(&state, "a\\ b\\\n");
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("read"),
+                is_special: false,
+            });
+
+            let args = Field::dummies(["-r", "x", "y"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::default());
+            assert_eq!(get(&env, "x"), Some(Value::scalar("a\\")));
+            assert_eq!(get(&env, "y"), Some(Value::scalar("b\\")));
+        })
+    }
+
+    #[test]
+    fn read_only_variable() {
+        in_virtual_system(|mut env, state| async move {
+            set_stdin(&state, "1 2\n");
+            let var = Variable::new("x").make_read_only(Location::dummy("readonly"));
+            env.variables
+                .assign(Global, "a".to_string(), var.clone())
+                .unwrap();
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("read"),
+                is_special: false,
+            });
+
+            let args = Field::dummies(["a", "b"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::ERROR));
+            assert_eq!(env.variables.get("a"), Some(&var));
+            assert_eq!(get(&env, "b"), Some(Value::scalar("2")));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("read-only"), "{stderr:?}")
+            });
+        })
+    }
+}
@@ -0,0 +1,164 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Assigning the input to variables
+//!
+//! The [`split`] function divides the input into fields with the rules of
+//! [field splitting](yash_semantics::expansion::split). The [`assign`]
+//! function assigns the fields to variables.
+
+use yash_env::semantics::Field;
+use yash_env::variable::ReadOnlyError;
+use yash_env::variable::Scope::Global;
+use yash_env::variable::Value::Scalar;
+use yash_env::variable::Variable;
+use yash_env::Env;
+use yash_semantics::expansion::attr::AttrChar;
+use yash_semantics::expansion::split::Class;
+use yash_semantics::expansion::split::Ifs;
+
+fn to_string(chars: &[AttrChar]) -> String {
+    chars.iter().map(|c| c.value).collect()
+}
+
+/// Splits the input into the given number of fields.
+///
+/// If the input contains more fields than `count`, the last field contains
+/// the rest of the input, including separators, except for trailing IFS
+/// whitespaces. If the input contains fewer fields, the result is padded
+/// with empty strings.
+///
+/// This function **panics** if `count` is zero.
+#[must_use]
+pub fn split(text: &[AttrChar], ifs: &Ifs, count: usize) -> Vec<String> {
+    assert_ne!(count, 0, "at least one field is required");
+
+    let ranges: Vec<_> = ifs.ranges(text.iter().copied()).collect();
+    let mut fields: Vec<String> = if ranges.len() <= count {
+        ranges
+            .iter()
+            .map(|range| to_string(&text[range.clone()]))
+            .collect()
+    } else {
+        let start = ranges[count - 1].start;
+        let mut end = text.len();
+        while end > start
+            && !text[end - 1].is_quoted
+            && ifs.classify(text[end - 1].value) == Class::IfsWhitespace
+        {
+            end -= 1;
+        }
+
+        let mut fields: Vec<String> = ranges[..count - 1]
+            .iter()
+            .map(|range| to_string(&text[range.clone()]))
+            .collect();
+        fields.push(to_string(&text[start..end]));
+        fields
+    };
+    fields.resize_with(count, String::new);
+    fields
+}
+
+/// Assigns the input to the variables.
+///
+/// The input is [split](split) with `$IFS` and the resulting fields are
+/// assigned to the variables. Errors are returned for read-only variables,
+/// but the other variables are still assigned.
+pub fn assign(env: &mut Env, text: &[AttrChar], variables: Vec<Field>) -> Vec<ReadOnlyError> {
+    #[rustfmt::skip]
+    let values = match env.variables.get("IFS") {
+        Some(&Variable { value: Some(Scalar(ref ifs)), .. }) => {
+            split(text, &Ifs::new(ifs), variables.len())
+        }
+        _ => split(text, &Ifs::default(), variables.len()),
+    };
+
+    let mut errors = Vec::new();
+    for (name, value) in variables.into_iter().zip(values) {
+        let variable = Variable::new(value).set_assigned_location(name.origin);
+        if let Err(error) = env.assign_variable(Global, name.value, variable) {
+            errors.push(error);
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yash_env::variable::Value;
+    use yash_semantics::expansion::attr::Origin;
+
+    fn attr_chars(s: &str) -> Vec<AttrChar> {
+        s.chars()
+            .map(|value| AttrChar {
+                value,
+                origin: Origin::SoftExpansion,
+                is_quoted: false,
+                is_quoting: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fewer_fields_than_count() {
+        let fields = split(&attr_chars("  foo  "), &Ifs::default(), 3);
+        assert_eq!(fields, ["foo", "", ""]);
+    }
+
+    #[test]
+    fn as_many_fields_as_count() {
+        let fields = split(&attr_chars(" foo bar\tbaz "), &Ifs::default(), 3);
+        assert_eq!(fields, ["foo", "bar", "baz"]);
+    }
+
+    #[test]
+    fn last_field_takes_rest_of_input() {
+        let fields = split(&attr_chars(" foo  bar  baz  "), &Ifs::default(), 2);
+        assert_eq!(fields, ["foo", "bar  baz"]);
+
+        let fields = split(&attr_chars("a:b::c:"), &Ifs::new(":"), 2);
+        assert_eq!(fields, ["a", "b::c:"]);
+    }
+
+    #[test]
+    fn quoted_characters_are_not_separators() {
+        let mut text = attr_chars("a b c ");
+        text[1].is_quoted = true;
+        text[5].is_quoted = true;
+        let fields = split(&text, &Ifs::default(), 1);
+        assert_eq!(fields, ["a b c "]);
+        let fields = split(&text, &Ifs::default(), 3);
+        assert_eq!(fields, ["a b", "c ", ""]);
+    }
+
+    #[test]
+    fn assigning_with_ifs() {
+        let mut env = Env::new_virtual();
+        env.variables
+            .assign(Global, "IFS".to_string(), Variable::new("-"))
+            .unwrap();
+        let variables = Field::dummies(["a", "b"]);
+        let errors = assign(&mut env, &attr_chars("1 - 2-3"), variables);
+        assert_eq!(errors, []);
+        let a = env.variables.get("a").unwrap();
+        assert_eq!(a.value, Some(Value::scalar("1 ")));
+        assert_eq!(a.last_assigned_location, Some(Field::dummy("a").origin));
+        let b = env.variables.get("b").unwrap();
+        assert_eq!(b.value, Some(Value::scalar(" 2-3")));
+    }
+}
@@ -0,0 +1,170 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Reading input for the read built-in
+//!
+//! The [`read`] function reads a line from the standard input. Unless in the
+//! raw mode, a backslash quotes the following character, and a
+//! backslash-newline pair continues the line.
+
+use std::slice::from_mut;
+use yash_env::io::Fd;
+use yash_env::system::Errno;
+use yash_env::Env;
+use yash_semantics::expansion::attr::AttrChar;
+use yash_semantics::expansion::attr::Origin;
+
+/// Reads a physical line from the standard input.
+///
+/// The result does not include the terminating newline. The second value of
+/// the tuple is true if the line was terminated by a newline, and false if
+/// the end of input was reached.
+async fn read_line(env: &mut Env) -> Result<(String, bool), Errno> {
+    // We read one byte at a time so that we do not consume bytes after the
+    // newline, which should remain available to subsequent commands.
+    let mut bytes = Vec::new();
+    let newline = loop {
+        let mut byte = 0;
+        match env
+            .system
+            .read_async(Fd::STDIN, from_mut(&mut byte))
+            .await?
+        {
+            0 => break false,
+            _ if byte == b'\n' => break true,
+            _ => bytes.push(byte),
+        }
+    };
+
+    // TODO Maybe we should report invalid UTF-8 bytes rather than ignoring them
+    let line = String::from_utf8(bytes)
+        .unwrap_or_else(|e| String::from_utf8_lossy(&e.into_bytes()).into());
+    Ok((line, newline))
+}
+
+fn attr_char(value: char, is_quoted: bool) -> AttrChar {
+    AttrChar {
+        value,
+        origin: Origin::SoftExpansion,
+        is_quoted,
+        is_quoting: false,
+    }
+}
+
+/// Reads a logical line from the standard input.
+///
+/// The result does not include the terminating newline or backslashes that
+/// quote other characters. Characters quoted by a backslash have the
+/// `is_quoted` flag set so that they are not subject to field splitting.
+///
+/// The second value of the tuple is true if the line was terminated by a
+/// newline, and false if the end of input was reached.
+pub async fn read(env: &mut Env, is_raw: bool) -> Result<(Vec<AttrChar>, bool), Errno> {
+    let mut result = Vec::new();
+    loop {
+        let (line, newline) = read_line(env).await?;
+        let mut chars = line.chars();
+        let mut continued = false;
+        while let Some(c) = chars.next() {
+            if !is_raw && c == '\\' {
+                match chars.next() {
+                    Some(c) => result.push(attr_char(c, true)),
+                    None => continued = newline,
+                }
+            } else {
+                result.push(attr_char(c, false));
+            }
+        }
+
+        // TODO Print $PS2 if interactive
+        if !continued {
+            return Ok((result, newline));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read::tests::set_stdin;
+    use crate::tests::in_virtual_system;
+
+    fn to_string(chars: &[AttrChar]) -> String {
+        chars.iter().map(|c| c.value).collect()
+    }
+
+    #[test]
+    fn empty_input() {
+        in_virtual_system(|mut env, _state| async move {
+            let (chars, newline) = read(&mut env, false).await.unwrap();
+            assert_eq!(chars, []);
+            assert!(!newline);
+        })
+    }
+
+    #[test]
+    fn reading_one_line_at_a_time() {
+        in_virtual_system(|mut env, state| async move {
+            set_stdin(&state, "foo\nbar");
+            let (chars, newline) = read(&mut env, false).await.unwrap();
+            assert_eq!(to_string(&chars), "foo");
+            assert!(newline);
+            let (chars, newline) = read(&mut env, false).await.unwrap();
+            assert_eq!(to_string(&chars), "bar");
+            assert!(!newline);
+        })
+    }
+
+    #[test]
+    fn backslash_quotes_next_character() {
+        in_virtual_system(|mut env, state| async move {
+            set_stdin(&state, "a\\ b\\\\\n");
+            let (chars, _) = read(&mut env, false).await.unwrap();
+            assert_eq!(
+                chars,
+                [
+                    attr_char('a', false),
+                    attr_char(' ', true),
+                    attr_char('b', false),
+                    attr_char('\\', true),
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn line_continuation() {
+        in_virtual_system(|mut env, state| async move {
+            set_stdin(&state, "foo\\\nbar\\\n\nbaz\n");
+            let (chars, newline) = read(&mut env, false).await.unwrap();
+            assert_eq!(to_string(&chars), "foobar");
+            assert!(newline);
+            let (chars, _) = read(&mut env, false).await.unwrap();
+            assert_eq!(to_string(&chars), "baz");
+        })
+    }
+
+    #[test]
+    fn raw_mode() {
+        in_virtual_system(|mut env, state| async move {
+            set_stdin(&state, "a\\ b\\\nc\n");
+            let (chars, newline) = read(&mut env, true).await.unwrap();
+            assert_eq!(to_string(&chars), "a\\ b\\");
+            assert!(chars.iter().all(|c| !c.is_quoted));
+            assert!(newline);
+        })
+    }
+}
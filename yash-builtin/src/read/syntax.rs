@@ -0,0 +1,134 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Command line argument parser for the read built-in
+
+use super::Command;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax::OptionSpec;
+use crate::common::BuiltinEnv;
+use std::borrow::Cow;
+use thiserror::Error;
+use yash_env::semantics::Field;
+use yash_env::Env;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::MessageBase;
+use yash_syntax::source::Location;
+
+/// Error in parsing command line arguments
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// An error occurred in the common parser.
+    #[error(transparent)]
+    CommonError(#[from] crate::common::syntax::Error<'static>),
+
+    /// No variable names are given.
+    #[error("missing operand")]
+    MissingOperand {
+        /// Location of the built-in name
+        location: Location,
+    },
+}
+
+impl MessageBase for Error {
+    fn message_title(&self) -> Cow<str> {
+        self.to_string().into()
+    }
+
+    fn main_annotation(&self) -> Annotation<'_> {
+        use Error::*;
+        match self {
+            CommonError(e) => e.main_annotation(),
+            MissingOperand { location } => Annotation::new(
+                AnnotationType::Error,
+                "at least one variable name is required".into(),
+                location,
+            ),
+        }
+    }
+}
+
+/// Result of parsing command line arguments
+pub type Result = std::result::Result<Command, Error>;
+
+const OPTION_SPECS: &[OptionSpec] = &[OptionSpec::new().short('r').long("raw-mode")];
+
+/// Parses command line arguments for the read built-in.
+pub fn parse(env: &Env, args: Vec<Field>) -> Result {
+    let (options, variables) = parse_arguments(OPTION_SPECS, Mode::with_env(env), args)?;
+
+    if variables.is_empty() {
+        let location = env.builtin_name().origin.clone();
+        return Err(Error::MissingOperand { location });
+    }
+
+    let is_raw = !options.is_empty();
+    Ok(Command { is_raw, variables })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yash_env::stack::Frame;
+
+    #[test]
+    fn variables_without_options() {
+        let env = Env::new_virtual();
+        let args = Field::dummies(["foo", "bar"]);
+        let result = parse(&env, args.clone());
+        assert_eq!(
+            result,
+            Ok(Command {
+                is_raw: false,
+                variables: args
+            })
+        );
+    }
+
+    #[test]
+    fn raw_mode() {
+        let env = Env::new_virtual();
+        let args = Field::dummies(["-r", "foo"]);
+        let variables = args[1..].to_vec();
+        let result = parse(&env, args);
+        assert_eq!(
+            result,
+            Ok(Command {
+                is_raw: true,
+                variables
+            })
+        );
+    }
+
+    #[test]
+    fn missing_operand() {
+        let mut env = Env::new_virtual();
+        let env = env.push_frame(Frame::Builtin {
+            name: Field::dummy("read"),
+            is_special: false,
+        });
+        let result = parse(&env, Field::dummies(["-r"]));
+        assert_eq!(
+            result,
+            Err(Error::MissingOperand {
+                location: Location::dummy("read")
+            })
+        );
+    }
+}
@@ -300,6 +300,7 @@ mod tests {
             assert_eq!(
                 stdout,
                 "allexport        on
+braceexpand      off
 clobber          on
 cmdline          off
 errexit          off
@@ -0,0 +1,239 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Shift built-in
+//!
+//! The **`shift`** built-in removes some positional parameters.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! shift [n]
+//! ```
+//!
+//! # Semantics
+//!
+//! The built-in removes the first *n* positional parameters from the list of
+//! positional parameters. The remaining parameters are renumbered so that
+//! `$(n+1)` becomes `$1`, `$(n+2)` becomes `$2`, and so on.
+//!
+//! # Options
+//!
+//! None.
+//!
+//! # Operands
+//!
+//! The optional operand *n* is a non-negative decimal integer that specifies
+//! the number of positional parameters to remove. The default is 1.
+//!
+//! # Exit status
+//!
+//! Zero if successful, non-zero otherwise.
+//!
+//! # Errors
+//!
+//! It is an error if *n* is not a valid non-negative integer or greater than
+//! the number of positional parameters. In that case, the positional
+//! parameters are not modified. Since the shift built-in is a special
+//! built-in, the error interrupts the shell if it is not interactive.
+//!
+//! # Portability
+//!
+//! POSIX does not require the shift built-in to conform to the Utility Syntax
+//! Guidelines, which means portable scripts cannot use any options or the `--`
+//! separator for the built-in.
+//!
+//! The behavior is unspecified in POSIX if *n* is greater than the number of
+//! positional parameters. Some other shells silently ignore such a case.
+
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax_error;
+use crate::common::BuiltinEnv;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::variable::Value::Array;
+use yash_env::Env;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+use yash_syntax::source::Location;
+
+fn too_large_count_message(label: String, location: &Location) -> Message<'_> {
+    Message {
+        r#type: AnnotationType::Error,
+        title: "cannot shift positional parameters".into(),
+        annotations: vec![Annotation::new(
+            AnnotationType::Error,
+            label.into(),
+            location,
+        )],
+    }
+}
+
+/// Entry point for executing the `shift` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let mut operands = match parse_arguments(&[], Mode::with_env(env), args) {
+        Ok((_options, operands)) => operands,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+    if operands.len() > 1 {
+        return syntax_error(env, "too many operands", &operands[1].origin).await;
+    }
+
+    let count = match operands.pop() {
+        None => 1,
+        Some(operand) => match operand.value.parse::<usize>() {
+            Ok(count) => count,
+            Err(error) => return syntax_error(env, &error.to_string(), &operand.origin).await,
+        },
+    };
+
+    let location = env.builtin_name().origin.clone();
+    let params = env.variables.positional_params_mut();
+    let values = match &mut params.value {
+        Some(Array(values)) => values,
+        _ => unreachable!("positional parameters should be an array"),
+    };
+    if count > values.len() {
+        let label = format!(
+            "cannot shift {} parameter(s) while there are only {}",
+            count,
+            values.len()
+        );
+        let message = too_large_count_message(label, &location);
+        return print_failure_message(env, message).await;
+    }
+
+    values.drain(..count);
+    params.last_assigned_location = Some(location);
+    Result::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use std::ops::ControlFlow::Break;
+    use yash_env::semantics::Divert;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
+    use yash_env::variable::Value;
+
+    fn set_positional_params(env: &mut Env, values: &[&str]) {
+        env.variables.positional_params_mut().value = Some(Value::array(values.iter().copied()));
+    }
+
+    #[test]
+    fn shifting_one_parameter_by_default() {
+        let mut env = Env::new_virtual();
+        let mut env = env.push_frame(Frame::Builtin {
+            name: Field::dummy("shift"),
+            is_special: true,
+        });
+        set_positional_params(&mut env, &["a", "b", "c"]);
+
+        let result = main(&mut env, vec![]).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        let params = env.variables.positional_params();
+        assert_eq!(params.value, Some(Value::array(["b", "c"])));
+        assert_eq!(
+            params.last_assigned_location,
+            Some(Field::dummy("shift").origin)
+        );
+    }
+
+    #[test]
+    fn shifting_some_parameters() {
+        let mut env = Env::new_virtual();
+        let mut env = env.push_frame(Frame::Builtin {
+            name: Field::dummy("shift"),
+            is_special: true,
+        });
+        set_positional_params(&mut env, &["a", "b", "c"]);
+
+        let result = main(&mut env, Field::dummies(["2"]))
+            .now_or_never()
+            .unwrap();
+        assert_eq!(result, Result::default());
+        let params = env.variables.positional_params();
+        assert_eq!(params.value, Some(Value::array(["c"])));
+
+        let result = main(&mut env, Field::dummies(["1"]))
+            .now_or_never()
+            .unwrap();
+        assert_eq!(result, Result::default());
+        let params = env.variables.positional_params();
+        assert_eq!(params.value, Some(Value::array([] as [&str; 0])));
+    }
+
+    #[test]
+    fn shifting_zero_parameters() {
+        let mut env = Env::new_virtual();
+        let mut env = env.push_frame(Frame::Builtin {
+            name: Field::dummy("shift"),
+            is_special: true,
+        });
+
+        let result = main(&mut env, Field::dummies(["0"]))
+            .now_or_never()
+            .unwrap();
+        assert_eq!(result, Result::default());
+    }
+
+    #[test]
+    fn shifting_too_many_parameters() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("shift"),
+                is_special: true,
+            });
+            set_positional_params(&mut env, &["a", "b"]);
+
+            let result = main(&mut env, Field::dummies(["3"])).await;
+            assert_eq!(
+                result,
+                Result::with_exit_status_and_divert(
+                    ExitStatus::FAILURE,
+                    Break(Divert::Interrupt(None))
+                )
+            );
+            let params = env.variables.positional_params();
+            assert_eq!(params.value, Some(Value::array(["a", "b"])));
+            assert_stderr(&state, |stderr| assert_ne!(stderr, ""));
+        })
+    }
+
+    #[test]
+    fn invalid_operand() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("shift"),
+                is_special: true,
+            });
+
+            let result = main(&mut env, Field::dummies(["-1"])).await;
+            assert_eq!(result.exit_status(), ExitStatus::ERROR);
+            assert_stderr(&state, |stderr| assert_ne!(stderr, ""));
+        })
+    }
+}
@@ -0,0 +1,369 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Dot built-in
+//!
+//! The **`.`** (dot) built-in reads and executes commands from a file in the
+//! current shell environment. The built-in is also available as **`source`**.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! . file
+//! ```
+//!
+//! # Semantics
+//!
+//! The dot built-in reads the specified file, parses its content as commands,
+//! and executes them in the current shell environment.
+//!
+//! If the *file* operand does not contain a slash, the built-in searches
+//! `$PATH` for a readable regular file having the name. Otherwise, the operand
+//! is used as a pathname to the file.
+//!
+//! The `return` built-in executed in the file makes the dot built-in return.
+//!
+//! # Options
+//!
+//! None.
+//!
+//! # Operands
+//!
+//! The ***file*** operand names the file to execute.
+//!
+//! # Exit status
+//!
+//! The exit status of the built-in is that of the last command executed in the
+//! file, or zero if the file contains no commands.
+//!
+//! # Errors
+//!
+//! It is an error if the file is not found or cannot be opened. Since the dot
+//! built-in is a special built-in, the error interrupts the shell if it is not
+//! interactive.
+//!
+//! # Portability
+//!
+//! POSIX does not require the dot built-in to conform to the Utility Syntax
+//! Guidelines, which means portable scripts cannot use any options or the `--`
+//! separator for the built-in.
+//!
+//! The `source` built-in and passing additional operands to the built-in are
+//! non-standard. This implementation does not support additional operands.
+//!
+//! # Implementation notes
+//!
+//! The file is searched for with [`search_path`]. The file content is parsed
+//! with a [`Lexer`] whose source is [`Source::DotScript`]. While executing the
+//! file, [`Frame::DotScript`] is pushed to the stack.
+
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax_error;
+use crate::common::BuiltinEnv;
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::num::NonZeroU64;
+use std::ops::ControlFlow::{Break, Continue};
+use std::rc::Rc;
+use yash_env::builtin::Result;
+use yash_env::input::FdReader;
+use yash_env::io::Fd;
+use yash_env::option::State::Off;
+use yash_env::semantics::Divert;
+use yash_env::semantics::Field;
+use yash_env::stack::Frame;
+use yash_env::system::AtFlags;
+use yash_env::system::Errno;
+use yash_env::system::Mode as FileMode;
+use yash_env::system::OFlag;
+use yash_env::system::SFlag;
+use yash_env::system::SystemEx;
+use yash_env::system::AT_FDCWD;
+use yash_env::variable::Variable;
+use yash_env::Env;
+use yash_env::System;
+use yash_semantics::command_search::search_path;
+use yash_semantics::command_search::PathEnv;
+use yash_semantics::ReadEvalLoop;
+use yash_syntax::parser::lex::Lexer;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+use yash_syntax::source::Source;
+
+/// Environment for searching `$PATH` for a script file
+///
+/// Unlike external utilities, a script file for the dot built-in does not have
+/// to be executable. This wrapper makes [`search_path`] accept any regular
+/// file.
+struct ScriptSearchEnv<'a>(&'a Env);
+
+impl PathEnv for ScriptSearchEnv<'_> {
+    fn path(&self) -> Option<&Variable> {
+        self.0.variables.get("PATH")
+    }
+    fn is_executable_file(&self, path: &CStr) -> bool {
+        match self.0.system.fstatat(AT_FDCWD, path, AtFlags::empty()) {
+            Ok(stat) => SFlag::from_bits_truncate(stat.st_mode) & SFlag::S_IFMT == SFlag::S_IFREG,
+            Err(_) => false,
+        }
+    }
+}
+
+/// Opens the script file and moves the FD to
+/// [`MIN_INTERNAL_FD`](yash_env::io::MIN_INTERNAL_FD) or above.
+fn open_file(env: &mut Env, path: &CStr) -> std::result::Result<Fd, Errno> {
+    let fd = env
+        .system
+        .open(path, OFlag::O_RDONLY | OFlag::O_CLOEXEC, FileMode::empty())?;
+    env.system.move_fd_internal(fd)
+}
+
+/// Entry point for executing the `.` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let mode = Mode::with_env(env);
+    let mut operands = match parse_arguments(&[], mode, args) {
+        Ok((_, operands)) => operands,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+    match operands.len() {
+        0 => {
+            let location = env.builtin_name().origin.clone();
+            return syntax_error(env, "missing file operand", &location).await;
+        }
+        1 => (),
+        _ => return syntax_error(env, "unexpected operand", &operands[1].origin).await,
+    }
+    let name = operands.pop().unwrap();
+
+    let path = if name.value.contains('/') {
+        CString::new(name.value.as_str()).ok()
+    } else {
+        search_path(&mut ScriptSearchEnv(env), &name.value)
+    };
+    let Some(path) = path else {
+        let message = Message {
+            r#type: AnnotationType::Error,
+            title: format!("cannot find file {:?}", name.value).into(),
+            annotations: vec![Annotation::new(
+                AnnotationType::Error,
+                "no such file in $PATH".into(),
+                &name.origin,
+            )],
+        };
+        return print_failure_message(env, message).await;
+    };
+
+    let fd = match open_file(env, &path) {
+        Ok(fd) => fd,
+        Err(errno) => {
+            let message = Message {
+                r#type: AnnotationType::Error,
+                title: format!("cannot open file {:?}", name.value).into(),
+                annotations: vec![Annotation::new(
+                    AnnotationType::Error,
+                    errno.desc().into(),
+                    &name.origin,
+                )],
+            };
+            return print_failure_message(env, message).await;
+        }
+    };
+
+    let mut input = Box::new(FdReader::new(fd, env.system.clone()));
+    let echo = Rc::new(Cell::new(Off));
+    input.set_echo(Some(Rc::clone(&echo)));
+    let line = NonZeroU64::new(1).unwrap();
+    let source = Source::DotScript {
+        name: name.value,
+        origin: name.origin,
+    };
+    let mut lexer = Lexer::new(input, line, source);
+
+    let divert = {
+        let mut env = env.push_frame(Frame::DotScript);
+        let mut rel = ReadEvalLoop::new(&mut env, &mut lexer);
+        rel.set_verbose(Some(echo));
+        rel.run().await
+    };
+    drop(lexer);
+    let _ = env.system.close(fd);
+
+    let divert = match divert {
+        Break(Divert::Return(exit_status)) => {
+            if let Some(exit_status) = exit_status {
+                env.exit_status = exit_status;
+            }
+            Continue(())
+        }
+        other => other,
+    };
+    Result::with_exit_status_and_divert(env.exit_status, divert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use std::cell::RefCell;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::system::r#virtual::INode;
+    use yash_env::system::r#virtual::SystemState;
+    use yash_env::variable::Scope;
+    use yash_env::variable::Value;
+    use yash_env::VirtualSystem;
+
+    fn save_file(state: &RefCell<SystemState>, path: &str, content: &str) {
+        let file = Rc::new(RefCell::new(INode::new(content.as_bytes())));
+        state.borrow_mut().file_system.save(path, file).unwrap();
+    }
+
+    fn env_with_file(path: &str, content: &str) -> Env {
+        let system = VirtualSystem::new();
+        save_file(&system.state, path, content);
+        let mut env = Env::with_system(Box::new(system));
+        env.builtins.extend(crate::BUILTINS.iter().cloned());
+        env
+    }
+
+    #[test]
+    fn executes_file_in_current_environment() {
+        let mut env = env_with_file("/foo/lib.sh", "set a b c\nreturn -n 4\n");
+        let args = Field::dummies(["/foo/lib.sh"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus(4)));
+        assert_eq!(
+            env.variables.positional_params().value,
+            Some(Value::array(["a", "b", "c"]))
+        );
+        assert_eq!(env.stack[..], []);
+    }
+
+    #[test]
+    fn empty_file() {
+        let mut env = env_with_file("/foo/empty", "");
+        env.exit_status = ExitStatus(17);
+        let args = Field::dummies(["/foo/empty"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus::SUCCESS));
+    }
+
+    #[test]
+    fn return_from_file() {
+        let mut env = env_with_file("/foo/lib.sh", "return 7\nset x\n");
+        let args = Field::dummies(["/foo/lib.sh"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus(7)));
+        assert_eq!(
+            env.variables.positional_params().value,
+            Some(Value::Array(vec![]))
+        );
+    }
+
+    #[test]
+    fn return_without_exit_status() {
+        let mut env = env_with_file("/foo/lib.sh", "return -n 3\nreturn\n");
+        let args = Field::dummies(["/foo/lib.sh"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus(3)));
+    }
+
+    #[test]
+    fn searches_path_for_name_without_slash() {
+        let mut env = env_with_file("/dir2/lib.sh", "return -n 11\n");
+        env.variables
+            .assign(
+                Scope::Global,
+                "PATH".to_string(),
+                Variable::new("/dir1:/dir2"),
+            )
+            .unwrap();
+        let args = Field::dummies(["lib.sh"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::new(ExitStatus(11)));
+    }
+
+    #[test]
+    fn file_not_found_in_path() {
+        in_virtual_system(|mut env, state| async move {
+            env.variables
+                .assign(Scope::Global, "PATH".to_string(), Variable::new("/dir"))
+                .unwrap();
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("."),
+                is_special: true,
+            });
+            let args = Field::dummies(["lib.sh"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_eq!(result.divert(), Break(Divert::Interrupt(None)));
+            assert_stderr(&state, |stderr| assert_ne!(stderr, ""));
+        })
+    }
+
+    #[test]
+    fn file_cannot_be_opened() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("."),
+                is_special: true,
+            });
+            let args = Field::dummies(["/no/such/file"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_eq!(result.divert(), Break(Divert::Interrupt(None)));
+            assert_stderr(&state, |stderr| assert_ne!(stderr, ""));
+        })
+    }
+
+    #[test]
+    fn missing_operand() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("."),
+                is_special: true,
+            });
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result.exit_status(), ExitStatus::ERROR);
+            assert_stderr(&state, |stderr| assert_ne!(stderr, ""));
+        })
+    }
+
+    #[test]
+    fn syntax_error_in_file_refers_to_file() {
+        in_virtual_system(|mut env, state| async move {
+            save_file(&state, "/foo/bad.sh", "return -n 0\nfi\n");
+            let args = Field::dummies(["/foo/bad.sh"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(
+                result.divert(),
+                Break(Divert::Interrupt(Some(ExitStatus::ERROR)))
+            );
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("/foo/bad.sh:2:1"), "{stderr:?}");
+                assert!(stderr.contains("script was sourced here"), "{stderr:?}");
+            });
+        })
+    }
+}
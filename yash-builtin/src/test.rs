@@ -0,0 +1,242 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Test built-in
+//!
+//! The **`test`** built-in evaluates a conditional expression. The **`[`**
+//! built-in is equivalent except that it requires a closing `]` operand.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! test [expression...]
+//! [ [expression...] ]
+//! ```
+//!
+//! # Semantics
+//!
+//! The built-in evaluates the expression given by the operands and returns
+//! the result as the exit status. How the operands are parsed depends on the
+//! number of operands:
+//!
+//! - With no operands, the expression is false.
+//! - With one operand, the expression is true if the operand is non-empty.
+//! - With two operands, the first operand must be `!` or a unary operator.
+//!   `!` negates the result of the one-operand test of the second operand.
+//! - With three operands, if the second operand is a binary operator, the
+//!   first and third operands are its operands. Otherwise, if the first
+//!   operand is `!`, it negates the two-operand test of the others. If the
+//!   first and third operands are `(` and `)`, the result is the one-operand
+//!   test of the second operand.
+//! - With four operands, if the first operand is `!`, it negates the
+//!   three-operand test of the others. If the first and fourth operands are
+//!   `(` and `)`, the result is the two-operand test of the others.
+//! - Otherwise, the operands are parsed as a full expression in which `!`
+//!   negates, `-a` and `-o` combine, and parentheses group primaries. See
+//!   the [`syntax`] module for the grammar.
+//!
+//! # Primaries
+//!
+//! ## Unary primaries
+//!
+//! - `-b file`: `file` is a block special file.
+//! - `-c file`: `file` is a character special file.
+//! - `-d file`: `file` is a directory.
+//! - `-e file`: `file` exists.
+//! - `-f file`: `file` is a regular file.
+//! - `-g file`: `file` has the set-group-ID bit.
+//! - `-h file` or `-L file`: `file` is a symbolic link.
+//! - `-k file`: `file` has the sticky bit.
+//! - `-n string`: `string` is not empty.
+//! - `-p file`: `file` is a FIFO.
+//! - `-r file`: `file` is readable.
+//! - `-S file`: `file` is a socket.
+//! - `-s file`: `file` has a size greater than zero.
+//! - `-t fd`: file descriptor `fd` is associated with a terminal.
+//! - `-u file`: `file` has the set-user-ID bit.
+//! - `-w file`: `file` is writable.
+//! - `-x file`: `file` is executable (or searchable if a directory).
+//! - `-z string`: `string` is empty.
+//!
+//! Except for `-h` and `-L`, symbolic links are followed.
+//!
+//! ## Binary primaries
+//!
+//! - `string1 = string2`: the strings are identical.
+//! - `string1 != string2`: the strings are different.
+//! - `string1 < string2`: `string1` sorts before `string2`.
+//! - `string1 > string2`: `string1` sorts after `string2`.
+//! - `int1 -eq int2`: the integers are equal.
+//! - `int1 -ne int2`: the integers are different.
+//! - `int1 -gt int2`: `int1` is greater than `int2`.
+//! - `int1 -ge int2`: `int1` is greater than or equal to `int2`.
+//! - `int1 -lt int2`: `int1` is less than `int2`.
+//! - `int1 -le int2`: `int1` is less than or equal to `int2`.
+//! - `file1 -ef file2`: the files are the same file.
+//! - `file1 -nt file2`: `file1` is newer than `file2`, or `file1` exists and
+//!   `file2` does not.
+//! - `file1 -ot file2`: `file1` is older than `file2`, or `file2` exists and
+//!   `file1` does not.
+//!
+//! # Options
+//!
+//! None. Operands that look like options are parsed as part of the expression.
+//!
+//! # Exit status
+//!
+//! Zero if the expression is true, one if false, and two on an error.
+//!
+//! # Errors
+//!
+//! It is an error if the operands do not form a valid expression, an integer
+//! operand is not a valid integer, or the `[` built-in is missing the closing
+//! `]`.
+//!
+//! # Portability
+//!
+//! The `-a` and `-o` operators, parentheses, and expressions with more than
+//! four operands are obsolescent in POSIX and may be parsed differently in
+//! other shells. The `<` and `>` operators and the `-ef`, `-nt`, `-ot`, and
+//! `-k` primaries are not defined in older versions of POSIX.
+//!
+//! # Implementation notes
+//!
+//! The `-r` and `-w` primaries check the file with
+//! [`faccessat`](yash_env::System::faccessat) using the effective user and
+//! group IDs. The virtual system only simulates the owner's permission bits,
+//! regarding the current process as the owner of every file. The `<` and `>`
+//! operators compare strings by code points rather than the collation order of
+//! the current locale.
+
+use crate::common::print_error_message;
+use crate::common::BuiltinEnv;
+use yash_env::builtin::Result;
+use yash_env::semantics::ExitStatus;
+use yash_env::semantics::Field;
+use yash_env::Env;
+
+pub mod semantics;
+pub mod syntax;
+
+/// Entry point for executing the `test` and `[` built-ins
+///
+/// The built-in requires the closing `]` if invoked as `[`.
+pub async fn main(env: &mut Env, mut args: Vec<Field>) -> Result {
+    if env.builtin_name().value == "[" {
+        if args.last().map_or(false, |arg| arg.value == "]") {
+            args.pop();
+        } else {
+            let location = match args.last() {
+                Some(arg) => arg.origin.clone(),
+                None => env.builtin_name().origin.clone(),
+            };
+            let error = syntax::Error::MissingClosingBracket { location };
+            return print_error_message(env, &error).await;
+        }
+    }
+
+    if args.is_empty() {
+        return Result::new(ExitStatus::FAILURE);
+    }
+
+    let expression = match syntax::parse(&args) {
+        Ok(expression) => expression,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+
+    match semantics::evaluate(env, &expression) {
+        Ok(true) => Result::new(ExitStatus::SUCCESS),
+        Ok(false) => Result::new(ExitStatus::FAILURE),
+        Err(error) => print_error_message(env, &error).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use yash_env::stack::Frame;
+
+    fn run(name: &str, args: &[&str]) -> Result {
+        let mut env = Env::new_virtual();
+        let mut env = env.push_frame(Frame::Builtin {
+            name: Field::dummy(name),
+            is_special: false,
+        });
+        main(&mut env, Field::dummies(args.iter().copied()))
+            .now_or_never()
+            .unwrap()
+    }
+
+    #[test]
+    fn no_operands() {
+        assert_eq!(run("test", &[]), Result::new(ExitStatus::FAILURE));
+        assert_eq!(run("[", &["]"]), Result::new(ExitStatus::FAILURE));
+    }
+
+    #[test]
+    fn true_and_false_expressions() {
+        assert_eq!(run("test", &["a"]), Result::new(ExitStatus::SUCCESS));
+        assert_eq!(run("test", &[""]), Result::new(ExitStatus::FAILURE));
+        assert_eq!(
+            run("[", &["1", "-lt", "2", "]"]),
+            Result::new(ExitStatus::SUCCESS)
+        );
+        assert_eq!(
+            run("[", &["!", "a", "=", "a", "]"]),
+            Result::new(ExitStatus::FAILURE)
+        );
+    }
+
+    #[test]
+    fn closing_bracket_is_an_operand_for_test() {
+        assert_eq!(run("test", &["]"]), Result::new(ExitStatus::SUCCESS));
+        assert_eq!(
+            run("test", &["a", "=", "]"]),
+            Result::new(ExitStatus::FAILURE)
+        );
+    }
+
+    #[test]
+    fn missing_closing_bracket() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("["),
+                is_special: false,
+            });
+            let result = main(&mut env, Field::dummies(["a", "=", "a"])).await;
+            assert_eq!(result, Result::new(ExitStatus::ERROR));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("missing `]`"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn syntax_error() {
+        assert_eq!(run("test", &["a", "b"]), Result::new(ExitStatus::ERROR));
+    }
+
+    #[test]
+    fn invalid_integer() {
+        assert_eq!(
+            run("test", &["1", "-eq", "x"]),
+            Result::new(ExitStatus::ERROR)
+        );
+    }
+}
@@ -0,0 +1,227 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Evaluation of expressions for the test built-in
+//!
+//! Primaries other than `=` and `!=` are evaluated by
+//! [`yash_semantics::primary`], which is shared with the double-bracket
+//! command.
+
+use super::syntax::BinaryOperator;
+use super::syntax::Expression;
+use yash_env::semantics::Field;
+use yash_env::Env;
+use yash_semantics::primary::compare_integers;
+use yash_semantics::primary::evaluate_unary;
+use yash_semantics::primary::is_newer_than;
+use yash_semantics::primary::is_older_than;
+use yash_semantics::primary::is_same_file;
+pub use yash_semantics::primary::Error;
+pub use yash_semantics::primary::Result;
+
+fn evaluate_binary(env: &Env, left: &Field, operator: BinaryOperator, right: &Field) -> Result {
+    use BinaryOperator::*;
+    match operator {
+        StringEqual => Ok(left.value == right.value),
+        StringNotEqual => Ok(left.value != right.value),
+        // TODO Apply the collation order of the current locale
+        StringLess => Ok(left.value < right.value),
+        StringGreater => Ok(left.value > right.value),
+        IntEqual => compare_integers(left, right, |l, r| l == r),
+        IntNotEqual => compare_integers(left, right, |l, r| l != r),
+        IntGreater => compare_integers(left, right, |l, r| l > r),
+        IntGreaterEqual => compare_integers(left, right, |l, r| l >= r),
+        IntLess => compare_integers(left, right, |l, r| l < r),
+        IntLessEqual => compare_integers(left, right, |l, r| l <= r),
+        SameFile => Ok(is_same_file(env, &left.value, &right.value)),
+        NewerThan => Ok(is_newer_than(env, &left.value, &right.value)),
+        OlderThan => Ok(is_older_than(env, &left.value, &right.value)),
+    }
+}
+
+/// Evaluates the expression.
+pub fn evaluate(env: &Env, expression: &Expression) -> Result {
+    match expression {
+        Expression::String(operand) => Ok(!operand.value.is_empty()),
+        Expression::Unary { operator, operand } => evaluate_unary(env, *operator, operand),
+        Expression::Binary {
+            left,
+            operator,
+            right,
+        } => evaluate_binary(env, left, *operator, right),
+        Expression::Not(expression) => Ok(!evaluate(env, expression)?),
+        Expression::And(left, right) => Ok(evaluate(env, left)? && evaluate(env, right)?),
+        Expression::Or(left, right) => Ok(evaluate(env, left)? || evaluate(env, right)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::syntax::UnaryOperator;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use yash_env::system::r#virtual::FileBody;
+    use yash_env::system::r#virtual::INode;
+    use yash_env::system::r#virtual::Mode;
+    use yash_env::VirtualSystem;
+
+    fn env_with_files() -> Env {
+        let system = VirtualSystem::new();
+        let mut state = system.state.borrow_mut();
+        let mut save = |path: &str, body, permissions| {
+            let inode = INode { body, permissions };
+            state
+                .file_system
+                .save(path, Rc::new(RefCell::new(inode)))
+                .unwrap();
+        };
+        save("/file", FileBody::new([1, 2, 3]), Mode(0o644));
+        save("/empty", FileBody::new([]), Mode(0o000));
+        save("/exec", FileBody::new([]), Mode(0o755));
+        let directory = FileBody::Directory {
+            files: Default::default(),
+        };
+        save("/dir", directory, Mode(0o755));
+        let fifo = FileBody::Fifo {
+            content: Default::default(),
+            readers: 0,
+            writers: 0,
+        };
+        save("/fifo", fifo, Mode(0o644));
+        let link = FileBody::Symlink {
+            target: "file".into(),
+        };
+        save("/link", link, Mode(0o777));
+        save("/setid", FileBody::new([]), Mode(0o7644));
+        save("/others", FileBody::new([]), Mode(0o066));
+        drop(state);
+        Env::with_system(Box::new(system))
+    }
+
+    fn unary(env: &Env, operator: &str, operand: &str) -> Result {
+        let operator = UnaryOperator::parse(operator).unwrap();
+        evaluate_unary(env, operator, &Field::dummy(operand))
+    }
+
+    fn binary(env: &Env, left: &str, operator: &str, right: &str) -> Result {
+        let operator = BinaryOperator::parse(operator).unwrap();
+        evaluate_binary(env, &Field::dummy(left), operator, &Field::dummy(right))
+    }
+
+    #[test]
+    fn file_type_primaries() {
+        let env = env_with_files();
+        assert_eq!(unary(&env, "-e", "/file"), Ok(true));
+        assert_eq!(unary(&env, "-e", "/none"), Ok(false));
+        assert_eq!(unary(&env, "-f", "/file"), Ok(true));
+        assert_eq!(unary(&env, "-f", "/link"), Ok(true));
+        assert_eq!(unary(&env, "-f", "/dir"), Ok(false));
+        assert_eq!(unary(&env, "-d", "/dir"), Ok(true));
+        assert_eq!(unary(&env, "-d", "/file"), Ok(false));
+        assert_eq!(unary(&env, "-p", "/fifo"), Ok(true));
+        assert_eq!(unary(&env, "-p", "/file"), Ok(false));
+        assert_eq!(unary(&env, "-L", "/link"), Ok(true));
+        assert_eq!(unary(&env, "-h", "/file"), Ok(false));
+        assert_eq!(unary(&env, "-b", "/file"), Ok(false));
+        assert_eq!(unary(&env, "-c", "/file"), Ok(false));
+        assert_eq!(unary(&env, "-S", "/file"), Ok(false));
+    }
+
+    #[test]
+    fn file_attribute_primaries() {
+        let env = env_with_files();
+        assert_eq!(unary(&env, "-s", "/file"), Ok(true));
+        assert_eq!(unary(&env, "-s", "/empty"), Ok(false));
+        assert_eq!(unary(&env, "-r", "/file"), Ok(true));
+        assert_eq!(unary(&env, "-r", "/empty"), Ok(false));
+        assert_eq!(unary(&env, "-w", "/file"), Ok(true));
+        assert_eq!(unary(&env, "-w", "/empty"), Ok(false));
+        assert_eq!(unary(&env, "-x", "/exec"), Ok(true));
+        assert_eq!(unary(&env, "-x", "/dir"), Ok(true));
+        assert_eq!(unary(&env, "-x", "/file"), Ok(false));
+        assert_eq!(unary(&env, "-u", "/setid"), Ok(true));
+        assert_eq!(unary(&env, "-g", "/setid"), Ok(true));
+        assert_eq!(unary(&env, "-k", "/setid"), Ok(true));
+        assert_eq!(unary(&env, "-u", "/file"), Ok(false));
+        assert_eq!(unary(&env, "-r", "/none"), Ok(false));
+        assert_eq!(unary(&env, "-r", "/others"), Ok(false));
+        assert_eq!(unary(&env, "-w", "/others"), Ok(false));
+    }
+
+    #[test]
+    fn terminal_primary() {
+        let env = env_with_files();
+        assert_eq!(unary(&env, "-t", "0"), Ok(false));
+        assert_eq!(
+            unary(&env, "-t", "x"),
+            Err(Error::InvalidInteger(Field::dummy("x")))
+        );
+    }
+
+    #[test]
+    fn string_primaries() {
+        let env = Env::new_virtual();
+        assert_eq!(unary(&env, "-n", "a"), Ok(true));
+        assert_eq!(unary(&env, "-n", ""), Ok(false));
+        assert_eq!(unary(&env, "-z", ""), Ok(true));
+        assert_eq!(unary(&env, "-z", "a"), Ok(false));
+        assert_eq!(binary(&env, "a", "=", "a"), Ok(true));
+        assert_eq!(binary(&env, "a", "=", "b"), Ok(false));
+        assert_eq!(binary(&env, "a", "!=", "b"), Ok(true));
+        assert_eq!(binary(&env, "a", "<", "b"), Ok(true));
+        assert_eq!(binary(&env, "a", ">", "b"), Ok(false));
+    }
+
+    #[test]
+    fn integer_primaries() {
+        let env = Env::new_virtual();
+        assert_eq!(binary(&env, "1", "-eq", " 1 "), Ok(true));
+        assert_eq!(binary(&env, "-1", "-ne", "1"), Ok(true));
+        assert_eq!(binary(&env, "2", "-gt", "10"), Ok(false));
+        assert_eq!(binary(&env, "2", "-ge", "2"), Ok(true));
+        assert_eq!(binary(&env, "2", "-lt", "10"), Ok(true));
+        assert_eq!(binary(&env, "3", "-le", "2"), Ok(false));
+        assert_eq!(
+            binary(&env, "1", "-eq", "one"),
+            Err(Error::InvalidInteger(Field::dummy("one")))
+        );
+    }
+
+    #[test]
+    fn file_comparison_primaries() {
+        let env = env_with_files();
+        assert_eq!(binary(&env, "/file", "-ef", "/link"), Ok(true));
+        assert_eq!(binary(&env, "/file", "-ef", "/dir"), Ok(false));
+        assert_eq!(binary(&env, "/file", "-ef", "/none"), Ok(false));
+        assert_eq!(binary(&env, "/file", "-nt", "/none"), Ok(true));
+        assert_eq!(binary(&env, "/none", "-nt", "/file"), Ok(false));
+        assert_eq!(binary(&env, "/none", "-ot", "/file"), Ok(true));
+        assert_eq!(binary(&env, "/file", "-ot", "/none"), Ok(false));
+    }
+
+    #[test]
+    fn compound_expressions() {
+        let env = Env::new_virtual();
+        let t = || Box::new(Expression::String(Field::dummy("x")));
+        let f = || Box::new(Expression::String(Field::dummy("")));
+        assert_eq!(evaluate(&env, &Expression::Not(f())), Ok(true));
+        assert_eq!(evaluate(&env, &Expression::And(t(), f())), Ok(false));
+        assert_eq!(evaluate(&env, &Expression::And(t(), t())), Ok(true));
+        assert_eq!(evaluate(&env, &Expression::Or(f(), t())), Ok(true));
+        assert_eq!(evaluate(&env, &Expression::Or(f(), f())), Ok(false));
+    }
+}
@@ -0,0 +1,482 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Command line argument parser for the test built-in
+//!
+//! The [`parse`] function parses operands into an [`Expression`]. When given
+//! four or fewer operands, the parser follows the operand-count-based rules
+//! defined in POSIX. With more operands, the parser uses the following
+//! grammar, where `-a` has higher precedence than `-o`:
+//!
+//! ```text
+//! or_expr  := and_expr ("-o" and_expr)*
+//! and_expr := not_expr ("-a" not_expr)*
+//! not_expr := "!" not_expr | primary
+//! primary  := operand binary_operator operand
+//!           | "(" or_expr ")"
+//!           | unary_operator operand
+//!           | operand
+//! ```
+
+use std::borrow::Cow;
+use thiserror::Error;
+use yash_env::semantics::Field;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::MessageBase;
+use yash_syntax::source::Location;
+
+/// Unary operator
+///
+/// The test built-in shares the unary operators with the double-bracket
+/// command.
+pub use yash_syntax::syntax::CondUnaryOperator as UnaryOperator;
+
+/// Binary operator
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BinaryOperator {
+    /// `=`: identical strings
+    StringEqual,
+    /// `!=`: different strings
+    StringNotEqual,
+    /// `<`: string sorted before another
+    StringLess,
+    /// `>`: string sorted after another
+    StringGreater,
+    /// `-eq`: equal integers
+    IntEqual,
+    /// `-ne`: different integers
+    IntNotEqual,
+    /// `-gt`: greater integer
+    IntGreater,
+    /// `-ge`: greater or equal integer
+    IntGreaterEqual,
+    /// `-lt`: less integer
+    IntLess,
+    /// `-le`: less or equal integer
+    IntLessEqual,
+    /// `-ef`: same file
+    SameFile,
+    /// `-nt`: newer file
+    NewerThan,
+    /// `-ot`: older file
+    OlderThan,
+}
+
+impl BinaryOperator {
+    /// Parses a binary operator.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        use BinaryOperator::*;
+        Some(match s {
+            "=" => StringEqual,
+            "!=" => StringNotEqual,
+            "<" => StringLess,
+            ">" => StringGreater,
+            "-eq" => IntEqual,
+            "-ne" => IntNotEqual,
+            "-gt" => IntGreater,
+            "-ge" => IntGreaterEqual,
+            "-lt" => IntLess,
+            "-le" => IntLessEqual,
+            "-ef" => SameFile,
+            "-nt" => NewerThan,
+            "-ot" => OlderThan,
+            _ => return None,
+        })
+    }
+}
+
+/// Expression evaluated by the test built-in
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Expression {
+    /// Single operand that is tested for non-emptiness
+    String(Field),
+    /// Unary primary
+    Unary {
+        operator: UnaryOperator,
+        operand: Field,
+    },
+    /// Binary primary
+    Binary {
+        left: Field,
+        operator: BinaryOperator,
+        right: Field,
+    },
+    /// Negation (`!`)
+    Not(Box<Expression>),
+    /// Logical conjunction (`-a`)
+    And(Box<Expression>, Box<Expression>),
+    /// Logical disjunction (`-o`)
+    Or(Box<Expression>, Box<Expression>),
+}
+
+/// Error in parsing operands
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The `[` built-in is missing the closing `]`.
+    #[error("missing `]`")]
+    MissingClosingBracket {
+        /// Location of the built-in name
+        location: Location,
+    },
+
+    /// An operator is not followed by an operand.
+    #[error("missing operand")]
+    MissingOperand(Field),
+
+    /// An opening parenthesis is not closed.
+    #[error("unmatched parenthesis")]
+    UnmatchedParenthesis(Field),
+
+    /// The first of two operands is not a unary operator.
+    #[error("unary operator expected")]
+    UnaryOperatorExpected(Field),
+
+    /// An operand appears where it is not expected.
+    #[error("unexpected operand")]
+    UnexpectedOperand(Field),
+}
+
+impl MessageBase for Error {
+    fn message_title(&self) -> Cow<str> {
+        self.to_string().into()
+    }
+
+    fn main_annotation(&self) -> Annotation<'_> {
+        use Error::*;
+        let (label, location): (Cow<str>, _) = match self {
+            MissingClosingBracket { location } => ("`]` is required here".into(), location),
+            MissingOperand(operator) => (
+                format!(
+                    "{}: operand is required after this operator",
+                    operator.value
+                )
+                .into(),
+                &operator.origin,
+            ),
+            UnmatchedParenthesis(parenthesis) => {
+                ("parenthesis not closed".into(), &parenthesis.origin)
+            }
+            UnaryOperatorExpected(operand) => (
+                format!("{}: not a unary operator", operand.value).into(),
+                &operand.origin,
+            ),
+            UnexpectedOperand(operand) => (
+                format!("{}: unexpected operand", operand.value).into(),
+                &operand.origin,
+            ),
+        };
+        Annotation::new(AnnotationType::Error, label, location)
+    }
+}
+
+/// Result of parsing operands
+pub type Result = std::result::Result<Expression, Error>;
+
+fn string(operand: &Field) -> Expression {
+    Expression::String(operand.clone())
+}
+
+fn not(expression: Expression) -> Expression {
+    Expression::Not(Box::new(expression))
+}
+
+fn parse_two(args: &[Field]) -> Result {
+    if args[0].value == "!" {
+        return Ok(not(string(&args[1])));
+    }
+    match UnaryOperator::parse(&args[0].value) {
+        Some(operator) => Ok(Expression::Unary {
+            operator,
+            operand: args[1].clone(),
+        }),
+        None => Err(Error::UnaryOperatorExpected(args[0].clone())),
+    }
+}
+
+fn parse_three(args: &[Field]) -> Result {
+    if let Some(operator) = BinaryOperator::parse(&args[1].value) {
+        return Ok(Expression::Binary {
+            left: args[0].clone(),
+            operator,
+            right: args[2].clone(),
+        });
+    }
+    match (
+        args[0].value.as_str(),
+        args[1].value.as_str(),
+        &args[2].value,
+    ) {
+        (_, "-a", _) => Ok(Expression::And(
+            Box::new(string(&args[0])),
+            Box::new(string(&args[2])),
+        )),
+        (_, "-o", _) => Ok(Expression::Or(
+            Box::new(string(&args[0])),
+            Box::new(string(&args[2])),
+        )),
+        ("!", _, _) => Ok(not(parse_two(&args[1..])?)),
+        ("(", _, close) if close == ")" => Ok(string(&args[1])),
+        _ => Err(Error::UnexpectedOperand(args[1].clone())),
+    }
+}
+
+/// Recursive descent parser used for more than four operands
+struct Parser<'a> {
+    args: &'a [Field],
+    index: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self, offset: usize) -> Option<&Field> {
+        self.args.get(self.index + offset)
+    }
+
+    fn next_is(&self, token: &str) -> bool {
+        self.peek(0).map_or(false, |arg| arg.value == token)
+    }
+
+    fn or_expr(&mut self) -> Result {
+        let mut expression = self.and_expr()?;
+        while self.next_is("-o") {
+            self.index += 1;
+            let right = self.and_expr()?;
+            expression = Expression::Or(Box::new(expression), Box::new(right));
+        }
+        Ok(expression)
+    }
+
+    fn and_expr(&mut self) -> Result {
+        let mut expression = self.not_expr()?;
+        while self.next_is("-a") {
+            self.index += 1;
+            let right = self.not_expr()?;
+            expression = Expression::And(Box::new(expression), Box::new(right));
+        }
+        Ok(expression)
+    }
+
+    fn not_expr(&mut self) -> Result {
+        if self.next_is("!") && self.peek(1).is_some() {
+            self.index += 1;
+            Ok(not(self.not_expr()?))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result {
+        let first = self.peek(0).cloned();
+        let Some(first) = first else {
+            let last = &self.args[self.args.len() - 1];
+            return Err(Error::MissingOperand(last.clone()));
+        };
+
+        if let Some(operator) = self.peek(1).and_then(|f| BinaryOperator::parse(&f.value)) {
+            if let Some(right) = self.peek(2).cloned() {
+                self.index += 3;
+                return Ok(Expression::Binary {
+                    left: first,
+                    operator,
+                    right,
+                });
+            }
+        }
+
+        if first.value == "(" && self.peek(1).is_some() {
+            self.index += 1;
+            let expression = self.or_expr()?;
+            if !self.next_is(")") {
+                return Err(Error::UnmatchedParenthesis(first));
+            }
+            self.index += 1;
+            return Ok(expression);
+        }
+
+        self.index += 1;
+        if let Some(operator) = UnaryOperator::parse(&first.value) {
+            if let Some(operand) = self.peek(0).cloned() {
+                self.index += 1;
+                return Ok(Expression::Unary { operator, operand });
+            }
+        }
+        Ok(Expression::String(first))
+    }
+}
+
+fn parse_many(args: &[Field]) -> Result {
+    let mut parser = Parser { args, index: 0 };
+    let expression = parser.or_expr()?;
+    match parser.peek(0) {
+        None => Ok(expression),
+        Some(operand) => Err(Error::UnexpectedOperand(operand.clone())),
+    }
+}
+
+/// Parses operands of the test built-in.
+///
+/// This function **panics** if `args` is empty.
+pub fn parse(args: &[Field]) -> Result {
+    match args.len() {
+        0 => panic!("no operands to parse"),
+        1 => Ok(string(&args[0])),
+        2 => parse_two(args),
+        3 => parse_three(args),
+        4 if args[0].value == "!" => Ok(not(parse_three(&args[1..])?)),
+        4 if args[0].value == "(" && args[3].value == ")" => parse_two(&args[1..3]),
+        _ => parse_many(args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unary(operator: UnaryOperator, operand: &str) -> Expression {
+        Expression::Unary {
+            operator,
+            operand: Field::dummy(operand),
+        }
+    }
+
+    fn binary(left: &str, operator: BinaryOperator, right: &str) -> Expression {
+        Expression::Binary {
+            left: Field::dummy(left),
+            operator,
+            right: Field::dummy(right),
+        }
+    }
+
+    fn string(operand: &str) -> Expression {
+        Expression::String(Field::dummy(operand))
+    }
+
+    #[test]
+    fn one_operand() {
+        assert_eq!(parse(&Field::dummies(["-n"])), Ok(string("-n")));
+        assert_eq!(parse(&Field::dummies(["!"])), Ok(string("!")));
+    }
+
+    #[test]
+    fn two_operands() {
+        let result = parse(&Field::dummies(["-f", "foo"]));
+        assert_eq!(result, Ok(unary(UnaryOperator::Regular, "foo")));
+
+        let result = parse(&Field::dummies(["!", "-z"]));
+        assert_eq!(result, Ok(not(string("-z"))));
+
+        let args = Field::dummies(["foo", "bar"]);
+        let result = parse(&args);
+        assert_eq!(result, Err(Error::UnaryOperatorExpected(args[0].clone())));
+    }
+
+    #[test]
+    fn three_operands() {
+        let result = parse(&Field::dummies(["a", "=", "b"]));
+        assert_eq!(result, Ok(binary("a", BinaryOperator::StringEqual, "b")));
+
+        // A binary operator takes precedence over `!` and parentheses.
+        let result = parse(&Field::dummies(["!", "=", "!"]));
+        assert_eq!(result, Ok(binary("!", BinaryOperator::StringEqual, "!")));
+        let result = parse(&Field::dummies(["(", "!=", ")"]));
+        assert_eq!(result, Ok(binary("(", BinaryOperator::StringNotEqual, ")")));
+
+        let result = parse(&Field::dummies(["!", "-n", ""]));
+        assert_eq!(result, Ok(not(unary(UnaryOperator::NonEmptyString, ""))));
+
+        let result = parse(&Field::dummies(["(", "-d", ")"]));
+        assert_eq!(result, Ok(string("-d")));
+
+        let result = parse(&Field::dummies(["a", "-a", ""]));
+        assert_eq!(
+            result,
+            Ok(Expression::And(Box::new(string("a")), Box::new(string(""))))
+        );
+
+        let args = Field::dummies(["a", "b", "c"]);
+        let result = parse(&args);
+        assert_eq!(result, Err(Error::UnexpectedOperand(args[1].clone())));
+    }
+
+    #[test]
+    fn four_operands() {
+        let result = parse(&Field::dummies(["!", "1", "-lt", "2"]));
+        assert_eq!(result, Ok(not(binary("1", BinaryOperator::IntLess, "2"))));
+
+        let result = parse(&Field::dummies(["(", "-e", "f", ")"]));
+        assert_eq!(result, Ok(unary(UnaryOperator::Exists, "f")));
+
+        let result = parse(&Field::dummies(["-n", "a", "-o", "b"]));
+        assert_eq!(
+            result,
+            Ok(Expression::Or(
+                Box::new(unary(UnaryOperator::NonEmptyString, "a")),
+                Box::new(string("b"))
+            ))
+        );
+    }
+
+    #[test]
+    fn and_has_higher_precedence_than_or() {
+        let result = parse(&Field::dummies(["a", "-o", "b", "-a", "c"]));
+        assert_eq!(
+            result,
+            Ok(Expression::Or(
+                Box::new(string("a")),
+                Box::new(Expression::And(
+                    Box::new(string("b")),
+                    Box::new(string("c"))
+                ))
+            ))
+        );
+    }
+
+    #[test]
+    fn parentheses_and_negation() {
+        let args = Field::dummies(["!", "(", "a", "=", "b", "-o", "-z", "", ")"]);
+        let result = parse(&args);
+        assert_eq!(
+            result,
+            Ok(not(Expression::Or(
+                Box::new(binary("a", BinaryOperator::StringEqual, "b")),
+                Box::new(unary(UnaryOperator::EmptyString, ""))
+            )))
+        );
+    }
+
+    #[test]
+    fn unmatched_parenthesis() {
+        let args = Field::dummies(["(", "a", "-a", "b", "c"]);
+        let result = parse(&args);
+        assert_eq!(result, Err(Error::UnmatchedParenthesis(args[0].clone())));
+    }
+
+    #[test]
+    fn missing_operand() {
+        let args = Field::dummies(["a", "-a", "b", "-o"]);
+        let result = parse(&args);
+        assert_eq!(result, Err(Error::MissingOperand(args[3].clone())));
+    }
+
+    #[test]
+    fn unexpected_operand() {
+        let args = Field::dummies(["a", "=", "b", "c", "d"]);
+        let result = parse(&args);
+        assert_eq!(result, Err(Error::UnexpectedOperand(args[3].clone())));
+    }
+}
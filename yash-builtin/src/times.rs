@@ -0,0 +1,180 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Times built-in
+//!
+//! The **`times`** built-in prints the CPU times consumed by the shell and
+//! its children.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! times
+//! ```
+//!
+//! # Semantics
+//!
+//! The built-in prints two lines. The first line contains the user and system
+//! CPU times consumed by the shell process, and the second line those
+//! consumed by the child processes of the shell. Each time is printed in the
+//! form of `<minutes>m<seconds>s`, where the seconds have three decimal
+//! places, like `1m2.345s`.
+//!
+//! # Options
+//!
+//! None.
+//!
+//! # Operands
+//!
+//! None.
+//!
+//! # Exit status
+//!
+//! Zero unless an error occurs.
+//!
+//! # Errors
+//!
+//! It is an error if the times cannot be obtained from the system or printed
+//! to the standard output.
+//!
+//! # Portability
+//!
+//! POSIX does not specify the number of decimal places of the seconds.
+//!
+//! # Implementation notes
+//!
+//! The times are obtained from [`System::times`].
+
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax_error;
+use crate::common::BuiltinEnv;
+use crate::common::Print;
+use std::time::Duration;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::system::Times;
+use yash_env::Env;
+use yash_env::System;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+
+/// Formats a time in the form of `<minutes>m<seconds>s`.
+fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    let minutes = millis / 60_000;
+    let seconds = millis % 60_000 / 1000;
+    let millis = millis % 1000;
+    format!("{minutes}m{seconds}.{millis:03}s")
+}
+
+/// Formats the result of the built-in.
+#[must_use]
+pub fn format(times: &Times) -> String {
+    format!(
+        "{} {}\n{} {}\n",
+        format_duration(times.self_user),
+        format_duration(times.self_system),
+        format_duration(times.children_user),
+        format_duration(times.children_system),
+    )
+}
+
+/// Entry point for executing the `times` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let operands = match parse_arguments(&[], Mode::with_env(env), args) {
+        Ok((_options, operands)) => operands,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+    if let Some(operand) = operands.first() {
+        return syntax_error(env, "no operands are expected", &operand.origin).await;
+    }
+
+    match env.system.times() {
+        Ok(times) => env.print(&format(&times)).await,
+        Err(errno) => {
+            let location = env.builtin_name().origin.clone();
+            let message = Message {
+                r#type: AnnotationType::Error,
+                title: "cannot obtain CPU times".into(),
+                annotations: vec![Annotation::new(
+                    AnnotationType::Error,
+                    errno.desc().into(),
+                    &location,
+                )],
+            };
+            print_failure_message(env, message).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+
+    #[test]
+    fn formatting_durations() {
+        assert_eq!(format_duration(Duration::ZERO), "0m0.000s");
+        assert_eq!(format_duration(Duration::from_millis(1_234)), "0m1.234s");
+        assert_eq!(format_duration(Duration::from_millis(62_005)), "1m2.005s");
+        assert_eq!(
+            format_duration(Duration::from_micros(3_599_999_999)),
+            "59m59.999s"
+        );
+    }
+
+    #[test]
+    fn printing_simulated_times() {
+        in_virtual_system(|mut env, state| async move {
+            let times = Times {
+                self_user: Duration::from_millis(1_500),
+                self_system: Duration::from_millis(20),
+                children_user: Duration::from_secs(125),
+                children_system: Duration::ZERO,
+            };
+            state
+                .borrow_mut()
+                .processes
+                .get_mut(&env.main_pid)
+                .unwrap()
+                .set_times(times);
+
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| {
+                assert_eq!(stdout, "0m1.500s 0m0.020s\n2m5.000s 0m0.000s\n")
+            });
+        })
+    }
+
+    #[test]
+    fn printing_zero_times() {
+        in_virtual_system(|mut env, state| async move {
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| {
+                assert_eq!(stdout, "0m0.000s 0m0.000s\n0m0.000s 0m0.000s\n")
+            });
+        })
+    }
+}
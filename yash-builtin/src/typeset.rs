@@ -0,0 +1,536 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Typeset built-in
+//!
+//! The **`typeset`** built-in defines variables in the current function
+//! scope. The **`local`** built-in is a synonym for `typeset`.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! typeset [-rx] name[=value]...
+//! ```
+//!
+//! ```sh
+//! typeset -p [-rx] [name...]
+//! ```
+//!
+//! # Semantics
+//!
+//! The typeset built-in (without the `-p` option) defines each of the
+//! specified variables in the innermost regular variable context, that is, the
+//! context of the function being executed, or the global context if no
+//! function is running. A variable defined in a function hides variables of
+//! the same name defined outside the function, and disappears when the
+//! function returns.
+//!
+//! If a value is given to a name, the value is assigned to the variable. A
+//! name without a value applies the attributes specified by the options to the
+//! variable that already exists in the current context, or defines a new
+//! variable that has no value.
+//!
+//! # Options
+//!
+//! The **`-x`** (**`--export`**) option makes the variables exported.
+//!
+//! The **`-r`** (**`--readonly`**) option makes the variables read-only.
+//!
+//! The **`-p`** (**`--print`**) option makes the built-in print the variables
+//! instead of defining them. If names are given, the named variables are
+//! printed. Otherwise, all variables are printed. If the `-x` or `-r` option is
+//! specified with `-p`, only the variables having the attributes are printed.
+//!
+//! The output is a sequence of commands that would re-define the variables
+//! with the current values and attributes if executed. Values are quoted with
+//! [`yash_quote`].
+//!
+//! If there are no operands, the built-in behaves as if the `-p` option were
+//! specified.
+//!
+//! # Operands
+//!
+//! Operands are names of variables to be defined or printed. When defining, a
+//! name may be followed by an equal sign and a value to be assigned.
+//!
+//! # Exit status
+//!
+//! Zero unless an error occurs.
+//!
+//! # Errors
+//!
+//! It is an error if an operand is not a valid variable name or assigns to a
+//! read-only variable. The other operands are still processed.
+//!
+//! When printing, it is an error if an operand names an undefined variable.
+//!
+//! # Portability
+//!
+//! The typeset and local built-ins are not defined in POSIX. Many shells
+//! implement them with different sets of options and slightly different
+//! semantics. In particular, the output format of `typeset -p` differs among
+//! shells.
+
+use crate::common::invalid_name_message;
+use crate::common::is_valid_name;
+use crate::common::not_found_message;
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax::OptionSpec;
+use crate::common::Print;
+use std::fmt::Write;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::variable::ReadOnlyError;
+use yash_env::variable::Scope::{Global, Local};
+use yash_env::variable::Value::{Array, Scalar};
+use yash_env::variable::Variable;
+use yash_env::Env;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+
+const OPTION_SPECS: &[OptionSpec] = &[
+    OptionSpec::new().short('p').long("print"),
+    OptionSpec::new().short('r').long("readonly"),
+    OptionSpec::new().short('x').long("export"),
+];
+
+/// Attributes specified by the options
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Attributes {
+    read_only: bool,
+    export: bool,
+}
+
+impl Attributes {
+    /// Tests whether the variable has all of the attributes.
+    fn matches(&self, var: &Variable) -> bool {
+        (!self.read_only || var.is_read_only()) && (!self.export || var.is_exported)
+    }
+
+    /// Applies the attributes to the variable.
+    fn apply(&self, var: &mut Variable, operand: &Field) {
+        if self.export {
+            var.is_exported = true;
+        }
+        if self.read_only && var.read_only_location.is_none() {
+            var.read_only_location = Some(operand.origin.clone());
+        }
+    }
+}
+
+/// Appends a command that re-defines the variable to the output.
+fn print_variable(output: &mut String, name: &str, var: &Variable) {
+    let mut flags = String::new();
+    if var.is_read_only() {
+        flags.push('r');
+    }
+    if var.is_exported {
+        flags.push('x');
+    }
+    let options = if flags.is_empty() {
+        flags
+    } else {
+        format!("-{flags} ")
+    };
+
+    match &var.value {
+        Some(value @ Scalar(_)) => {
+            writeln!(output, "typeset {}{}={}", options, name, value.quote())
+        }
+        Some(value @ Array(_)) => {
+            writeln!(
+                output,
+                "{}={}\ntypeset {}{}",
+                name,
+                value.quote(),
+                options,
+                name
+            )
+        }
+        None => writeln!(output, "typeset {options}{name}"),
+    }
+    .unwrap()
+}
+
+/// Prints the variables.
+///
+/// If `names` is empty, all variables having the attributes are printed.
+async fn print_variables(env: &mut Env, attributes: Attributes, names: &[Field]) -> Result {
+    let mut output = String::new();
+    let mut result = Result::default();
+    if names.is_empty() {
+        let mut vars: Vec<_> = env
+            .variables
+            .iter(Global)
+            .filter(|(_, var)| attributes.matches(var))
+            .collect();
+        // TODO apply current locale's collation
+        vars.sort_unstable_by_key(|&(name, _)| name);
+        for (name, var) in vars {
+            print_variable(&mut output, name, var);
+        }
+    } else {
+        for name in names {
+            match env.variables.get(&name.value) {
+                Some(var) => {
+                    if attributes.matches(var) {
+                        print_variable(&mut output, &name.value, var);
+                    }
+                }
+                None => {
+                    result = print_failure_message(env, not_found_message("variable", name)).await
+                }
+            }
+        }
+    }
+
+    let print_result = env.print(&output).await;
+    if print_result == Result::default() {
+        result
+    } else {
+        print_result
+    }
+}
+
+/// Error in defining a variable
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum DefineError {
+    /// The operand does not start with a valid variable name.
+    InvalidName,
+    /// The variable is read-only.
+    ReadOnly(Box<ReadOnlyError>),
+}
+
+impl From<ReadOnlyError> for DefineError {
+    fn from(error: ReadOnlyError) -> Self {
+        DefineError::ReadOnly(Box::new(error))
+    }
+}
+
+impl DefineError {
+    /// Converts the error into a message.
+    fn to_message<'a>(&'a self, operand: &'a Field) -> Message<'a> {
+        match self {
+            DefineError::InvalidName => {
+                let name = match operand.value.split_once('=') {
+                    Some((name, _)) => name,
+                    None => &operand.value,
+                };
+                invalid_name_message(name, operand)
+            }
+            DefineError::ReadOnly(error) => Message {
+                r#type: AnnotationType::Error,
+                title: format!("cannot assign to read-only variable `{}`", error.name).into(),
+                annotations: vec![
+                    Annotation::new(
+                        AnnotationType::Error,
+                        error.to_string().into(),
+                        &operand.origin,
+                    ),
+                    Annotation::new(
+                        AnnotationType::Info,
+                        "the variable was made read-only here".into(),
+                        &error.read_only_location,
+                    ),
+                ],
+            },
+        }
+    }
+}
+
+/// Defines the variable in the current context.
+fn define(
+    env: &mut Env,
+    attributes: Attributes,
+    operand: &Field,
+) -> std::result::Result<(), DefineError> {
+    let (name, value) = match operand.value.split_once('=') {
+        Some((name, value)) => (name, Some(value)),
+        None => (operand.value.as_str(), None),
+    };
+    if !is_valid_name(name) {
+        return Err(DefineError::InvalidName);
+    }
+
+    let mut var = match value {
+        Some(value) => Variable::new(value).set_assigned_location(operand.origin.clone()),
+        None => {
+            let is_local = env.variables.iter(Local).any(|(n, _)| n == name);
+            if is_local {
+                let var = env.variables.get_mut(name).unwrap();
+                attributes.apply(var, operand);
+                return Ok(());
+            }
+            Variable::default()
+        }
+    };
+
+    attributes.apply(&mut var, operand);
+    env.assign_variable(Local, name.to_owned(), var)?;
+    Ok(())
+}
+
+/// Entry point for executing the `typeset` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (options, operands) = match parse_arguments(OPTION_SPECS, Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+
+    let mut print = false;
+    let mut attributes = Attributes::default();
+    for option in options {
+        match option.spec.get_short() {
+            Some('p') => print = true,
+            Some('r') => attributes.read_only = true,
+            Some('x') => attributes.export = true,
+            _ => unreachable!("unhandled option: {:?}", option),
+        }
+    }
+
+    if print || operands.is_empty() {
+        return print_variables(env, attributes, &operands).await;
+    }
+
+    let mut result = Result::default();
+    for operand in &operands {
+        if let Err(error) = define(env, attributes, operand) {
+            result = print_failure_message(env, error.to_message(operand)).await;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
+    use yash_env::variable::ContextType;
+    use yash_env::variable::Value;
+    use yash_syntax::source::Location;
+
+    #[test]
+    fn defining_local_variable_in_function_context() {
+        let mut env = Env::new_virtual();
+        env.variables
+            .assign(Global, "foo".to_string(), Variable::new("global"))
+            .unwrap();
+        let mut inner = env.push_context(ContextType::Regular);
+
+        let args = Field::dummies(["foo=local", "bar"]);
+        let result = main(&mut inner, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(
+            inner.variables.get("foo").unwrap().value,
+            Some(Value::scalar("local"))
+        );
+        assert_eq!(inner.variables.get("bar"), Some(&Variable::default()));
+
+        Env::pop_context(inner);
+        assert_eq!(
+            env.variables.get("foo").unwrap().value,
+            Some(Value::scalar("global"))
+        );
+        assert_eq!(env.variables.get("bar"), None);
+    }
+
+    #[test]
+    fn name_without_value_hides_outer_variable() {
+        let mut env = Env::new_virtual();
+        env.variables
+            .assign(Global, "foo".to_string(), Variable::new("global"))
+            .unwrap();
+        let mut inner = env.push_context(ContextType::Regular);
+
+        let args = Field::dummies(["foo"]);
+        let result = main(&mut inner, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(inner.variables.get("foo"), Some(&Variable::default()));
+    }
+
+    #[test]
+    fn defining_variable_with_attributes() {
+        let mut env = Env::new_virtual();
+        let args = Field::dummies(["-rx", "foo=bar baz"]);
+        let location = args[1].origin.clone();
+
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+
+        let v = env.variables.get("foo").unwrap();
+        assert_eq!(v.value, Some(Value::scalar("bar baz")));
+        assert!(v.is_exported);
+        assert_eq!(v.read_only_location, Some(location.clone()));
+        assert_eq!(v.last_assigned_location, Some(location));
+    }
+
+    #[test]
+    fn defining_variable_without_value() {
+        let mut env = Env::new_virtual();
+        let args = Field::dummies(["foo"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(env.variables.get("foo"), Some(&Variable::default()));
+    }
+
+    #[test]
+    fn changing_attributes_of_existing_variable() {
+        let mut env = Env::new_virtual();
+        env.variables
+            .assign(Global, "foo".to_string(), Variable::new("value"))
+            .unwrap();
+
+        let args = Field::dummies(["-x", "foo"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(
+            env.variables.get("foo"),
+            Some(&Variable::new("value").export())
+        );
+    }
+
+    #[test]
+    fn assigning_to_read_only_variable() {
+        in_virtual_system(|mut env, state| async move {
+            let var = Variable::new("value").make_read_only(Location::dummy("readonly"));
+            env.variables
+                .assign(Global, "foo".to_string(), var.clone())
+                .unwrap();
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("typeset"),
+                is_special: false,
+            });
+
+            let args = Field::dummies(["foo=new", "bar=baz"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::FAILURE));
+            assert_eq!(env.variables.get("foo"), Some(&var));
+            assert_eq!(
+                env.variables.get("bar").unwrap().value,
+                Some(Value::scalar("baz"))
+            );
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("read-only"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn rejecting_invalid_names() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("typeset"),
+                is_special: false,
+            });
+
+            let args = Field::dummies(["1x=3", "=x", "a b=1", "ok=1", "2y"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::FAILURE));
+            assert_eq!(
+                env.variables.get("ok").unwrap().value,
+                Some(Value::scalar("1"))
+            );
+            assert_eq!(env.variables.get("1x"), None);
+            assert_eq!(env.variables.get(""), None);
+            assert_eq!(env.variables.get("a b"), None);
+            assert_eq!(env.variables.get("2y"), None);
+            assert_stderr(&state, |stderr| {
+                assert_eq!(stderr.matches("not a valid variable name").count(), 4);
+                assert!(stderr.contains("`a b`"), "{stderr:?}");
+            });
+        })
+    }
+
+    #[test]
+    fn printing_all_variables() {
+        in_virtual_system(|mut env, state| async move {
+            env.variables
+                .assign(Global, "foo".to_string(), Variable::new("1").export())
+                .unwrap();
+            env.variables
+                .assign(
+                    Global,
+                    "bar".to_string(),
+                    Variable::new("it's").make_read_only(Location::dummy("")),
+                )
+                .unwrap();
+            env.variables
+                .assign(Global, "baz".to_string(), Variable::default())
+                .unwrap();
+            env.variables
+                .assign(Global, "a".to_string(), Variable::new_array(["1", "2"]))
+                .unwrap();
+
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| {
+                assert_eq!(
+                    stdout,
+                    "a=(1 2)\ntypeset a\ntypeset -r bar=\"it's\"\ntypeset baz\ntypeset -x foo=1\n"
+                )
+            });
+        })
+    }
+
+    #[test]
+    fn printing_variables_filtered_by_attributes() {
+        in_virtual_system(|mut env, state| async move {
+            env.variables
+                .assign(Global, "foo".to_string(), Variable::new("1").export())
+                .unwrap();
+            env.variables
+                .assign(Global, "bar".to_string(), Variable::new("2"))
+                .unwrap();
+
+            let args = Field::dummies(["-px"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "typeset -x foo=1\n"));
+        })
+    }
+
+    #[test]
+    fn printing_undefined_variable() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("typeset"),
+                is_special: false,
+            });
+            env.variables
+                .assign(Global, "foo".to_string(), Variable::new("1"))
+                .unwrap();
+
+            let args = Field::dummies(["-p", "none", "foo"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::FAILURE));
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "typeset foo=1\n"));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("`none`"), "{stderr:?}")
+            });
+        })
+    }
+}
@@ -0,0 +1,375 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Ulimit built-in
+//!
+//! The **`ulimit`** built-in shows or sets system resource limits.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! ulimit [-H|-S] [-a|-resource] [limit]
+//! ```
+//!
+//! # Semantics
+//!
+//! If the *limit* operand is given, the built-in sets the limit of the
+//! resource to the value. Otherwise, it prints the current limit.
+//!
+//! Each resource has two limits: the soft limit, which is actually enforced,
+//! and the hard limit, which is the ceiling for the soft limit. A process can
+//! freely lower the hard limit, but cannot raise it without a special
+//! privilege.
+//!
+//! # Options
+//!
+//! The **`-H`** (**`--hard`**) option selects the hard limit. The **`-S`**
+//! (**`--soft`**) option selects the soft limit. When printing, the soft limit
+//! is printed by default. When setting, both the limits are set by default.
+//!
+//! The **`-a`** (**`--all`**) option prints the limits of all the supported
+//! resources. It cannot be used with the *limit* operand.
+//!
+//! The following options select the resource to show or set. If none of them
+//! is specified, the **`-f`** option is assumed. Which resources are
+//! supported depends on the operating system.
+//!
+//! - **`-c`**: maximum size of core files in 512-byte blocks
+//! - **`-d`**: maximum size of a process's data segment in kilobytes
+//! - **`-e`**: maximum scheduling priority (nice value)
+//! - **`-f`**: maximum size of files created by the shell and its children
+//!   in 512-byte blocks
+//! - **`-i`**: maximum number of pending signals
+//! - **`-l`**: maximum size of memory locked into RAM in kilobytes
+//! - **`-m`**: maximum resident set size in kilobytes
+//! - **`-n`**: maximum file descriptor value plus one
+//! - **`-q`**: maximum size of POSIX message queues in bytes
+//! - **`-r`**: maximum real-time scheduling priority
+//! - **`-s`**: maximum size of a process's stack in kilobytes
+//! - **`-t`**: maximum CPU time in seconds
+//! - **`-u`**: maximum number of processes for the user
+//! - **`-v`**: maximum size of a process's virtual memory in kilobytes
+//! - **`-x`**: maximum number of file locks
+//!
+//! # Operands
+//!
+//! The *limit* operand is a non-negative decimal integer in the unit of the
+//! resource, or `unlimited` to remove the limit.
+//!
+//! # Exit status
+//!
+//! Zero unless an error occurs.
+//!
+//! # Errors
+//!
+//! It is an error if the *limit* operand is invalid or the limit cannot be
+//! set, for example, because the new hard limit exceeds the current one.
+//!
+//! # Portability
+//!
+//! POSIX only defines the `-f` option. The other options are extensions that
+//! are also found in many other shells. Some shells print both limits when
+//! both `-H` and `-S` are given to print a limit.
+
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax::OptionSpec;
+use crate::common::syntax_error;
+use crate::common::BuiltinEnv;
+use crate::common::Print;
+use std::fmt::Write;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::system::resource::Limit;
+use yash_env::system::resource::INFINITY;
+use yash_env::Env;
+use yash_env::System;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+use yash_syntax::source::Location;
+
+pub mod resource;
+
+/// Which of the soft and hard limits to show or set
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Selection {
+    soft: bool,
+    hard: bool,
+}
+
+/// Returns the option specifications for the built-in.
+fn option_specs() -> Vec<OptionSpec<'static>> {
+    let common = [
+        OptionSpec::new().short('a').long("all"),
+        OptionSpec::new().short('H').long("hard"),
+        OptionSpec::new().short('S').long("soft"),
+    ];
+    let resources = resource::RESOURCES
+        .iter()
+        .map(|info| OptionSpec::new().short(info.option));
+    common.into_iter().chain(resources).collect()
+}
+
+/// Formats a limit value in the unit of the resource.
+fn format_limit(limit: Limit, unit: Limit) -> String {
+    if limit == INFINITY {
+        "unlimited".to_string()
+    } else {
+        (limit / unit).to_string()
+    }
+}
+
+/// Parses the limit operand.
+///
+/// Returns `None` if the operand is not a valid limit.
+pub fn parse_limit(operand: &str, unit: Limit) -> Option<Limit> {
+    if operand == "unlimited" {
+        return Some(INFINITY);
+    }
+    if !operand.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    operand.parse::<Limit>().ok()?.checked_mul(unit)
+}
+
+fn system_error_message<'a>(
+    title: &'static str,
+    label: String,
+    location: &'a Location,
+) -> Message<'a> {
+    Message {
+        r#type: AnnotationType::Error,
+        title: title.into(),
+        annotations: vec![Annotation::new(
+            AnnotationType::Error,
+            label.into(),
+            location,
+        )],
+    }
+}
+
+/// Prints the limits of all the resources.
+async fn print_all(env: &mut Env, selection: Selection) -> Result {
+    let mut output = String::new();
+    for info in resource::RESOURCES {
+        let Ok(limits) = env.system.getrlimit(info.resource) else {
+            continue;
+        };
+        let limit = if selection.hard && !selection.soft {
+            limits.hard
+        } else {
+            limits.soft
+        };
+        let value = format_limit(limit, info.unit);
+        writeln!(
+            output,
+            "-{}: {:<30}{}",
+            info.option, info.description, value
+        )
+        .unwrap();
+    }
+    env.print(&output).await
+}
+
+/// Entry point for executing the `ulimit` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let specs = option_specs();
+    let (options, operands) = match parse_arguments(&specs, Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+
+    let mut all = false;
+    let mut selection = Selection::default();
+    let mut info = resource::find('f').unwrap();
+    for option in &options {
+        match option.spec.get_short() {
+            Some('a') => all = true,
+            Some('H') => selection.hard = true,
+            Some('S') => selection.soft = true,
+            Some(c) => info = resource::find(c).unwrap(),
+            None => unreachable!("unhandled option: {:?}", option),
+        }
+    }
+
+    let limit = match operands.as_slice() {
+        [] => None,
+        [limit] => Some(limit),
+        [_, redundant, ..] => {
+            return syntax_error(env, "too many operands", &redundant.origin).await
+        }
+    };
+
+    let Some(limit) = limit else {
+        if all {
+            return print_all(env, selection).await;
+        }
+        let limits = match env.system.getrlimit(info.resource) {
+            Ok(limits) => limits,
+            Err(errno) => {
+                let location = env.builtin_name().origin.clone();
+                let message = system_error_message(
+                    "cannot get the resource limit",
+                    errno.desc().to_string(),
+                    &location,
+                );
+                return print_failure_message(env, message).await;
+            }
+        };
+        let limit = if selection.hard && !selection.soft {
+            limits.hard
+        } else {
+            limits.soft
+        };
+        let output = format!("{}\n", format_limit(limit, info.unit));
+        return env.print(&output).await;
+    };
+
+    if all {
+        let label = "the -a option cannot be used with an operand";
+        return syntax_error(env, label, &limit.origin).await;
+    }
+    let Some(value) = parse_limit(&limit.value, info.unit) else {
+        return syntax_error(env, "invalid limit", &limit.origin).await;
+    };
+    if !selection.soft && !selection.hard {
+        selection = Selection {
+            soft: true,
+            hard: true,
+        };
+    }
+
+    let result = env.system.getrlimit(info.resource).and_then(|mut limits| {
+        if selection.soft {
+            limits.soft = value;
+        }
+        if selection.hard {
+            limits.hard = value;
+        }
+        env.system.setrlimit(info.resource, limits)
+    });
+    match result {
+        Ok(()) => Result::default(),
+        Err(errno) => {
+            let label = format!("{}: {}", info.description, errno.desc());
+            let message =
+                system_error_message("cannot set the resource limit", label, &limit.origin);
+            print_failure_message(env, message).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
+    use yash_env::system::resource::LimitPair;
+    use yash_env::system::resource::Resource;
+
+    #[test]
+    fn parsing_limits() {
+        assert_eq!(parse_limit("unlimited", 512), Some(INFINITY));
+        assert_eq!(parse_limit("0", 512), Some(0));
+        assert_eq!(parse_limit("3", 512), Some(1536));
+        assert_eq!(parse_limit("-1", 1), None);
+        assert_eq!(parse_limit("+1", 1), None);
+        assert_eq!(parse_limit("", 1), None);
+        assert_eq!(parse_limit(&Limit::MAX.to_string(), 2), None);
+    }
+
+    #[test]
+    fn printing_default_resource() {
+        in_virtual_system(|mut env, state| async move {
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "unlimited\n"));
+        })
+    }
+
+    #[test]
+    fn setting_and_printing_soft_and_hard_limits() {
+        in_virtual_system(|mut env, state| async move {
+            let result = main(&mut env, Field::dummies(["-c", "8"])).await;
+            assert_eq!(result, Result::default());
+            let result = main(&mut env, Field::dummies(["-S", "-c", "2"])).await;
+            assert_eq!(result, Result::default());
+            assert_eq!(
+                env.system.getrlimit(Resource::RLIMIT_CORE),
+                Ok(LimitPair {
+                    soft: 1024,
+                    hard: 4096
+                })
+            );
+
+            let result = main(&mut env, Field::dummies(["-c"])).await;
+            assert_eq!(result, Result::default());
+            let result = main(&mut env, Field::dummies(["-Hc"])).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "2\n8\n"));
+        })
+    }
+
+    #[test]
+    fn printing_all_limits() {
+        in_virtual_system(|mut env, state| async move {
+            let result = main(&mut env, Field::dummies(["-a"])).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| {
+                assert!(
+                    stdout.contains("-f: file size (blocks)            unlimited\n"),
+                    "{stdout:?}"
+                );
+                assert!(
+                    stdout.contains("-n: open files                    1024\n"),
+                    "{stdout:?}"
+                );
+                assert_eq!(stdout.lines().count(), resource::RESOURCES.len());
+            });
+        })
+    }
+
+    #[test]
+    fn raising_hard_limit() {
+        in_virtual_system(|mut env, _state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("ulimit"),
+                is_special: false,
+            });
+            let result = main(&mut env, Field::dummies(["-n", "2048"])).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+        })
+    }
+
+    #[test]
+    fn invalid_limit() {
+        in_virtual_system(|mut env, _state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("ulimit"),
+                is_special: false,
+            });
+            let result = main(&mut env, Field::dummies(["foo"])).await;
+            assert_eq!(result.exit_status(), ExitStatus::ERROR);
+        })
+    }
+}
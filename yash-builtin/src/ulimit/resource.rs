@@ -0,0 +1,158 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Resources the ulimit built-in can handle
+
+use yash_env::system::resource::Limit;
+use yash_env::system::resource::Resource;
+
+/// Description of a resource
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Info {
+    /// Option character that selects the resource
+    pub option: char,
+    /// Resource
+    pub resource: Resource,
+    /// Human-readable description of the resource, including the unit
+    pub description: &'static str,
+    /// Number of bytes (or other base units) in one unit of the limit value
+    /// used in the built-in's operand and output
+    pub unit: Limit,
+}
+
+/// Resources the ulimit built-in can handle, sorted by the option character
+pub const RESOURCES: &[Info] = &[
+    Info {
+        option: 'c',
+        resource: Resource::RLIMIT_CORE,
+        description: "core file size (blocks)",
+        unit: 512,
+    },
+    Info {
+        option: 'd',
+        resource: Resource::RLIMIT_DATA,
+        description: "data segment size (kbytes)",
+        unit: 1024,
+    },
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    Info {
+        option: 'e',
+        resource: Resource::RLIMIT_NICE,
+        description: "scheduling priority",
+        unit: 1,
+    },
+    Info {
+        option: 'f',
+        resource: Resource::RLIMIT_FSIZE,
+        description: "file size (blocks)",
+        unit: 512,
+    },
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    Info {
+        option: 'i',
+        resource: Resource::RLIMIT_SIGPENDING,
+        description: "pending signals",
+        unit: 1,
+    },
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "linux"
+    ))]
+    Info {
+        option: 'l',
+        resource: Resource::RLIMIT_MEMLOCK,
+        description: "locked memory (kbytes)",
+        unit: 1024,
+    },
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "linux"
+    ))]
+    Info {
+        option: 'm',
+        resource: Resource::RLIMIT_RSS,
+        description: "resident set size (kbytes)",
+        unit: 1024,
+    },
+    Info {
+        option: 'n',
+        resource: Resource::RLIMIT_NOFILE,
+        description: "open files",
+        unit: 1,
+    },
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    Info {
+        option: 'q',
+        resource: Resource::RLIMIT_MSGQUEUE,
+        description: "message queue size (bytes)",
+        unit: 1,
+    },
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    Info {
+        option: 'r',
+        resource: Resource::RLIMIT_RTPRIO,
+        description: "real-time priority",
+        unit: 1,
+    },
+    Info {
+        option: 's',
+        resource: Resource::RLIMIT_STACK,
+        description: "stack size (kbytes)",
+        unit: 1024,
+    },
+    Info {
+        option: 't',
+        resource: Resource::RLIMIT_CPU,
+        description: "CPU time (seconds)",
+        unit: 1,
+    },
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "linux"
+    ))]
+    Info {
+        option: 'u',
+        resource: Resource::RLIMIT_NPROC,
+        description: "processes",
+        unit: 1,
+    },
+    #[cfg(not(any(target_os = "freebsd", target_os = "netbsd", target_os = "openbsd")))]
+    Info {
+        option: 'v',
+        resource: Resource::RLIMIT_AS,
+        description: "virtual memory (kbytes)",
+        unit: 1024,
+    },
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    Info {
+        option: 'x',
+        resource: Resource::RLIMIT_LOCKS,
+        description: "file locks",
+        unit: 1,
+    },
+];
+
+/// Returns the resource selected by the option character.
+#[must_use]
+pub fn find(option: char) -> Option<&'static Info> {
+    RESOURCES.iter().find(|info| info.option == option)
+}
@@ -0,0 +1,212 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Umask built-in
+//!
+//! The **`umask`** built-in shows or sets the file mode creation mask.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! umask [-S] [mode]
+//! ```
+//!
+//! # Semantics
+//!
+//! The file mode creation mask is a set of permission bits that are removed
+//! from the permissions of newly created files.
+//!
+//! If the *mode* operand is given, the built-in sets the mask to the mode.
+//! Otherwise, it prints the current mask.
+//!
+//! # Options
+//!
+//! The **`-S`** (**`--symbolic`**) option makes the built-in print the mask in
+//! the symbolic form like `u=rwx,g=rx,o=rx`, which lists the permissions
+//! that are *not* masked. Without this option, the mask is printed as an octal
+//! number like `0022`.
+//!
+//! # Operands
+//!
+//! The *mode* operand is either an octal number or a symbolic mode. An octal
+//! number is the new mask itself. A symbolic mode is a comma-separated list of
+//! clauses like `u=rwx,g+r,o-w` that modify the permissions that are not
+//! masked, in the same manner as the `chmod` utility. See the
+//! [`symbolic`] module for the syntax.
+//!
+//! # Exit status
+//!
+//! Zero unless an error occurs.
+//!
+//! # Errors
+//!
+//! It is an error if the *mode* operand is not a valid octal number or
+//! symbolic mode.
+//!
+//! # Portability
+//!
+//! The `--symbolic` form of the option is not portable.
+//!
+//! POSIX does not specify the output format when the `-S` option is not
+//! specified, but it must be re-inputtable as the *mode* operand.
+
+use crate::common::print_error_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax::OptionSpec;
+use crate::common::syntax_error;
+use crate::common::Print;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::Env;
+use yash_env::System;
+
+pub mod symbolic;
+
+const OPTION_SPECS: &[OptionSpec] = &[OptionSpec::new().short('S').long("symbolic")];
+
+/// Permission bits that can be masked
+const ALL: u32 = 0o777;
+
+/// Returns the current mask without changing it.
+fn current_mask(env: &mut Env) -> u32 {
+    let mask = env.system.umask(yash_env::system::Mode::empty());
+    env.system.umask(mask);
+    mask.bits()
+}
+
+/// Parses the mode operand and returns the new mask.
+///
+/// The `mask` is the current mask, which is used to interpret a symbolic mode.
+pub fn parse_mode(mode: &str, mask: u32) -> std::result::Result<u32, symbolic::Error> {
+    if !mode.is_empty() && mode.bytes().all(|b| b.is_ascii_digit()) {
+        if let Ok(mask) = u32::from_str_radix(mode, 8) {
+            if mask <= ALL {
+                return Ok(mask);
+            }
+        }
+    }
+    let permissions = symbolic::apply(mode, !mask & ALL)?;
+    Ok(!permissions & ALL)
+}
+
+/// Entry point for executing the `umask` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (options, operands) = match parse_arguments(OPTION_SPECS, Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+    let symbolic = !options.is_empty();
+
+    let mode = match operands.as_slice() {
+        [] => None,
+        [mode] => Some(mode),
+        [_, redundant, ..] => {
+            return syntax_error(env, "too many operands", &redundant.origin).await
+        }
+    };
+
+    let mask = current_mask(env);
+    let Some(mode) = mode else {
+        let output = if symbolic {
+            format!("{}\n", symbolic::format(!mask & ALL))
+        } else {
+            format!("{mask:04o}\n")
+        };
+        return env.print(&output).await;
+    };
+
+    match parse_mode(&mode.value, mask) {
+        Ok(new_mask) => {
+            let new_mask = yash_env::system::Mode::from_bits_truncate(new_mask);
+            env.system.umask(new_mask);
+            Result::default()
+        }
+        Err(error) => {
+            let origin = mode.origin.clone();
+            syntax_error(env, &error.to_string(), &origin).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stdout;
+    use crate::tests::in_virtual_system;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
+
+    #[test]
+    fn parsing_octal_modes() {
+        assert_eq!(parse_mode("0", 0o022), Ok(0));
+        assert_eq!(parse_mode("027", 0o022), Ok(0o027));
+        assert_eq!(parse_mode("777", 0o022), Ok(0o777));
+        assert!(parse_mode("1000", 0o022).is_err());
+        assert!(parse_mode("8", 0o022).is_err());
+    }
+
+    #[test]
+    fn parsing_symbolic_modes() {
+        assert_eq!(parse_mode("u=rwx,g=rx,o=", 0o022), Ok(0o027));
+        assert_eq!(parse_mode("g-w", 0o002), Ok(0o022));
+        assert_eq!(parse_mode("o+r", 0o077), Ok(0o073));
+    }
+
+    #[test]
+    fn printing_octal_mask() {
+        in_virtual_system(|mut env, state| async move {
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "0022\n"));
+        })
+    }
+
+    #[test]
+    fn printing_symbolic_mask() {
+        in_virtual_system(|mut env, state| async move {
+            let result = main(&mut env, Field::dummies(["-S"])).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "u=rwx,g=rx,o=rx\n"));
+        })
+    }
+
+    #[test]
+    fn setting_mask() {
+        in_virtual_system(|mut env, state| async move {
+            let result = main(&mut env, Field::dummies(["g-r,o="])).await;
+            assert_eq!(result, Result::default());
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result, Result::default());
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "0067\n"));
+        })
+    }
+
+    #[test]
+    fn invalid_mode() {
+        in_virtual_system(|mut env, _state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("umask"),
+                is_special: false,
+            });
+            let result = main(&mut env, Field::dummies(["u=q"])).await;
+            assert_eq!(result.exit_status(), ExitStatus::ERROR);
+            assert_eq!(current_mask(&mut env), 0o022);
+        })
+    }
+}
@@ -0,0 +1,192 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Symbolic representation of file mode creation masks
+//!
+//! The [`apply`] function modifies file permission bits according to a
+//! symbolic mode like `u=rwx,g+r,o-w`, and the [`format`] function returns a
+//! symbolic representation of permission bits like `u=rwx,g=rx,o=rx`.
+//!
+//! Note that the symbolic modes describe permissions to be allowed, which is
+//! the complement of the mask.
+
+use thiserror::Error;
+
+/// Permission bits of all the users
+const ALL: u32 = 0o777;
+
+/// Error in parsing a symbolic mode
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum Error {
+    /// A clause has no operator (`+`, `-`, or `=`).
+    #[error("missing operator in {0:?}")]
+    MissingOperator(String),
+
+    /// An unexpected character was found.
+    #[error("unexpected character {0:?}")]
+    UnexpectedChar(char),
+}
+
+/// Returns the permission bits selected by the "who" character.
+fn who_bits(c: char) -> Option<u32> {
+    match c {
+        'u' => Some(0o700),
+        'g' => Some(0o070),
+        'o' => Some(0o007),
+        'a' => Some(ALL),
+        _ => None,
+    }
+}
+
+/// Applies one clause of a symbolic mode to the permission bits.
+fn apply_clause(clause: &str, mut permissions: u32) -> Result<u32, Error> {
+    let mut chars = clause.chars().peekable();
+
+    let mut who = 0;
+    while let Some(bits) = chars.peek().copied().and_then(who_bits) {
+        who |= bits;
+        chars.next();
+    }
+    if who == 0 {
+        who = ALL;
+    }
+
+    if chars.peek().is_none() {
+        return Err(Error::MissingOperator(clause.to_owned()));
+    }
+
+    while let Some(op) = chars.next() {
+        if !matches!(op, '+' | '-' | '=') {
+            return Err(Error::UnexpectedChar(op));
+        }
+
+        let mut bits = 0;
+        while let Some(&c) = chars.peek() {
+            bits |= match c {
+                'r' => 0o444,
+                'w' => 0o222,
+                'x' => 0o111,
+                'X' if permissions & 0o111 != 0 => 0o111,
+                'X' | 's' | 't' => 0,
+                'u' => ((permissions >> 6) & 7) * 0o111,
+                'g' => ((permissions >> 3) & 7) * 0o111,
+                'o' => (permissions & 7) * 0o111,
+                '+' | '-' | '=' => break,
+                _ => return Err(Error::UnexpectedChar(c)),
+            };
+            chars.next();
+        }
+        bits &= who;
+
+        permissions = match op {
+            '+' => permissions | bits,
+            '-' => permissions & !bits,
+            _ => (permissions & !who) | bits,
+        };
+    }
+
+    Ok(permissions)
+}
+
+/// Applies a symbolic mode to the permission bits.
+///
+/// The mode is a comma-separated list of clauses, each of which consists of
+/// zero or more "who" characters (`u`, `g`, `o`, `a`) followed by one or more
+/// actions. An action is an operator (`+`, `-`, `=`) followed by permission
+/// characters (`r`, `w`, `x`, `X`, `s`, `t`) or a "who" character (`u`, `g`,
+/// `o`) to copy the permissions from. The `s` and `t` characters are accepted
+/// but have no effect.
+///
+/// Returns the new permission bits.
+pub fn apply(mode: &str, permissions: u32) -> Result<u32, Error> {
+    mode.split(',')
+        .try_fold(permissions & ALL, |permissions, clause| {
+            apply_clause(clause, permissions)
+        })
+}
+
+/// Returns the symbolic representation of the permission bits.
+#[must_use]
+pub fn format(permissions: u32) -> String {
+    let mut result = String::new();
+    for (who, shift) in [('u', 6), ('g', 3), ('o', 0)] {
+        if who != 'u' {
+            result.push(',');
+        }
+        result.push(who);
+        result.push('=');
+        let bits = permissions >> shift;
+        if bits & 4 != 0 {
+            result.push('r');
+        }
+        if bits & 2 != 0 {
+            result.push('w');
+        }
+        if bits & 1 != 0 {
+            result.push('x');
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assigning_permissions() {
+        assert_eq!(apply("u=rwx,g=rx,o=", 0), Ok(0o750));
+        assert_eq!(apply("=r", 0o777), Ok(0o444));
+        assert_eq!(apply("go=", 0o777), Ok(0o700));
+        assert_eq!(apply("a=rw", 0o111), Ok(0o666));
+    }
+
+    #[test]
+    fn adding_and_removing_permissions() {
+        assert_eq!(apply("g+w", 0o755), Ok(0o775));
+        assert_eq!(apply("o-rx", 0o755), Ok(0o750));
+        assert_eq!(apply("+x", 0o644), Ok(0o755));
+        assert_eq!(apply("u-w+x", 0o644), Ok(0o544));
+    }
+
+    #[test]
+    fn copying_permissions() {
+        assert_eq!(apply("g=u", 0o700), Ok(0o770));
+        assert_eq!(apply("o=g", 0o750), Ok(0o755));
+        assert_eq!(apply("u=o", 0o005), Ok(0o505));
+    }
+
+    #[test]
+    fn conditional_execute_permission() {
+        assert_eq!(apply("a+X", 0o644), Ok(0o644));
+        assert_eq!(apply("a+X", 0o744), Ok(0o755));
+    }
+
+    #[test]
+    fn invalid_modes() {
+        assert_eq!(apply("u", 0), Err(Error::MissingOperator("u".to_owned())));
+        assert_eq!(apply("", 0), Err(Error::MissingOperator("".to_owned())));
+        assert_eq!(apply("u=rwz", 0), Err(Error::UnexpectedChar('z')));
+        assert_eq!(apply("k=r", 0), Err(Error::UnexpectedChar('k')));
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(format(0o755), "u=rwx,g=rx,o=rx");
+        assert_eq!(format(0o640), "u=rw,g=r,o=");
+        assert_eq!(format(0), "u=,g=,o=");
+    }
+}
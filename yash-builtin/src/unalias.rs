@@ -0,0 +1,178 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Unalias built-in
+//!
+//! The **`unalias`** built-in removes alias definitions.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! unalias name...
+//! ```
+//!
+//! ```sh
+//! unalias -a
+//! ```
+//!
+//! # Semantics
+//!
+//! The unalias built-in removes the definitions of the aliases specified by
+//! the operands.
+//!
+//! # Options
+//!
+//! The **`-a`** (**`--all`**) option removes all alias definitions.
+//!
+//! # Operands
+//!
+//! Operands are names of aliases to be removed. Operands are ignored if the
+//! `-a` option is specified.
+//!
+//! # Exit status
+//!
+//! Zero unless an error occurs.
+//!
+//! # Errors
+//!
+//! It is an error if an operand names an alias that is not defined. The other
+//! operands are still processed.
+//!
+//! It is a syntax error if there are no operands and the `-a` option is not
+//! specified.
+//!
+//! # Portability
+//!
+//! The `--all` form of the option is not portable.
+
+use crate::common::not_found_message;
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax::OptionSpec;
+use crate::common::syntax_error;
+use crate::common::BuiltinEnv;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::Env;
+
+const OPTION_SPECS: &[OptionSpec] = &[OptionSpec::new().short('a').long("all")];
+
+/// Entry point for executing the `unalias` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (options, operands) = match parse_arguments(OPTION_SPECS, Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+
+    if !options.is_empty() {
+        env.aliases.clear();
+        return Result::default();
+    }
+
+    if operands.is_empty() {
+        let location = env.builtin_name().origin.clone();
+        return syntax_error(env, "no operands", &location).await;
+    }
+
+    let mut result = Result::default();
+    for operand in operands {
+        if !env.aliases.remove(operand.value.as_str()) {
+            result = print_failure_message(env, not_found_message("alias", &operand)).await;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
+    use yash_syntax::alias::HashEntry;
+    use yash_syntax::source::Location;
+
+    fn define_aliases(env: &mut Env, names: &[&str]) {
+        for name in names {
+            env.aliases.insert(HashEntry::new(
+                name.to_string(),
+                "value".to_string(),
+                false,
+                Location::dummy(""),
+            ));
+        }
+    }
+
+    #[test]
+    fn removing_named_aliases() {
+        let mut env = Env::new_virtual();
+        define_aliases(&mut env, &["a", "b", "c"]);
+
+        let args = Field::dummies(["a", "c"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(env.aliases.len(), 1);
+        assert!(env.aliases.contains("b"));
+    }
+
+    #[test]
+    fn removing_all_aliases() {
+        let mut env = Env::new_virtual();
+        define_aliases(&mut env, &["a", "b", "c"]);
+
+        let args = Field::dummies(["-a"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert!(env.aliases.is_empty());
+    }
+
+    #[test]
+    fn removing_undefined_alias() {
+        in_virtual_system(|mut env, state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("unalias"),
+                is_special: false,
+            });
+            define_aliases(&mut env, &["a", "b"]);
+
+            let args = Field::dummies(["x", "a"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result, Result::new(ExitStatus::FAILURE));
+            assert!(!env.aliases.contains("a"));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("`x`"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn no_operands() {
+        in_virtual_system(|mut env, _state| async move {
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("unalias"),
+                is_special: false,
+            });
+            let result = main(&mut env, vec![]).await;
+            assert_eq!(result.exit_status(), ExitStatus::ERROR);
+        })
+    }
+}
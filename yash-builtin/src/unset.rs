@@ -0,0 +1,277 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Unset built-in
+//!
+//! The **`unset`** built-in removes variables or functions.
+//!
+//! # Syntax
+//!
+//! ```sh
+//! unset [-f|-v] name...
+//! ```
+//!
+//! # Semantics
+//!
+//! The unset built-in removes the variables or functions named by the
+//! operands. Removing a variable that does not exist is not an error.
+//!
+//! # Options
+//!
+//! The **`-f`** (**`--functions`**) option makes the built-in remove
+//! functions.
+//!
+//! The **`-v`** (**`--variables`**) option makes the built-in remove
+//! variables. This is the default.
+//!
+//! These two options are mutually exclusive. The last specified one applies if
+//! given both.
+//!
+//! # Operands
+//!
+//! Operands are the names of variables or functions to be removed.
+//!
+//! # Exit status
+//!
+//! Zero unless an error occurs.
+//!
+//! # Errors
+//!
+//! It is an error to remove a read-only variable or function. The built-in
+//! continues to remove the remaining operands after the error. Since the
+//! unset built-in is a special built-in, the error interrupts the shell if it
+//! is not interactive.
+//!
+//! # Portability
+//!
+//! POSIX does not specify the behavior when both `-f` and `-v` are given. The
+//! long options are not portable.
+//!
+//! When a variable is defined in multiple [contexts](yash_env::variable), the
+//! built-in removes the variable from all the contexts. Some other shells only
+//! remove the variable in the innermost context.
+
+use crate::common::print_error_message;
+use crate::common::print_failure_message;
+use crate::common::syntax::parse_arguments;
+use crate::common::syntax::Mode;
+use crate::common::syntax::OptionOccurrence;
+use crate::common::syntax::OptionSpec;
+use yash_env::builtin::Result;
+use yash_env::semantics::Field;
+use yash_env::variable::Scope::Global;
+use yash_env::Env;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::Message;
+use yash_syntax::source::Location;
+
+/// What the built-in removes
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Target {
+    /// Removes variables.
+    #[default]
+    Variables,
+    /// Removes functions.
+    Functions,
+}
+
+const OPTION_SPECS: &[OptionSpec] = &[
+    OptionSpec::new().short('f').long("functions"),
+    OptionSpec::new().short('v').long("variables"),
+];
+
+fn target_for_option(option: &OptionOccurrence) -> Target {
+    match option.spec.get_short() {
+        Some('f') => Target::Functions,
+        Some('v') => Target::Variables,
+        _ => unreachable!(),
+    }
+}
+
+/// Creates an error message for a read-only variable or function.
+fn read_only_error_message<'a>(
+    target: Target,
+    name: &'a Field,
+    read_only_location: &'a Location,
+) -> Message<'a> {
+    let (kind, info) = match target {
+        Target::Variables => ("variable", "the variable was made read-only here"),
+        Target::Functions => ("function", "the function was defined here"),
+    };
+    Message {
+        r#type: AnnotationType::Error,
+        title: format!("cannot unset read-only {kind} `{}`", name.value).into(),
+        annotations: vec![
+            Annotation::new(
+                AnnotationType::Error,
+                format!("{kind} `{}` is read-only", name.value).into(),
+                &name.origin,
+            ),
+            Annotation::new(AnnotationType::Info, info.into(), read_only_location),
+        ],
+    }
+}
+
+/// Removes the named variable or function.
+///
+/// If the variable or function is read-only, this function returns the
+/// location where it was made read-only.
+fn unset(env: &mut Env, target: Target, name: &str) -> std::result::Result<(), Location> {
+    match target {
+        Target::Variables => match env.variables.unset(Global, name) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(error.read_only_location),
+        },
+        Target::Functions => match env.functions.get(name) {
+            Some(function) if function.0.is_read_only => Err(function.0.origin.clone()),
+            _ => {
+                env.functions.remove(name);
+                Ok(())
+            }
+        },
+    }
+}
+
+/// Entry point for executing the `unset` built-in
+///
+/// See the [module-level documentation](self) for details.
+pub async fn main(env: &mut Env, args: Vec<Field>) -> Result {
+    let (options, operands) = match parse_arguments(OPTION_SPECS, Mode::with_env(env), args) {
+        Ok(result) => result,
+        Err(error) => return print_error_message(env, &error).await,
+    };
+    let target = options.last().map(target_for_option).unwrap_or_default();
+
+    let mut result = Result::default();
+    for name in &operands {
+        if let Err(location) = unset(env, target, &name.value) {
+            let message = read_only_error_message(target, name, &location);
+            result = print_failure_message(env, message).await;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use std::ops::ControlFlow::Break;
+    use std::rc::Rc;
+    use yash_env::function::HashEntry;
+    use yash_env::semantics::Divert;
+    use yash_env::semantics::ExitStatus;
+    use yash_env::stack::Frame;
+    use yash_env::variable::Variable;
+
+    fn define_function(env: &mut Env, name: &str, is_read_only: bool) {
+        let body = Rc::new("{ :; }".parse().unwrap());
+        let origin = Location::dummy(name);
+        let entry = HashEntry::new(name.to_string(), body, origin, is_read_only);
+        env.functions.insert(entry);
+    }
+
+    #[test]
+    fn unsetting_variables() {
+        let mut env = Env::new_virtual();
+        for name in ["foo", "bar", "baz"] {
+            env.variables
+                .assign(Global, name.to_string(), Variable::new("value"))
+                .unwrap();
+        }
+        define_function(&mut env, "foo", false);
+
+        let args = Field::dummies(["foo", "baz", "none"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert_eq!(env.variables.get("foo"), None);
+        assert_eq!(env.variables.get("bar"), Some(&Variable::new("value")));
+        assert_eq!(env.variables.get("baz"), None);
+        assert!(env.functions.contains("foo"));
+    }
+
+    #[test]
+    fn unsetting_functions() {
+        let mut env = Env::new_virtual();
+        env.variables
+            .assign(Global, "foo".to_string(), Variable::new("value"))
+            .unwrap();
+        define_function(&mut env, "foo", false);
+        define_function(&mut env, "bar", false);
+
+        let args = Field::dummies(["-v", "-f", "foo", "none"]);
+        let result = main(&mut env, args).now_or_never().unwrap();
+        assert_eq!(result, Result::default());
+        assert!(!env.functions.contains("foo"));
+        assert!(env.functions.contains("bar"));
+        assert_eq!(env.variables.get("foo"), Some(&Variable::new("value")));
+    }
+
+    #[test]
+    fn unsetting_read_only_variable() {
+        in_virtual_system(|mut env, state| async move {
+            let var = Variable::new("value").make_read_only(Location::dummy("readonly"));
+            env.variables
+                .assign(Global, "foo".to_string(), var.clone())
+                .unwrap();
+            env.variables
+                .assign(Global, "bar".to_string(), Variable::new("value"))
+                .unwrap();
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("unset"),
+                is_special: true,
+            });
+
+            let args = Field::dummies(["foo", "bar"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert_eq!(result.divert(), Break(Divert::Interrupt(None)));
+            assert_eq!(env.variables.get("foo"), Some(&var));
+            assert_eq!(env.variables.get("bar"), None);
+            assert_stderr(&state, |stderr| {
+                assert!(
+                    stderr.contains("cannot unset read-only variable `foo`"),
+                    "{stderr:?}"
+                )
+            });
+        })
+    }
+
+    #[test]
+    fn unsetting_read_only_function() {
+        in_virtual_system(|mut env, state| async move {
+            define_function(&mut env, "foo", true);
+            let mut env = env.push_frame(Frame::Builtin {
+                name: Field::dummy("unset"),
+                is_special: true,
+            });
+
+            let args = Field::dummies(["-f", "foo"]);
+            let result = main(&mut env, args).await;
+            assert_eq!(result.exit_status(), ExitStatus::FAILURE);
+            assert!(env.functions.contains("foo"));
+            assert_stderr(&state, |stderr| {
+                assert!(
+                    stderr.contains("cannot unset read-only function `foo`"),
+                    "{stderr:?}"
+                )
+            });
+        })
+    }
+}
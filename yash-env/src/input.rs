@@ -31,29 +31,38 @@ use std::slice::from_mut;
 #[doc(no_inline)]
 pub use yash_syntax::input::*;
 
-// TODO Redefine Stdin as FdReader to support FDs other than stdin
-
-/// Input function that reads from the standard input.
+/// Input function that reads from a file descriptor.
 ///
-/// An instance of `Stdin` contains a [`SharedSystem`] to read the input from,
-/// as well as the current line number.
+/// An instance of `FdReader` contains a [`SharedSystem`] to read the input
+/// from, as well as the file descriptor to read from. It can be used to read
+/// commands from the standard input or from a script file.
 ///
-/// Although `Stdin` implements `Clone`, it does not mean you can create and
-/// keep a copy of a `Stdin` instance to replay the input later. Since both the
-/// original and clone share the same `SharedSystem`, reading a line from one
-/// instance will affect the next read from the other instance.
+/// Although `FdReader` implements `Clone`, it does not mean you can create and
+/// keep a copy of a `FdReader` instance to replay the input later. Since both
+/// the original and clone share the same `SharedSystem`, reading a line from
+/// one instance will affect the next read from the other instance.
 #[derive(Clone, Debug)]
-pub struct Stdin {
+pub struct FdReader {
+    /// File descriptor to read from
+    fd: Fd,
     /// System to interact with the FD
     system: SharedSystem,
     /// Whether lines read are echoed to stderr
     echo: Option<Rc<Cell<State>>>,
 }
 
-impl Stdin {
-    /// Creates a new `Stdin` instance.
-    pub fn new(system: SharedSystem) -> Self {
-        Stdin { system, echo: None }
+impl FdReader {
+    /// Creates a new `FdReader` instance.
+    ///
+    /// The `fd` argument is the file descriptor to read from. It should be
+    /// readable, have the close-on-exec flag set, and remain open for the
+    /// lifetime of the `FdReader` instance.
+    pub fn new(fd: Fd, system: SharedSystem) -> Self {
+        FdReader {
+            fd,
+            system,
+            echo: None,
+        }
     }
 
     /// Sets the "echo" flag.
@@ -74,14 +83,14 @@ impl Stdin {
 }
 
 #[async_trait(?Send)]
-impl Input for Stdin {
+impl Input for FdReader {
     async fn next_line(&mut self, _context: &Context) -> Result {
         // TODO Read many bytes at once if seekable
 
         let mut bytes = Vec::new();
         loop {
             let mut byte = 0;
-            match self.system.read_async(Fd::STDIN, from_mut(&mut byte)).await {
+            match self.system.read_async(self.fd, from_mut(&mut byte)).await {
                 // End of input
                 Ok(0) => break,
 
@@ -115,24 +124,33 @@ impl Input for Stdin {
 mod tests {
     use super::*;
     use crate::system::r#virtual::FileBody;
+    use crate::system::r#virtual::INode;
     use crate::system::r#virtual::VirtualSystem;
     use crate::system::Errno;
+    use crate::system::Mode;
+    use crate::system::OFlag;
+    use crate::System;
     use assert_matches::assert_matches;
     use futures_util::FutureExt;
+    use std::cell::RefCell;
+    use std::ffi::CString;
 
     #[test]
-    fn stdin_empty() {
+    fn empty_reader() {
         let system = VirtualSystem::new();
         let system = SharedSystem::new(Box::new(system));
-        let mut stdin = Stdin::new(system);
+        let mut reader = FdReader::new(Fd::STDIN, system);
 
-        let result = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         let line = result.unwrap();
         assert_eq!(line, "");
     }
 
     #[test]
-    fn stdin_one_line() {
+    fn one_line_reader() {
         let system = VirtualSystem::new();
         {
             let state = system.state.borrow_mut();
@@ -140,18 +158,24 @@ mod tests {
             file.borrow_mut().body = FileBody::new(*b"echo ok\n");
         }
         let system = SharedSystem::new(Box::new(system));
-        let mut stdin = Stdin::new(system);
+        let mut reader = FdReader::new(Fd::STDIN, system);
 
-        let result = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         let line = result.unwrap();
         assert_eq!(line, "echo ok\n");
-        let result = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         let line = result.unwrap();
         assert_eq!(line, "");
     }
 
     #[test]
-    fn stdin_many_lines() {
+    fn reader_with_many_lines() {
         let system = VirtualSystem::new();
         {
             let state = system.state.borrow_mut();
@@ -159,30 +183,83 @@ mod tests {
             file.borrow_mut().body = FileBody::new(*b"#!/bin/sh\necho ok\nexit");
         }
         let system = SharedSystem::new(Box::new(system));
-        let mut stdin = Stdin::new(system);
+        let mut reader = FdReader::new(Fd::STDIN, system);
 
-        let result = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         let line = result.unwrap();
         assert_eq!(line, "#!/bin/sh\n");
-        let result = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         let line = result.unwrap();
         assert_eq!(line, "echo ok\n");
-        let result = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         let line = result.unwrap();
         assert_eq!(line, "exit");
-        let result = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
+        let line = result.unwrap();
+        assert_eq!(line, "");
+    }
+
+    #[test]
+    fn reader_with_non_stdin_fd() {
+        let mut system = VirtualSystem::new();
+        {
+            let state = system.state.borrow_mut();
+            let file = state.file_system.get("/dev/stdin").unwrap();
+            file.borrow_mut().body = FileBody::new(*b"from stdin\n");
+        }
+        {
+            let mut state = system.state.borrow_mut();
+            let file = Rc::new(RefCell::new(INode::new(*b"echo one\necho two\n")));
+            state.file_system.save("/foo/script", file).unwrap();
+        }
+        let path = CString::new("/foo/script").unwrap();
+        let fd = system.open(&path, OFlag::O_RDONLY, Mode::empty()).unwrap();
+        let system = SharedSystem::new(Box::new(system));
+        let mut reader = FdReader::new(fd, system);
+
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
+        let line = result.unwrap();
+        assert_eq!(line, "echo one\n");
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
+        let line = result.unwrap();
+        assert_eq!(line, "echo two\n");
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         let line = result.unwrap();
         assert_eq!(line, "");
     }
 
     #[test]
-    fn stdin_error() {
+    fn reader_error() {
         let mut system = VirtualSystem::new();
         system.current_process_mut().close_fd(Fd::STDIN);
         let system = SharedSystem::new(Box::new(system));
-        let mut stdin = Stdin::new(system);
+        let mut reader = FdReader::new(Fd::STDIN, system);
 
-        let result = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let result = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         let error = result.unwrap_err();
         assert_eq!(error.raw_os_error(), Some(Errno::EBADF as i32));
     }
@@ -197,10 +274,13 @@ mod tests {
             file.borrow_mut().body = FileBody::new(*b"one\ntwo");
         }
         let system = SharedSystem::new(Box::new(system));
-        let mut stdin = Stdin::new(system);
-        stdin.set_echo(Some(Rc::new(Cell::new(State::Off))));
+        let mut reader = FdReader::new(Fd::STDIN, system);
+        reader.set_echo(Some(Rc::new(Cell::new(State::Off))));
 
-        let _ = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let _ = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         let state = state.borrow();
         let file = state.file_system.get("/dev/stderr").unwrap();
         assert_matches!(&file.borrow().body, FileBody::Regular { content, .. } => {
@@ -218,10 +298,13 @@ mod tests {
             file.borrow_mut().body = FileBody::new(*b"one\ntwo");
         }
         let system = SharedSystem::new(Box::new(system));
-        let mut stdin = Stdin::new(system);
-        stdin.set_echo(Some(Rc::new(Cell::new(State::On))));
+        let mut reader = FdReader::new(Fd::STDIN, system);
+        reader.set_echo(Some(Rc::new(Cell::new(State::On))));
 
-        let _ = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let _ = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         {
             let state = state.borrow();
             let file = state.file_system.get("/dev/stderr").unwrap();
@@ -229,7 +312,10 @@ mod tests {
                 assert_eq!(content, b"one\n");
             });
         }
-        let _ = stdin.next_line(&Context::default()).now_or_never().unwrap();
+        let _ = reader
+            .next_line(&Context::default())
+            .now_or_never()
+            .unwrap();
         {
             let state = state.borrow();
             let file = state.file_system.get("/dev/stderr").unwrap();
@@ -109,7 +109,9 @@ impl Job {
         }
     }
 
-    fn is_suspended(&self) -> bool {
+    /// Whether the job is suspended
+    #[must_use]
+    pub fn is_suspended(&self) -> bool {
         matches!(self.status, WaitStatus::Stopped(_, _))
     }
 }
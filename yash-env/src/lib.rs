@@ -42,6 +42,7 @@ use self::job::WaitStatusEx;
 use self::option::OptionSet;
 use self::option::{AllExport, ErrExit, Monitor};
 use self::option::{Off, On};
+use self::path_cache::PathCache;
 use self::semantics::Divert;
 use self::semantics::ExitStatus;
 use self::stack::Frame;
@@ -115,6 +116,15 @@ pub struct Env {
     /// Shell option settings.
     pub options: OptionSet,
 
+    /// Paths of external utilities found by the command search
+    pub path_cache: PathCache,
+
+    /// File descriptors opened by process substitutions
+    ///
+    /// The file descriptors are kept open while the command that uses them is
+    /// running. See [`close_process_subst_fds`](Self::close_process_subst_fds).
+    pub process_subst_fds: Vec<Fd>,
+
     /// Runtime execution context stack.
     pub stack: Stack,
 
@@ -152,6 +162,8 @@ impl Env {
             main_pgid: system.getpgrp(),
             main_pid: system.getpid(),
             options: Default::default(),
+            path_cache: Default::default(),
+            process_subst_fds: Default::default(),
             stack: Default::default(),
             traps: Default::default(),
             tty: Default::default(),
@@ -183,6 +195,8 @@ impl Env {
             main_pgid: self.main_pgid,
             main_pid: self.main_pid,
             options: self.options,
+            path_cache: self.path_cache.clone(),
+            process_subst_fds: self.process_subst_fds.clone(),
             stack: self.stack.clone(),
             traps: self.traps.clone(),
             tty: self.tty,
@@ -366,6 +380,20 @@ impl Env {
         }
     }
 
+    /// Closes file descriptors opened by process substitutions.
+    ///
+    /// This function closes and removes the file descriptors in
+    /// `self.process_subst_fds` except the first `keep` ones. A command that
+    /// performs expansions should remember the length of `process_subst_fds`
+    /// before the expansions and pass it to this function after finishing so
+    /// that the file descriptors are closed when no longer needed.
+    pub fn close_process_subst_fds(&mut self, keep: usize) {
+        let keep = keep.min(self.process_subst_fds.len());
+        for fd in self.process_subst_fds.drain(keep..) {
+            let _: Result<(), _> = self.system.close(fd);
+        }
+    }
+
     /// Applies all job status updates to jobs in `self.jobs`.
     ///
     /// This function calls [`self.system.wait`](System::wait) repeatedly until
@@ -388,8 +416,9 @@ impl Env {
     ///
     /// This function is a thin wrapper around [`VariableSet::assign`] that
     /// automatically applies the `AllExport` [shell
-    /// option](crate::option::Option). You should always prefer this unless you
-    /// want to ignore the option.
+    /// option](crate::option::Option). It also clears the [path
+    /// cache](Self::path_cache) when `PATH` is assigned. You should always
+    /// prefer this unless you want to ignore the option.
     pub fn assign_variable(
         &mut self,
         scope: Scope,
@@ -400,7 +429,12 @@ impl Env {
             On => value.export(),
             Off => value,
         };
-        self.variables.assign(scope, name, value)
+        let is_path = name == "PATH";
+        let result = self.variables.assign(scope, name, value);
+        if is_path && result.is_ok() {
+            self.path_cache.clear();
+        }
+        result
     }
 
     pub(crate) fn errexit_is_applicable(&self) -> bool {
@@ -443,6 +477,7 @@ pub mod input;
 pub mod io;
 pub mod job;
 pub mod option;
+pub mod path_cache;
 pub mod pwd;
 pub mod semantics;
 pub mod stack;
@@ -460,10 +495,12 @@ mod tests {
     use crate::system::r#virtual::SystemState;
     use crate::system::Errno;
     use crate::trap::Action;
+    use assert_matches::assert_matches;
     use futures_executor::LocalPool;
     use futures_util::task::LocalSpawnExt;
     use std::cell::Cell;
     use std::cell::RefCell;
+    use std::ffi::CString;
     use yash_syntax::source::Location;
 
     /// Helper function to perform a test in a virtual system with an executor.
@@ -643,6 +680,24 @@ mod tests {
         });
     }
 
+    #[test]
+    fn close_process_subst_fds_keeps_preceding_fds() {
+        let mut env = Env::new_virtual();
+        let (reader, writer) = env.system.pipe().unwrap();
+        env.process_subst_fds = vec![reader, writer];
+
+        env.close_process_subst_fds(1);
+        assert_eq!(env.process_subst_fds, [reader]);
+        assert_matches!(env.system.fcntl_getfl(reader), Ok(_));
+        assert_eq!(env.system.fcntl_getfl(writer), Err(Errno::EBADF));
+
+        env.close_process_subst_fds(5);
+        assert_eq!(env.process_subst_fds, [reader]);
+        env.close_process_subst_fds(0);
+        assert_eq!(env.process_subst_fds, []);
+        assert_eq!(env.system.fcntl_getfl(reader), Err(Errno::EBADF));
+    }
+
     #[test]
     fn update_all_subshell_statuses_without_subshells() {
         let mut env = Env::new_virtual();
@@ -748,6 +803,23 @@ mod tests {
         assert_eq!(env.variables.get("b").unwrap(), &b);
     }
 
+    #[test]
+    fn assign_variable_clears_path_cache_on_path_assignment() {
+        let mut env = Env::new_virtual();
+        let ls = CString::new("/bin/ls").unwrap();
+        env.path_cache.insert("ls".to_string(), ls.clone());
+        let result = env.assign_variable(Scope::Global, "FOO".to_string(), Variable::new("/"));
+        assert_eq!(result, Ok(None));
+        assert_eq!(env.path_cache.get("ls"), Some(ls.as_c_str()));
+
+        let path = Variable::new("/bin").set_assigned_location(Location::dummy("PATH"));
+        for _ in 0..2 {
+            env.path_cache.insert("ls".to_string(), ls.clone());
+            let _ = env.assign_variable(Scope::Global, "PATH".to_string(), path.clone());
+            assert_eq!(env.path_cache.get("ls"), None);
+        }
+    }
+
     #[test]
     fn errexit_on() {
         let mut env = Env::new_virtual();
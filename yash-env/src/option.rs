@@ -80,6 +80,8 @@ impl Not for State {
 pub enum Option {
     /// Makes all variables exported when they are assigned.
     AllExport,
+    /// Enables brace expansion.
+    BraceExpand,
     /// Allows overwriting and truncating an existing file with the `>`
     /// redirection.
     Clobber,
@@ -144,6 +146,7 @@ impl Option {
     pub const fn short_name(self) -> std::option::Option<(char, State)> {
         match self {
             AllExport => Some(('a', On)),
+            BraceExpand => None,
             Clobber => Some(('C', Off)),
             CmdLine => Some(('c', On)),
             ErrExit => Some(('e', On)),
@@ -173,6 +176,7 @@ impl Option {
     pub const fn long_name(self) -> &'static str {
         match self {
             AllExport => "allexport",
+            BraceExpand => "braceexpand",
             Clobber => "clobber",
             CmdLine => "cmdline",
             ErrExit => "errexit",
@@ -237,6 +241,7 @@ impl FromStr for Option {
     fn from_str(name: &str) -> Result<Self, FromStrError> {
         const OPTIONS: &[(&str, Option)] = &[
             ("allexport", AllExport),
+            ("braceexpand", BraceExpand),
             ("clobber", Clobber),
             ("cmdline", CmdLine),
             ("errexit", ErrExit),
@@ -0,0 +1,139 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Cache of command paths
+//!
+//! The [command search](../../yash_semantics/command_search/index.html)
+//! remembers the paths of external utilities it has found in a [`PathCache`]
+//! so that it does not have to search the `$PATH` again for the same command
+//! name.
+//!
+//! The cache is only valid for the `$PATH` it was built for.
+//! [`Env::assign_variable`](crate::Env::assign_variable) clears the cache
+//! whenever `$PATH` is assigned, even if the value does not change. The cache
+//! also records the value of `$PATH` and [`validate`](PathCache::validate)s
+//! itself against the current value before use, so that the cache is
+//! discarded when `$PATH` changes without an assignment, e.g., when it is
+//! unset or a temporary assignment expires.
+
+use crate::variable::Value;
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::ffi::CString;
+
+/// Collection of command paths found in `$PATH`
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PathCache {
+    /// Value of `$PATH` the cached paths were found in
+    path: Option<Value>,
+    /// Map from command names to paths
+    entries: BTreeMap<String, CString>,
+}
+
+impl PathCache {
+    /// Creates an empty cache.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clears the cache if it was built for a `$PATH` other than `path`.
+    ///
+    /// After this function returns, the cache is valid for `path`.
+    pub fn validate(&mut self, path: Option<&Value>) {
+        if self.path.as_ref() != path {
+            self.entries.clear();
+            self.path = path.cloned();
+        }
+    }
+
+    /// Returns the path cached for the command name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&CStr> {
+        self.entries.get(name).map(CString::as_c_str)
+    }
+
+    /// Adds a path to the cache.
+    ///
+    /// Returns the path previously cached for the name, if any.
+    pub fn insert(&mut self, name: String, path: CString) -> Option<CString> {
+        self.entries.insert(name, path)
+    }
+
+    /// Removes the path cached for the command name.
+    pub fn remove(&mut self, name: &str) -> Option<CString> {
+        self.entries.remove(name)
+    }
+
+    /// Removes all the cached paths.
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+
+    /// Returns an iterator over the cached command names and paths.
+    ///
+    /// The entries are ordered by the command name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &CStr)> {
+        self.entries
+            .iter()
+            .map(|(name, path)| (name.as_str(), path.as_c_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_keeps_entries_for_same_path() {
+        let path = Value::scalar("/bin:/usr/bin");
+        let mut cache = PathCache::new();
+        cache.validate(Some(&path));
+        cache.insert("ls".to_string(), CString::new("/bin/ls").unwrap());
+
+        cache.validate(Some(&path));
+        assert_eq!(
+            cache.get("ls"),
+            Some(CStr::from_bytes_with_nul(b"/bin/ls\0").unwrap())
+        );
+    }
+
+    #[test]
+    fn validate_clears_entries_for_different_path() {
+        let mut cache = PathCache::new();
+        cache.validate(Some(&Value::scalar("/bin")));
+        cache.insert("ls".to_string(), CString::new("/bin/ls").unwrap());
+
+        cache.validate(Some(&Value::scalar("/usr/bin")));
+        assert_eq!(cache.get("ls"), None);
+
+        cache.insert("ls".to_string(), CString::new("/usr/bin/ls").unwrap());
+        cache.validate(None);
+        assert_eq!(cache.get("ls"), None);
+    }
+
+    #[test]
+    fn iter_is_ordered_by_name() {
+        let mut cache = PathCache::new();
+        cache.insert("sh".to_string(), CString::new("/bin/sh").unwrap());
+        cache.insert("cat".to_string(), CString::new("/bin/cat").unwrap());
+        cache.insert("ls".to_string(), CString::new("/bin/ls").unwrap());
+        let names = cache.iter().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names, ["cat", "ls", "sh"]);
+    }
+}
@@ -65,7 +65,12 @@ pub enum Frame {
 
     /// Trap
     Trap(crate::trap::Condition),
-    // TODO dot script, eval
+
+    /// Command string executed by the eval built-in
+    Eval,
+
+    /// Script file executed by the dot built-in
+    DotScript,
 }
 
 /// Runtime execution context stack
@@ -17,10 +17,14 @@
 //! [System] and its implementors.
 
 pub mod real;
+pub mod resource;
 pub mod r#virtual;
 
+use self::resource::LimitPair;
+use self::resource::Resource;
 use crate::io::Fd;
 use crate::io::Stderr;
+use crate::io::MIN_INTERNAL_FD;
 use crate::job::Pid;
 use crate::job::WaitStatus;
 #[cfg(doc)]
@@ -49,6 +53,8 @@ pub use nix::sys::signal::SigmaskHow;
 pub use nix::sys::stat::{FileStat, Mode, SFlag};
 #[doc(no_inline)]
 pub use nix::sys::time::TimeSpec;
+#[doc(no_inline)]
+pub use nix::unistd::AccessFlags;
 use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::cmp::Reverse;
@@ -59,6 +65,7 @@ use std::ffi::c_int;
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::ffi::OsStr;
+use std::ffi::OsString;
 use std::fmt::Debug;
 use std::future::Future;
 use std::io::SeekFrom;
@@ -93,6 +100,14 @@ pub trait System: Debug {
     #[must_use]
     fn is_executable_file(&self, path: &CStr) -> bool;
 
+    /// Tests whether the current process can access a file.
+    ///
+    /// This is a thin wrapper around the `faccessat` system call with the
+    /// `AT_EACCESS` flag, that is, the access is checked with the effective
+    /// user and group IDs of the process. Returns `Ok(())` if the access
+    /// specified by `mode` would be granted.
+    fn faccessat(&self, dir_fd: Fd, path: &CStr, mode: AccessFlags) -> nix::Result<()>;
+
     /// Creates an unnamed pipe.
     ///
     /// This is a thin wrapper around the `pipe` system call.
@@ -351,10 +366,33 @@ pub trait System: Debug {
     /// Returns the current working directory path.
     fn getcwd(&self) -> nix::Result<PathBuf>;
 
+    /// Changes the working directory.
+    fn chdir(&mut self, path: &CStr) -> nix::Result<()>;
+
+    /// Sets the file mode creation mask.
+    ///
+    /// Returns the previous mask. This function never fails.
+    fn umask(&mut self, mask: Mode) -> Mode;
+
+    /// Returns the current soft and hard limits of the resource.
+    fn getrlimit(&self, resource: Resource) -> nix::Result<LimitPair>;
+
+    /// Sets the soft and hard limits of the resource.
+    fn setrlimit(&mut self, resource: Resource, limits: LimitPair) -> nix::Result<()>;
+
+    /// Returns the consumed CPU times.
+    fn times(&self) -> nix::Result<Times>;
+
     /// Returns the home directory path of the given user.
     ///
     /// Returns `Ok(None)` if the user is not found.
     fn getpwnam_dir(&self, name: &str) -> nix::Result<Option<PathBuf>>;
+
+    /// Returns the standard `$PATH` value where all standard utilities are
+    /// expected to be found.
+    ///
+    /// This is a thin wrapper around the `confstr(_CS_PATH, …)`.
+    fn confstr_path(&self) -> nix::Result<OsString>;
 }
 
 /// Sentinel for the current working directory
@@ -375,6 +413,22 @@ pub enum SignalHandling {
     Catch,
 }
 
+/// CPU times consumed by the current process and its children
+///
+/// This is the result of [`System::times`]. The times for children include
+/// only those of terminated children that have been waited for.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Times {
+    /// User CPU time consumed by the current process
+    pub self_user: Duration,
+    /// System CPU time consumed by the current process
+    pub self_system: Duration,
+    /// User CPU time consumed by the children
+    pub children_user: Duration,
+    /// System CPU time consumed by the children
+    pub children_system: Duration,
+}
+
 /// Task executed in a child process
 ///
 /// This is an argument passed to a [`ChildProcessStarter`]. The task is
@@ -423,6 +477,23 @@ pub trait Dir: Debug {
 ///
 /// This trait provides some extension methods for `System`.
 pub trait SystemEx: System {
+    /// Moves a file descriptor to [`MIN_INTERNAL_FD`] or larger.
+    ///
+    /// This function can be used to make sure a file descriptor used by the
+    /// shell does not conflict with file descriptors used by the user.
+    /// If `from` is already [`MIN_INTERNAL_FD`] or larger, it is returned
+    /// intact. Otherwise, it is duplicated with the close-on-exec flag and the
+    /// original file descriptor is closed regardless of the result.
+    fn move_fd_internal(&mut self, from: Fd) -> nix::Result<Fd> {
+        if from >= MIN_INTERNAL_FD {
+            return Ok(from);
+        }
+
+        let new = self.dup(from, MIN_INTERNAL_FD, FdFlag::FD_CLOEXEC);
+        let _ = self.close(from);
+        new
+    }
+
     /// Switches the foreground process group with SIGTTOU blocked.
     ///
     /// This is a convenience function to change the foreground process group
@@ -753,6 +824,9 @@ impl System for SharedSystem {
     fn is_executable_file(&self, path: &CStr) -> bool {
         self.0.borrow().is_executable_file(path)
     }
+    fn faccessat(&self, dir_fd: Fd, path: &CStr, mode: AccessFlags) -> nix::Result<()> {
+        self.0.borrow().faccessat(dir_fd, path, mode)
+    }
     fn pipe(&mut self) -> nix::Result<(Fd, Fd)> {
         self.0.borrow_mut().pipe()
     }
@@ -862,9 +936,27 @@ impl System for SharedSystem {
     fn getcwd(&self) -> nix::Result<PathBuf> {
         self.0.borrow().getcwd()
     }
+    fn chdir(&mut self, path: &CStr) -> nix::Result<()> {
+        self.0.borrow_mut().chdir(path)
+    }
+    fn umask(&mut self, mask: Mode) -> Mode {
+        self.0.borrow_mut().umask(mask)
+    }
+    fn getrlimit(&self, resource: Resource) -> nix::Result<LimitPair> {
+        self.0.borrow().getrlimit(resource)
+    }
+    fn setrlimit(&mut self, resource: Resource, limits: LimitPair) -> nix::Result<()> {
+        self.0.borrow_mut().setrlimit(resource, limits)
+    }
+    fn times(&self) -> nix::Result<Times> {
+        self.0.borrow().times()
+    }
     fn getpwnam_dir(&self, name: &str) -> nix::Result<Option<PathBuf>> {
         self.0.borrow().getpwnam_dir(name)
     }
+    fn confstr_path(&self) -> nix::Result<OsString> {
+        self.0.borrow().confstr_path()
+    }
 }
 
 impl SignalSystem for SharedSystem {
@@ -1307,6 +1399,34 @@ mod tests {
     use std::rc::Rc;
     use std::task::Context;
 
+    #[test]
+    fn move_fd_internal_moves_low_fd() {
+        let mut system = VirtualSystem::new();
+        let (reader, writer) = system.pipe().unwrap();
+        assert!(reader < MIN_INTERNAL_FD, "{reader}");
+
+        let fd = system.move_fd_internal(reader).unwrap();
+        assert!(fd >= MIN_INTERNAL_FD, "{fd}");
+        assert_eq!(system.fcntl_getfd(fd), Ok(FdFlag::FD_CLOEXEC));
+        assert_eq!(system.fcntl_getfd(reader), Err(Errno::EBADF));
+
+        system.write(writer, &[7]).unwrap();
+        let mut buffer = [0; 1];
+        assert_eq!(system.read(fd, &mut buffer), Ok(1));
+        assert_eq!(buffer, [7]);
+    }
+
+    #[test]
+    fn move_fd_internal_keeps_high_fd() {
+        let mut system = VirtualSystem::new();
+        let (reader, _writer) = system.pipe().unwrap();
+        let fd = system.dup(reader, Fd(20), FdFlag::empty()).unwrap();
+
+        let result = system.move_fd_internal(fd);
+        assert_eq!(result, Ok(fd));
+        assert_eq!(system.fcntl_getfd(fd), Ok(FdFlag::empty()));
+    }
+
     #[test]
     fn shared_system_read_async_ready() {
         let mut system = SharedSystem::new(Box::new(VirtualSystem::new()));
@@ -16,6 +16,9 @@
 
 //! Implementation of `System` that actually interacts with the system.
 
+use super::resource::LimitPair;
+use super::resource::Resource;
+use super::resource::INFINITY;
 use super::AtFlags;
 use super::ChildProcessStarter;
 use super::Dir;
@@ -33,6 +36,7 @@ use super::SigmaskHow;
 use super::Signal;
 use super::System;
 use super::TimeSpec;
+use super::Times;
 use crate::io::Fd;
 use crate::job::Pid;
 use crate::SignalHandling;
@@ -50,14 +54,18 @@ use std::ffi::c_int;
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::ffi::OsStr;
+use std::ffi::OsString;
 use std::io::SeekFrom;
+use std::mem::MaybeUninit;
 use std::os::unix::ffi::OsStrExt;
+use std::os::unix::ffi::OsStringExt;
 use std::os::unix::io::IntoRawFd;
 use std::path::Path;
 use std::ptr::NonNull;
 use std::sync::atomic::compiler_fence;
 use std::sync::atomic::AtomicIsize;
 use std::sync::atomic::Ordering;
+use std::time::Duration;
 use std::time::Instant;
 
 fn is_executable(path: &CStr) -> bool {
@@ -137,6 +145,13 @@ impl System for RealSystem {
         is_regular_file(path) && is_executable(path)
     }
 
+    fn faccessat(&self, dir_fd: Fd, path: &CStr, mode: AccessFlags) -> nix::Result<()> {
+        let result = unsafe {
+            nix::libc::faccessat(dir_fd.0, path.as_ptr(), mode.bits(), nix::libc::AT_EACCESS)
+        };
+        Errno::result(result).map(drop)
+    }
+
     fn pipe(&mut self) -> nix::Result<(Fd, Fd)> {
         nix::unistd::pipe().map(|(reader, writer)| (Fd(reader), Fd(writer)))
     }
@@ -385,9 +400,69 @@ impl System for RealSystem {
         nix::unistd::getcwd()
     }
 
+    fn chdir(&mut self, path: &CStr) -> nix::Result<()> {
+        nix::unistd::chdir(path)
+    }
+
+    fn umask(&mut self, mask: Mode) -> Mode {
+        nix::sys::stat::umask(mask)
+    }
+
+    fn getrlimit(&self, resource: Resource) -> nix::Result<LimitPair> {
+        let (soft, hard) = nix::sys::resource::getrlimit(resource)?;
+        Ok(LimitPair {
+            soft: soft.unwrap_or(INFINITY),
+            hard: hard.unwrap_or(INFINITY),
+        })
+    }
+
+    fn setrlimit(&mut self, resource: Resource, limits: LimitPair) -> nix::Result<()> {
+        nix::sys::resource::setrlimit(resource, Some(limits.soft), Some(limits.hard))
+    }
+
+    fn times(&self) -> nix::Result<Times> {
+        fn getrusage(who: c_int) -> nix::Result<nix::libc::rusage> {
+            let mut usage = MaybeUninit::<nix::libc::rusage>::uninit();
+            let result = unsafe { nix::libc::getrusage(who, usage.as_mut_ptr()) };
+            Errno::result(result)?;
+            Ok(unsafe { usage.assume_init() })
+        }
+        fn to_duration(time: nix::libc::timeval) -> Duration {
+            Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000)
+        }
+
+        let self_usage = getrusage(nix::libc::RUSAGE_SELF)?;
+        let children_usage = getrusage(nix::libc::RUSAGE_CHILDREN)?;
+        Ok(Times {
+            self_user: to_duration(self_usage.ru_utime),
+            self_system: to_duration(self_usage.ru_stime),
+            children_user: to_duration(children_usage.ru_utime),
+            children_system: to_duration(children_usage.ru_stime),
+        })
+    }
+
     fn getpwnam_dir(&self, name: &str) -> nix::Result<Option<std::path::PathBuf>> {
         nix::unistd::User::from_name(name).map(|o| o.map(|passwd| passwd.dir))
     }
+
+    fn confstr_path(&self) -> nix::Result<OsString> {
+        let size = unsafe { nix::libc::confstr(nix::libc::_CS_PATH, std::ptr::null_mut(), 0) };
+        if size == 0 {
+            return Err(Errno::last());
+        }
+        let mut buffer = Vec::<u8>::with_capacity(size);
+        let final_size =
+            unsafe { nix::libc::confstr(nix::libc::_CS_PATH, buffer.as_mut_ptr().cast(), size) };
+        if final_size == 0 {
+            return Err(Errno::last());
+        }
+        if final_size > size {
+            return Err(Errno::ERANGE);
+        }
+        // Exclude the terminating null byte.
+        unsafe { buffer.set_len(final_size - 1) }
+        Ok(OsString::from_vec(buffer))
+    }
 }
 
 /// Implementor of [`Dir`] that iterates on a real directory
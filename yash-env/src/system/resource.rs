@@ -0,0 +1,56 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Resource limits
+//!
+//! This module defines types for the [`getrlimit`](super::System::getrlimit)
+//! and [`setrlimit`](super::System::setrlimit) functions.
+
+#[doc(no_inline)]
+pub use nix::sys::resource::Resource;
+
+/// Value of a resource limit
+pub type Limit = nix::sys::resource::rlim_t;
+
+/// Limit value that means no limit
+pub const INFINITY: Limit = nix::libc::RLIM_INFINITY;
+
+/// Pair of soft and hard limits
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct LimitPair {
+    /// Soft limit, which is the limit actually enforced
+    pub soft: Limit,
+    /// Hard limit, which is the ceiling for the soft limit
+    pub hard: Limit,
+}
+
+impl Default for LimitPair {
+    /// Returns a pair of infinite limits.
+    fn default() -> Self {
+        LimitPair {
+            soft: INFINITY,
+            hard: INFINITY,
+        }
+    }
+}
+
+impl LimitPair {
+    /// Tests whether the soft limit does not exceed the hard limit.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.soft <= self.hard
+    }
+}
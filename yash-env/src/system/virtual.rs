@@ -54,6 +54,9 @@ pub use self::file_system::*;
 pub use self::io::*;
 pub use self::process::*;
 pub use self::signal::*;
+use super::resource::LimitPair;
+use super::resource::Resource;
+use super::AccessFlags;
 use super::AtFlags;
 use super::Dir;
 use super::Errno;
@@ -65,6 +68,7 @@ use super::SigSet;
 use super::SigmaskHow;
 use super::Signal;
 use super::TimeSpec;
+use super::Times;
 use crate::io::Fd;
 use crate::job::Pid;
 use crate::job::WaitStatus;
@@ -85,11 +89,13 @@ use std::ffi::c_int;
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::ffi::OsStr;
+use std::ffi::OsString;
 use std::fmt::Debug;
 use std::future::Future;
 use std::io::SeekFrom;
 use std::mem::MaybeUninit;
 use std::os::unix::ffi::OsStrExt;
+use std::path::Component;
 use std::path::Path;
 use std::path::PathBuf;
 use std::pin::Pin;
@@ -331,6 +337,27 @@ impl System for VirtualSystem {
         }
     }
 
+    /// Tests whether the current process can access a file.
+    ///
+    /// The current implementation regards the current process as the owner
+    /// of every file, so only the owner permission bits are checked.
+    fn faccessat(&self, dir_fd: Fd, path: &CStr, mode: AccessFlags) -> nix::Result<()> {
+        let path = Path::new(OsStr::from_bytes(path.to_bytes()));
+        let inode = self.resolve_existing_file(dir_fd, path, AtFlags::empty())?;
+        let permissions = inode.borrow().permissions.0;
+        let required = [
+            (AccessFlags::R_OK, 0o400),
+            (AccessFlags::W_OK, 0o200),
+            (AccessFlags::X_OK, 0o100),
+        ];
+        for (flag, bit) in required {
+            if mode.contains(flag) && permissions & bit == 0 {
+                return Err(Errno::EACCES);
+            }
+        }
+        Ok(())
+    }
+
     fn pipe(&mut self) -> nix::Result<(Fd, Fd)> {
         let file = Rc::new(RefCell::new(INode {
             body: FileBody::Fifo {
@@ -410,8 +437,8 @@ impl System for VirtualSystem {
             }
             Err(Errno::ENOENT) if option.contains(OFlag::O_CREAT) => {
                 let mut inode = INode::new([]);
-                // TODO Apply umask
-                inode.permissions = Mode(mode.bits());
+                let umask = state.processes[&self.process_id].umask;
+                inode.permissions = Mode((mode & !umask).bits());
                 let inode = Rc::new(RefCell::new(inode));
                 state.file_system.save(&path, Rc::clone(&inode))?;
                 inode
@@ -522,8 +549,34 @@ impl System for VirtualSystem {
         self.with_open_file_description_mut(fd, |ofd| ofd.read(buffer))
     }
 
+    /// Writes to the file descriptor.
+    ///
+    /// When writing to a regular file, the soft limit of `RLIMIT_FSIZE` is
+    /// enforced: the data is truncated so that the file does not grow beyond
+    /// the limit, and `EFBIG` is returned if no data can be written. (Unlike a
+    /// real system, no `SIGXFSZ` signal is sent.)
     fn write(&mut self, fd: Fd, buffer: &[u8]) -> nix::Result<usize> {
-        self.with_open_file_description_mut(fd, |ofd| ofd.write(buffer))
+        let limit = self
+            .current_process()
+            .resource_limits(Resource::RLIMIT_FSIZE)
+            .soft;
+        let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+        self.with_open_file_description_mut(fd, |ofd| {
+            let start = match &ofd.file.borrow().body {
+                FileBody::Regular { content, .. } if ofd.is_appending => Some(content.len()),
+                FileBody::Regular { .. } => Some(ofd.offset),
+                _ => None,
+            };
+            let mut buffer = buffer;
+            if let Some(start) = start {
+                let room = limit.saturating_sub(start);
+                if room == 0 && !buffer.is_empty() {
+                    return Err(Errno::EFBIG);
+                }
+                buffer = &buffer[..buffer.len().min(room)];
+            }
+            ofd.write(buffer)
+        })
     }
 
     fn lseek(&mut self, fd: Fd, position: SeekFrom) -> nix::Result<u64> {
@@ -906,10 +959,100 @@ impl System for VirtualSystem {
         Ok(self.current_process().cwd.clone())
     }
 
+    /// Changes the working directory.
+    ///
+    /// The new working directory path is normalized by resolving symbolic
+    /// links and removing `.` and `..` components. Since the virtual file
+    /// system does not support symbolic links in the middle of a path, `..`
+    /// components are resolved lexically.
+    fn chdir(&mut self, path: &CStr) -> nix::Result<()> {
+        const _POSIX_SYMLOOP_MAX: i32 = 8;
+
+        let path = Path::new(OsStr::from_bytes(path.to_bytes()));
+        let mut path = normalize_path(&self.resolve_relative_path(path));
+        for _count in 0.._POSIX_SYMLOOP_MAX {
+            let inode = self.state.borrow().file_system.get(&path)?;
+            let inode = inode.borrow();
+            match &inode.body {
+                FileBody::Directory { .. } => {
+                    self.current_process_mut().chdir(path);
+                    return Ok(());
+                }
+                FileBody::Symlink { target } => {
+                    path.pop();
+                    path.push(target);
+                    path = normalize_path(&path);
+                }
+                _ => return Err(Errno::ENOTDIR),
+            }
+        }
+        Err(Errno::ELOOP)
+    }
+
+    fn umask(&mut self, mask: nix::sys::stat::Mode) -> nix::sys::stat::Mode {
+        std::mem::replace(&mut self.current_process_mut().umask, mask)
+    }
+
+    fn getrlimit(&self, resource: Resource) -> nix::Result<LimitPair> {
+        Ok(self.current_process().resource_limits(resource))
+    }
+
+    /// Sets the resource limits.
+    ///
+    /// Like a process without the privilege, this function fails with `EPERM`
+    /// if the new hard limit exceeds the current hard limit. It also fails
+    /// with `EINVAL` if the soft limit exceeds the hard limit.
+    fn setrlimit(&mut self, resource: Resource, limits: LimitPair) -> nix::Result<()> {
+        if !limits.is_valid() {
+            return Err(Errno::EINVAL);
+        }
+        let mut process = self.current_process_mut();
+        if limits.hard > process.resource_limits(resource).hard {
+            return Err(Errno::EPERM);
+        }
+        process.resource_limits.insert(resource, limits);
+        Ok(())
+    }
+
+    /// Returns the simulated CPU times of the current process.
+    ///
+    /// See [`Process::set_times`].
+    fn times(&self) -> nix::Result<Times> {
+        Ok(self.current_process().times())
+    }
+
     fn getpwnam_dir(&self, name: &str) -> nix::Result<Option<PathBuf>> {
         let state = self.state.borrow();
         Ok(state.home_dirs.get(name).cloned())
     }
+
+    /// Returns the standard path for the system.
+    ///
+    /// This function returns the value of [`SystemState::path`]. If it is
+    /// empty, it returns the `ENOSYS` error.
+    fn confstr_path(&self) -> nix::Result<OsString> {
+        let path = self.state.borrow().path.clone();
+        if path.is_empty() {
+            Err(Errno::ENOSYS)
+        } else {
+            Ok(path)
+        }
+    }
+}
+
+/// Removes `.` and `..` components from an absolute path lexically.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                result.pop();
+            }
+            _ => result.push(component),
+        }
+    }
+    result
 }
 
 fn send_signal_to_processes(
@@ -977,6 +1120,9 @@ pub struct SystemState {
     /// [`VirtualSystem::getpwnam_dir`] looks up its argument in this
     /// dictionary.
     pub home_dirs: HashMap<String, PathBuf>,
+
+    /// Standard path returned by [`VirtualSystem::confstr_path`]
+    pub path: OsString,
 }
 
 impl SystemState {
@@ -1227,6 +1373,38 @@ mod tests {
         assert!(system.is_executable_file(&CString::new("/some/file").unwrap()));
     }
 
+    #[test]
+    fn faccessat_non_existing_file() {
+        let system = VirtualSystem::new();
+        let path = CString::new("/no/such/file").unwrap();
+        let result = system.faccessat(Fd(0), &path, AccessFlags::F_OK);
+        assert_eq!(result, Err(Errno::ENOENT));
+    }
+
+    #[test]
+    fn faccessat_checks_owner_permissions() {
+        let system = VirtualSystem::new();
+        let content = Rc::new(RefCell::new(INode {
+            permissions: Mode(0o466),
+            ..INode::default()
+        }));
+        let mut state = system.state.borrow_mut();
+        state.file_system.save("/some/file", content).unwrap();
+        drop(state);
+        let path = CString::new("/some/file").unwrap();
+
+        assert_eq!(system.faccessat(Fd(0), &path, AccessFlags::F_OK), Ok(()));
+        assert_eq!(system.faccessat(Fd(0), &path, AccessFlags::R_OK), Ok(()));
+        assert_eq!(
+            system.faccessat(Fd(0), &path, AccessFlags::W_OK),
+            Err(Errno::EACCES)
+        );
+        assert_eq!(
+            system.faccessat(Fd(0), &path, AccessFlags::R_OK | AccessFlags::X_OK),
+            Err(Errno::EACCES)
+        );
+    }
+
     #[test]
     fn pipe_read_write() {
         let mut system = VirtualSystem::new();
@@ -1325,6 +1503,22 @@ mod tests {
         });
     }
 
+    #[test]
+    fn open_creating_file_applies_umask() {
+        let mut system = VirtualSystem::new();
+        system.umask(nix::sys::stat::Mode::from_bits_truncate(0o027));
+        system
+            .open(
+                &CString::new("new_file").unwrap(),
+                OFlag::O_WRONLY | OFlag::O_CREAT,
+                nix::sys::stat::Mode::from_bits_truncate(0o666),
+            )
+            .unwrap();
+
+        let file = system.state.borrow().file_system.get("new_file").unwrap();
+        assert_eq!(file.borrow().permissions, Mode(0o640));
+    }
+
     #[test]
     fn open_existing_file() {
         let mut system = VirtualSystem::new();
@@ -2286,4 +2480,127 @@ mod tests {
         let result = system.execve(&path, &[], &[]);
         assert_eq!(result, Err(Errno::ENOENT));
     }
+
+    #[test]
+    fn setting_and_getting_resource_limits() {
+        let mut system = VirtualSystem::new();
+        let limits = system.getrlimit(Resource::RLIMIT_CORE).unwrap();
+        assert_eq!(limits, LimitPair::default());
+
+        let limits = LimitPair { soft: 0, hard: 100 };
+        system.setrlimit(Resource::RLIMIT_CORE, limits).unwrap();
+        assert_eq!(system.getrlimit(Resource::RLIMIT_CORE), Ok(limits));
+
+        let result = system.setrlimit(Resource::RLIMIT_CORE, LimitPair { soft: 0, hard: 200 });
+        assert_eq!(result, Err(Errno::EPERM));
+        let result = system.setrlimit(Resource::RLIMIT_CORE, LimitPair { soft: 50, hard: 10 });
+        assert_eq!(result, Err(Errno::EINVAL));
+        assert_eq!(system.getrlimit(Resource::RLIMIT_CORE), Ok(limits));
+    }
+
+    #[test]
+    fn write_enforces_file_size_limit() {
+        let mut system = VirtualSystem::new();
+        let limits = LimitPair { soft: 3, hard: 3 };
+        system.setrlimit(Resource::RLIMIT_FSIZE, limits).unwrap();
+        let fd = system
+            .open(
+                &CString::new("file").unwrap(),
+                OFlag::O_WRONLY | OFlag::O_CREAT,
+                nix::sys::stat::Mode::all(),
+            )
+            .unwrap();
+
+        assert_eq!(system.write(fd, &[1, 2]), Ok(2));
+        assert_eq!(system.write(fd, &[3, 4]), Ok(1));
+        assert_eq!(system.write(fd, &[5]), Err(Errno::EFBIG));
+        let file = system.state.borrow().file_system.get("file").unwrap();
+        let file = file.borrow();
+        assert_matches!(&file.body, FileBody::Regular { content, .. } => {
+            assert_eq!(content[..], [1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn umask_returns_previous_mask() {
+        let mut system = VirtualSystem::new();
+        let old = system.umask(nix::sys::stat::Mode::from_bits_truncate(0o077));
+        assert_eq!(old.bits(), 0o022);
+        let old = system.umask(nix::sys::stat::Mode::from_bits_truncate(0o002));
+        assert_eq!(old.bits(), 0o077);
+        assert_eq!(system.current_process().umask().bits(), 0o002);
+    }
+
+    #[test]
+    fn chdir_to_directory() {
+        let mut system = VirtualSystem::new();
+        let dir = Rc::new(RefCell::new(INode {
+            body: FileBody::Directory {
+                files: Default::default(),
+            },
+            permissions: Mode(0o755),
+        }));
+        let mut state = system.state.borrow_mut();
+        state.file_system.save("/dir/sub", dir).unwrap();
+        drop(state);
+
+        let result = system.chdir(&CString::new("/dir/sub").unwrap());
+        assert_eq!(result, Ok(()));
+        assert_eq!(system.current_process().cwd, Path::new("/dir/sub"));
+
+        let result = system.chdir(&CString::new("..").unwrap());
+        assert_eq!(result, Ok(()));
+        assert_eq!(system.current_process().cwd, Path::new("/dir"));
+
+        let result = system.chdir(&CString::new("./sub/.").unwrap());
+        assert_eq!(result, Ok(()));
+        assert_eq!(system.current_process().cwd, Path::new("/dir/sub"));
+    }
+
+    #[test]
+    fn chdir_to_symlink_to_directory() {
+        let mut system = VirtualSystem::new();
+        let mut state = system.state.borrow_mut();
+        state
+            .file_system
+            .save("/some/dir/file", Rc::new(RefCell::new(INode::new([]))))
+            .unwrap();
+        state
+            .file_system
+            .save(
+                "/some/link",
+                Rc::new(RefCell::new(INode {
+                    body: FileBody::Symlink {
+                        target: "dir".into(),
+                    },
+                    permissions: Mode::default(),
+                })),
+            )
+            .unwrap();
+        drop(state);
+
+        let result = system.chdir(&CString::new("/some/link").unwrap());
+        assert_eq!(result, Ok(()));
+        assert_eq!(system.current_process().cwd, Path::new("/some/dir"));
+    }
+
+    #[test]
+    fn chdir_to_non_directory() {
+        let mut system = system_with_symlink();
+        let old_cwd = system.current_process().cwd.clone();
+        let result = system.chdir(&CString::new("/some/file").unwrap());
+        assert_eq!(result, Err(Errno::ENOTDIR));
+        let result = system.chdir(&CString::new("/link").unwrap());
+        assert_eq!(result, Err(Errno::ENOTDIR));
+        assert_eq!(system.current_process().cwd, old_cwd);
+    }
+
+    #[test]
+    fn chdir_to_non_existent_file() {
+        let mut system = VirtualSystem::new();
+        let old_cwd = system.current_process().cwd.clone();
+        let result = system.chdir(&CString::new("/no/such/dir").unwrap());
+        assert_eq!(result, Err(Errno::ENOENT));
+        assert_eq!(system.current_process().cwd, old_cwd);
+    }
 }
@@ -20,11 +20,16 @@ use super::io::FdBody;
 use super::signal::SignalEffect;
 use crate::io::Fd;
 use crate::semantics::ExitStatus;
+use crate::system::resource::Limit;
+use crate::system::resource::LimitPair;
+use crate::system::resource::Resource;
 use crate::system::SelectSystem;
+use crate::system::Times;
 use crate::SignalHandling;
 use nix::sys::signal::SigSet;
 use nix::sys::signal::SigmaskHow;
 use nix::sys::signal::Signal;
+use nix::sys::stat::Mode;
 use nix::sys::wait::WaitStatus;
 use nix::unistd::Pid;
 use std::cell::RefCell;
@@ -34,7 +39,6 @@ use std::ffi::CString;
 use std::fmt::Debug;
 use std::ops::BitOr;
 use std::ops::BitOrAssign;
-use std::os::unix::io::RawFd;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Weak;
@@ -54,6 +58,9 @@ pub struct Process {
     /// Working directory path
     pub(crate) cwd: PathBuf,
 
+    /// File mode creation mask
+    pub(crate) umask: Mode,
+
     /// Execution state of the process.
     pub(crate) state: ProcessState,
 
@@ -79,8 +86,14 @@ pub struct Process {
     /// List of signals that have been delivered and caught.
     pub(crate) caught_signals: Vec<Signal>,
 
-    /// Maximum number of open file descriptors.
-    pub(crate) rlimit_nofile: RawFd,
+    /// Resource limits.
+    ///
+    /// For resources not contained in this hash map, the limits are assumed
+    /// to be infinite.
+    pub(crate) resource_limits: HashMap<Resource, LimitPair>,
+
+    /// Simulated CPU times consumed by this process and its children
+    pub(crate) times: Times,
 
     /// Weak reference to the `SelectSystem` for this process.
     ///
@@ -120,13 +133,21 @@ impl Process {
             pgid,
             fds: BTreeMap::new(),
             cwd: PathBuf::new(),
+            umask: Mode::from_bits_truncate(0o022),
+            times: Times::default(),
             state: ProcessState::Running,
             state_has_changed: false,
             signal_handlings: HashMap::new(),
             blocked_signals: SigSet::empty(),
             pending_signals: SigSet::empty(),
             caught_signals: Vec::new(),
-            rlimit_nofile: 1 << 10,
+            resource_limits: HashMap::from([(
+                Resource::RLIMIT_NOFILE,
+                LimitPair {
+                    soft: 1 << 10,
+                    hard: 1 << 10,
+                },
+            )]),
             selector: Weak::new(),
             last_exec: None,
         }
@@ -138,6 +159,8 @@ impl Process {
     pub fn fork_from(ppid: Pid, parent: &Process) -> Process {
         let mut child = Self::with_parent_and_group(ppid, parent.pgid);
         child.fds = parent.fds.clone();
+        child.umask = parent.umask;
+        child.resource_limits = parent.resource_limits.clone();
         child.signal_handlings = parent.signal_handlings.clone();
         child.blocked_signals = parent.blocked_signals;
         child.pending_signals = SigSet::empty();
@@ -182,10 +205,11 @@ impl Process {
     /// Assigns the given FD to the body.
     ///
     /// If successful, returns an `Ok` value containing the previous body for
-    /// the FD. If the FD is equal to or greater than `rlimit_nofile`, returns
-    /// `Err(body)`.
+    /// the FD. If the FD is equal to or greater than the soft limit of
+    /// `RLIMIT_NOFILE`, returns `Err(body)`.
     pub fn set_fd(&mut self, fd: Fd, body: FdBody) -> Result<Option<FdBody>, FdBody> {
-        if fd.0 < self.rlimit_nofile {
+        let limit = self.resource_limits(Resource::RLIMIT_NOFILE).soft;
+        if Limit::try_from(fd.0).map_or(false, |fd| fd < limit) {
             Ok(self.fds.insert(fd, body))
         } else {
             Err(body)
@@ -238,6 +262,37 @@ impl Process {
         self.cwd = path
     }
 
+    /// Returns the file mode creation mask.
+    #[inline(always)]
+    #[must_use]
+    pub fn umask(&self) -> Mode {
+        self.umask
+    }
+
+    /// Returns the limits of the resource.
+    #[must_use]
+    pub fn resource_limits(&self, resource: Resource) -> LimitPair {
+        self.resource_limits
+            .get(&resource)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the simulated CPU times.
+    #[inline(always)]
+    #[must_use]
+    pub fn times(&self) -> Times {
+        self.times
+    }
+
+    /// Sets the simulated CPU times.
+    ///
+    /// The virtual system does not consume CPU time by itself. Use this
+    /// function to simulate CPU time consumption.
+    pub fn set_times(&mut self, times: Times) {
+        self.times = times
+    }
+
     /// Returns the process state.
     #[inline(always)]
     #[must_use]
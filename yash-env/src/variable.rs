@@ -404,7 +404,6 @@ pub enum Scope {
 }
 
 // TODO Rename to AssignReadOnlyError
-// TODO Add UnsetReadOnlyError that does not have the new_value field
 /// Error that occurs when assigning to an existing read-only variable.
 #[derive(Clone, Debug, Eq, Error, PartialEq)]
 #[error("variable `{name}` is read-only")]
@@ -417,6 +416,16 @@ pub struct ReadOnlyError {
     pub new_value: Variable,
 }
 
+/// Error that occurs when unsetting a read-only variable.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("variable `{name}` is read-only")]
+pub struct UnsetError {
+    /// Variable name.
+    pub name: String,
+    /// Location where the existing variable was made read-only.
+    pub read_only_location: Location,
+}
+
 /// Iterator of variables
 ///
 /// [`VariableSet::iter`] returns this iterator.
@@ -446,6 +455,28 @@ impl VariableSet {
         Some(&self.all_variables.get(name)?.last()?.variable)
     }
 
+    /// Gets a mutable reference to the variable with the specified name.
+    ///
+    /// This function returns the visible variable, that is, the variable in
+    /// the topmost context that has one with the name.
+    ///
+    /// Note that modifying the variable through the returned reference bypasses
+    /// the read-only check done in [`assign`](Self::assign). This function is
+    /// meant for changing attributes such as
+    /// [`is_exported`](Variable::is_exported) of an existing variable. You
+    /// should not change the value of a read-only variable.
+    ///
+    /// You cannot retrieve positional parameters using this function.
+    /// See [`positional_params_mut`](Self::positional_params_mut).
+    #[must_use]
+    pub fn get_mut<N: ?Sized>(&mut self, name: &N) -> Option<&mut Variable>
+    where
+        String: Borrow<N>,
+        N: Hash + Eq,
+    {
+        Some(&mut self.all_variables.get_mut(name)?.last_mut()?.variable)
+    }
+
     /// Assigns a variable.
     ///
     /// If successful, the return value is the previous value. If there is an
@@ -566,6 +597,66 @@ impl VariableSet {
         }
     }
 
+    /// Removes a variable.
+    ///
+    /// If successful, the return value is the removed variable that was
+    /// visible before the removal, if any. The removal fails if any of the
+    /// variables to be removed is read-only, in which case no variables are
+    /// removed.
+    ///
+    /// The `scope` parameter chooses variables to be removed:
+    ///
+    /// - `Global`: all variables with the name in all contexts
+    /// - `Local`: variables in the topmost [regular](ContextType::Regular)
+    ///   context or above. Variables in lower contexts become visible after
+    ///   the removal.
+    /// - `Volatile`: variables above the topmost
+    ///   [regular](ContextType::Regular) context
+    ///
+    /// You cannot modify positional parameters using this function.
+    /// See [`positional_params_mut`](Self::positional_params_mut).
+    pub fn unset(&mut self, scope: Scope, name: &str) -> Result<Option<Variable>, UnsetError> {
+        let min_context_index = self.min_context_index(scope);
+        let Some(stack) = self.all_variables.get_mut(name) else {
+            return Ok(None);
+        };
+
+        // The stack is sorted by the context index.
+        let start = stack
+            .iter()
+            .position(|vic| vic.context_index >= min_context_index)
+            .unwrap_or(stack.len());
+
+        if let Some(location) = stack[start..]
+            .iter()
+            .rev()
+            .find_map(|vic| vic.variable.read_only_location.as_ref())
+        {
+            return Err(UnsetError {
+                name: name.to_owned(),
+                read_only_location: location.clone(),
+            });
+        }
+
+        Ok(stack.drain(start..).next_back().map(|vic| vic.variable))
+    }
+
+    /// Returns the index of the lowest context that the scope covers.
+    fn min_context_index(&self, scope: Scope) -> usize {
+        fn index_of_topmost_regular_context(contexts: &[Context]) -> usize {
+            contexts
+                .iter()
+                .rposition(|context| context.r#type == ContextType::Regular)
+                .expect("base context has gone")
+        }
+
+        match scope {
+            Scope::Global => 0,
+            Scope::Local => index_of_topmost_regular_context(&self.contexts),
+            Scope::Volatile => index_of_topmost_regular_context(&self.contexts) + 1,
+        }
+    }
+
     /// Returns an iterator of variables.
     ///
     /// The `scope` parameter chooses variables returned by the iterator:
@@ -580,20 +671,9 @@ impl VariableSet {
     ///
     /// The order of iterated variables is unspecified.
     pub fn iter(&self, scope: Scope) -> Iter {
-        fn index_of_topmost_regular_context(contexts: &[Context]) -> usize {
-            contexts
-                .iter()
-                .rposition(|context| context.r#type == ContextType::Regular)
-                .expect("base context has gone")
-        }
-
         Iter {
             inner: self.all_variables.iter(),
-            min_context_index: match scope {
-                Scope::Global => 0,
-                Scope::Local => index_of_topmost_regular_context(&self.contexts),
-                Scope::Volatile => index_of_topmost_regular_context(&self.contexts) + 1,
-            },
+            min_context_index: self.min_context_index(scope),
         }
     }
 
@@ -1093,6 +1173,123 @@ mod tests {
         assert_eq!(variables.get("foo"), None);
     }
 
+    #[test]
+    fn get_mut_returns_visible_variable() {
+        let mut variables = VariableSet::new();
+        variables
+            .assign(Scope::Global, "foo".to_string(), Variable::new("0"))
+            .unwrap();
+        variables.push_context_impl(ContextType::Regular);
+        variables
+            .assign(Scope::Local, "foo".to_string(), Variable::new("1"))
+            .unwrap();
+
+        variables.get_mut("foo").unwrap().is_exported = true;
+        assert!(variables.get("foo").unwrap().is_exported);
+        variables.pop_context_impl();
+        assert!(!variables.get("foo").unwrap().is_exported);
+        assert_eq!(variables.get_mut("bar"), None);
+    }
+
+    #[test]
+    fn unset_global() {
+        let mut variables = VariableSet::new();
+        variables
+            .assign(Scope::Global, "foo".to_string(), Variable::new("0"))
+            .unwrap();
+        variables.push_context_impl(ContextType::Regular);
+        variables
+            .assign(Scope::Local, "foo".to_string(), Variable::new("1"))
+            .unwrap();
+        variables.push_context_impl(ContextType::Volatile);
+        variables
+            .assign(Scope::Volatile, "foo".to_string(), Variable::new("2"))
+            .unwrap();
+
+        let result = variables.unset(Scope::Global, "foo");
+        assert_eq!(result, Ok(Some(Variable::new("2"))));
+        assert_eq!(variables.get("foo"), None);
+        variables.pop_context_impl();
+        assert_eq!(variables.get("foo"), None);
+        variables.pop_context_impl();
+        assert_eq!(variables.get("foo"), None);
+    }
+
+    #[test]
+    fn unset_local() {
+        let mut variables = VariableSet::new();
+        variables
+            .assign(Scope::Global, "foo".to_string(), Variable::new("0"))
+            .unwrap();
+        variables.push_context_impl(ContextType::Regular);
+        variables
+            .assign(Scope::Local, "foo".to_string(), Variable::new("1"))
+            .unwrap();
+        variables.push_context_impl(ContextType::Volatile);
+        variables
+            .assign(Scope::Volatile, "foo".to_string(), Variable::new("2"))
+            .unwrap();
+
+        let result = variables.unset(Scope::Local, "foo");
+        assert_eq!(result, Ok(Some(Variable::new("2"))));
+        assert_eq!(variables.get("foo"), Some(&Variable::new("0")));
+    }
+
+    #[test]
+    fn unset_volatile() {
+        let mut variables = VariableSet::new();
+        variables
+            .assign(Scope::Global, "foo".to_string(), Variable::new("0"))
+            .unwrap();
+        variables.push_context_impl(ContextType::Volatile);
+        variables
+            .assign(Scope::Volatile, "foo".to_string(), Variable::new("1"))
+            .unwrap();
+
+        let result = variables.unset(Scope::Volatile, "foo");
+        assert_eq!(result, Ok(Some(Variable::new("1"))));
+        assert_eq!(variables.get("foo"), Some(&Variable::new("0")));
+
+        let result = variables.unset(Scope::Volatile, "foo");
+        assert_eq!(result, Ok(None));
+        assert_eq!(variables.get("foo"), Some(&Variable::new("0")));
+    }
+
+    #[test]
+    fn unset_non_existent_variable() {
+        let mut variables = VariableSet::new();
+        let result = variables.unset(Scope::Global, "foo");
+        assert_eq!(result, Ok(None));
+    }
+
+    #[test]
+    fn unset_read_only_variable() {
+        let mut variables = VariableSet::new();
+        let read_only_location = Location::dummy("read-only");
+        let v0 = Variable::new("0").make_read_only(read_only_location.clone());
+        variables
+            .assign(Scope::Global, "foo".to_string(), v0)
+            .unwrap();
+        variables.push_context_impl(ContextType::Regular);
+        variables
+            .assign(Scope::Local, "foo".to_string(), Variable::new("1"))
+            .unwrap();
+
+        let result = variables.unset(Scope::Global, "foo");
+        assert_eq!(
+            result,
+            Err(UnsetError {
+                name: "foo".to_string(),
+                read_only_location,
+            })
+        );
+        assert_eq!(variables.get("foo"), Some(&Variable::new("1")));
+
+        // The read-only variable is not in the local scope.
+        let result = variables.unset(Scope::Local, "foo");
+        assert_eq!(result, Ok(Some(Variable::new("1"))));
+    }
+
     #[test]
     #[should_panic(expected = "cannot pop the base context")]
     fn cannot_pop_base_context() {
@@ -1232,7 +1429,7 @@ mod tests {
     #[test]
     fn env_c_strings() {
         let mut variables = VariableSet::new();
-        assert_eq!(&variables.env_c_strings(), &[]);
+        assert_eq!(&variables.env_c_strings(), &[] as &[CString; 0]);
 
         variables
             .assign(
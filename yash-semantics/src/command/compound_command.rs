@@ -52,6 +52,7 @@ async fn evaluate_condition(env: &mut Env, condition: &syntax::List) -> Result<b
 }
 
 mod case;
+mod double_bracket;
 mod for_loop;
 mod r#if;
 mod subshell;
@@ -62,16 +63,29 @@ mod while_loop;
 /// The redirections are performed, if any, before executing the command body.
 /// Redirection errors are subject to the `ErrExit` option
 /// (`Env::apply_errexit`).
+///
+/// File descriptors opened by process substitutions in the command are
+/// [closed](Env::close_process_subst_fds) after the command has finished.
 #[async_trait(?Send)]
 impl Command for syntax::FullCompoundCommand {
     async fn execute(&self, env: &mut Env) -> Result {
-        let mut env = RedirGuard::new(env);
-        match perform_redirs(&mut env, &self.redirs).await {
-            Ok(_) => self.command.execute(&mut env).await,
-            Err(error) => {
-                error.handle(&mut env).await?;
-                env.apply_errexit()
-            }
+        let process_subst_fds = env.process_subst_fds.len();
+        let result = execute_full_compound_command(env, self).await;
+        env.close_process_subst_fds(process_subst_fds);
+        result
+    }
+}
+
+async fn execute_full_compound_command(
+    env: &mut Env,
+    command: &syntax::FullCompoundCommand,
+) -> Result {
+    let mut env = RedirGuard::new(env);
+    match perform_redirs(&mut env, &command.redirs).await {
+        Ok(_) => command.command.execute(&mut env).await,
+        Err(error) => {
+            error.handle(&mut env).await?;
+            env.apply_errexit()
         }
     }
 }
@@ -126,6 +140,15 @@ impl Command for syntax::FullCompoundCommand {
 ///
 /// POSIX does not specify the order in which the shell tests multiple patterns
 /// in an item. This implementation tries them in the order of appearance.
+///
+/// # Double-bracket conditional construct
+///
+/// The double-bracket command evaluates the conditional expression and exits
+/// with zero if it is true, one if false, or two on an error. Operands are
+/// expanded without field splitting and pathname expansion. The command is
+/// rejected in the POSIXly-correct mode, which interrupts the shell.
+///
+/// After the evaluation, [`Env::apply_errexit`] is called.
 #[async_trait(?Send)]
 impl Command for syntax::CompoundCommand {
     async fn execute(&self, env: &mut Env) -> Result {
@@ -143,6 +166,10 @@ impl Command for syntax::CompoundCommand {
                 r#else,
             } => r#if::execute(env, condition, body, elifs, r#else).await,
             Case { subject, items } => case::execute(env, subject, items).await,
+            DoubleBracket {
+                expression,
+                location,
+            } => double_bracket::execute(env, expression, location).await,
         }
     }
 }
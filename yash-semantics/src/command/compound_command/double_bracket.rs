@@ -0,0 +1,421 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Execution of the double-bracket command
+//!
+//! Operands are expanded by [`expand_word`], so they are not subject to field
+//! splitting or pathname expansion. The right-hand side of `==` and `!=` is
+//! expanded into a pattern matched with [`yash_fnmatch`], and that of `=~` is
+//! expanded into a regular expression matched with [`regex`]. In both cases,
+//! quoted characters match literally.
+//!
+//! The other primaries are evaluated by the [`primary`](crate::primary) module
+//! shared with the test built-in.
+
+use crate::expansion::attr::fnmatch::apply_escapes;
+use crate::expansion::attr::fnmatch::to_pattern_chars;
+use crate::expansion::attr::AttrChar;
+use crate::expansion::expand_word;
+use crate::expansion::expand_word_attr;
+use crate::expansion::Field;
+use crate::primary::compare_integers;
+use crate::primary::evaluate_unary;
+use crate::primary::is_newer_than;
+use crate::primary::is_older_than;
+use crate::primary::is_same_file;
+use crate::Handle;
+use std::borrow::Cow;
+use std::future::Future;
+use std::ops::ControlFlow::Break;
+use std::pin::Pin;
+use thiserror::Error;
+use yash_env::io::print_error;
+use yash_env::io::print_message;
+use yash_env::option::Option::PosixlyCorrect;
+use yash_env::option::State::On;
+use yash_env::semantics::Divert;
+use yash_env::semantics::ExitStatus;
+use yash_env::semantics::Result;
+use yash_env::Env;
+use yash_fnmatch::Config;
+use yash_fnmatch::Pattern;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::MessageBase;
+use yash_syntax::source::Location;
+use yash_syntax::syntax::CondBinaryOperator;
+use yash_syntax::syntax::CondExpr;
+use yash_syntax::syntax::Word;
+
+/// Error in evaluating a conditional expression
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum Error {
+    /// Error expanding an operand
+    #[error(transparent)]
+    Expansion(Box<crate::expansion::Error>),
+    /// Error evaluating a primary
+    #[error(transparent)]
+    Primary(crate::primary::Error),
+    /// The right-hand side of `=~` is not a valid regular expression.
+    #[error("invalid regular expression")]
+    InvalidRegex {
+        /// Expanded operand
+        operand: Field,
+        /// Description of the error
+        message: String,
+    },
+}
+
+impl From<crate::expansion::Error> for Error {
+    fn from(error: crate::expansion::Error) -> Self {
+        Error::Expansion(Box::new(error))
+    }
+}
+
+impl From<crate::primary::Error> for Error {
+    fn from(error: crate::primary::Error) -> Self {
+        Error::Primary(error)
+    }
+}
+
+impl MessageBase for Error {
+    fn message_title(&self) -> Cow<'_, str> {
+        match self {
+            Error::Expansion(error) => error.message_title(),
+            Error::Primary(error) => error.message_title(),
+            _ => self.to_string().into(),
+        }
+    }
+
+    fn main_annotation(&self) -> Annotation<'_> {
+        match self {
+            Error::Expansion(error) => error.main_annotation(),
+            Error::Primary(error) => error.main_annotation(),
+            Error::InvalidRegex { operand, message } => Annotation::new(
+                AnnotationType::Error,
+                message.as_str().into(),
+                &operand.origin,
+            ),
+        }
+    }
+}
+
+type EvalResult = std::result::Result<bool, Error>;
+
+fn pattern_config() -> Config {
+    let mut config = Config::default();
+    config.anchor_begin = true;
+    config.anchor_end = true;
+    config
+}
+
+/// Expands the word to a pattern and tests if the value matches it.
+///
+/// A broken pattern does not match anything.
+async fn matches_pattern(env: &mut Env, value: &str, pattern: &Word) -> EvalResult {
+    let (mut pattern, _exit_status) = expand_word_attr(env, pattern).await?;
+    // Unquoted backslashes should act as quoting, as in the case command
+    apply_escapes(&mut pattern.chars);
+    let config = pattern_config();
+    Ok(
+        Pattern::parse_with_config(to_pattern_chars(&pattern.chars), config)
+            .map_or(false, |pattern| pattern.is_match(value)),
+    )
+}
+
+/// Converts the expanded characters to a regular expression string.
+///
+/// Quoted characters are escaped so that they match literally.
+fn to_regex_source(chars: &[AttrChar]) -> String {
+    let mut source = String::new();
+    for c in chars {
+        if c.is_quoting {
+            continue;
+        }
+        if c.is_quoted {
+            source.push_str(&regex::escape(c.value.encode_utf8(&mut [0; 4])));
+        } else {
+            source.push(c.value);
+        }
+    }
+    source
+}
+
+/// Expands the word to a regular expression and tests if the value matches
+/// it.
+async fn matches_regex(env: &mut Env, value: &str, regex: &Word) -> EvalResult {
+    let (regex, _exit_status) = expand_word_attr(env, regex).await?;
+    let source = to_regex_source(&regex.chars);
+    match regex::Regex::new(&source) {
+        Ok(regex) => Ok(regex.is_match(value)),
+        Err(error) => Err(Error::InvalidRegex {
+            operand: Field {
+                value: source,
+                origin: regex.origin,
+            },
+            message: error.to_string(),
+        }),
+    }
+}
+
+async fn evaluate_binary(
+    env: &mut Env,
+    left: &Word,
+    operator: CondBinaryOperator,
+    right: &Word,
+) -> EvalResult {
+    use CondBinaryOperator::*;
+    let (left, _exit_status) = expand_word(env, left).await?;
+    match operator {
+        PatternMatch => return matches_pattern(env, &left.value, right).await,
+        PatternNotMatch => return Ok(!matches_pattern(env, &left.value, right).await?),
+        RegexMatch => return matches_regex(env, &left.value, right).await,
+        _ => (),
+    }
+
+    let (right, _exit_status) = expand_word(env, right).await?;
+    match operator {
+        PatternMatch | PatternNotMatch | RegexMatch => unreachable!(),
+        // TODO Apply the collation order of the current locale
+        StringLess => Ok(left.value < right.value),
+        StringGreater => Ok(left.value > right.value),
+        IntEqual => Ok(compare_integers(&left, &right, |l, r| l == r)?),
+        IntNotEqual => Ok(compare_integers(&left, &right, |l, r| l != r)?),
+        IntGreater => Ok(compare_integers(&left, &right, |l, r| l > r)?),
+        IntGreaterEqual => Ok(compare_integers(&left, &right, |l, r| l >= r)?),
+        IntLess => Ok(compare_integers(&left, &right, |l, r| l < r)?),
+        IntLessEqual => Ok(compare_integers(&left, &right, |l, r| l <= r)?),
+        SameFile => Ok(is_same_file(env, &left.value, &right.value)),
+        NewerThan => Ok(is_newer_than(env, &left.value, &right.value)),
+        OlderThan => Ok(is_older_than(env, &left.value, &right.value)),
+    }
+}
+
+/// Evaluates the conditional expression.
+fn evaluate<'a>(
+    env: &'a mut Env,
+    expression: &'a CondExpr,
+) -> Pin<Box<dyn Future<Output = EvalResult> + 'a>> {
+    Box::pin(async move {
+        match expression {
+            CondExpr::Word(word) => {
+                let (field, _exit_status) = expand_word(env, word).await?;
+                Ok(!field.value.is_empty())
+            }
+            CondExpr::Unary { operator, operand } => {
+                let (operand, _exit_status) = expand_word(env, operand).await?;
+                Ok(evaluate_unary(env, *operator, &operand)?)
+            }
+            CondExpr::Binary {
+                left,
+                operator,
+                right,
+            } => evaluate_binary(env, left, *operator, right).await,
+            CondExpr::Not(expression) => Ok(!evaluate(env, expression).await?),
+            CondExpr::And(left, right) => {
+                Ok(evaluate(env, left).await? && evaluate(env, right).await?)
+            }
+            CondExpr::Or(left, right) => {
+                Ok(evaluate(env, left).await? || evaluate(env, right).await?)
+            }
+            CondExpr::Group(expression) => evaluate(env, expression).await,
+        }
+    })
+}
+
+/// Executes the double-bracket command.
+pub async fn execute(env: &mut Env, expression: &CondExpr, location: &Location) -> Result {
+    if env.options.get(PosixlyCorrect) == On {
+        print_error(
+            &mut env.system,
+            "the `[[` command is not supported in the POSIXly-correct mode".into(),
+            "not a POSIX command".into(),
+            location,
+        )
+        .await;
+        return Break(Divert::Interrupt(Some(ExitStatus::ERROR)));
+    }
+
+    env.exit_status = match evaluate(env, expression).await {
+        Ok(true) => ExitStatus::SUCCESS,
+        Ok(false) => ExitStatus::FAILURE,
+        Err(Error::Expansion(error)) => return error.handle(env).await,
+        Err(error) => {
+            print_message(&mut env.system, &error).await;
+            ExitStatus::ERROR
+        }
+    };
+    env.apply_errexit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Command;
+    use crate::tests::assert_stderr;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use std::cell::RefCell;
+    use std::ops::ControlFlow::Continue;
+    use std::rc::Rc;
+    use yash_env::option::Option::ErrExit;
+    use yash_env::system::r#virtual::FileBody;
+    use yash_env::system::r#virtual::INode;
+    use yash_env::system::r#virtual::Mode;
+    use yash_env::variable::Scope;
+    use yash_env::variable::Variable;
+    use yash_env::VirtualSystem;
+    use yash_syntax::syntax::CompoundCommand;
+
+    fn run(env: &mut Env, source: &str) -> Result {
+        let command: CompoundCommand = source.parse().unwrap();
+        command.execute(env).now_or_never().unwrap()
+    }
+
+    fn status(env: &mut Env, source: &str) -> ExitStatus {
+        assert_eq!(run(env, source), Continue(()));
+        env.exit_status
+    }
+
+    fn env_with_variable(name: &str, value: &str) -> Env {
+        let mut env = Env::new_virtual();
+        env.variables
+            .assign(Scope::Global, name.to_string(), Variable::new(value))
+            .unwrap();
+        env
+    }
+
+    #[test]
+    fn string_and_logical_operators() {
+        let mut env = Env::new_virtual();
+        assert_eq!(status(&mut env, "[[ a ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ '' ]]"), ExitStatus::FAILURE);
+        assert_eq!(status(&mut env, "[[ ! '' ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ a && '' ]]"), ExitStatus::FAILURE);
+        assert_eq!(status(&mut env, "[[ '' || a ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ ! ( a || '' ) ]]"), ExitStatus::FAILURE);
+        assert_eq!(status(&mut env, "[[ -n a && -z '' ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ a < b ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ a > b ]]"), ExitStatus::FAILURE);
+    }
+
+    #[test]
+    fn no_field_splitting_or_pathname_expansion() {
+        let mut env = env_with_variable("x", "a b");
+        assert_eq!(status(&mut env, "[[ $x == 'a b' ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ -n $x ]]"), ExitStatus::SUCCESS);
+
+        let mut env = env_with_variable("x", "*");
+        assert_eq!(status(&mut env, "[[ $x == '*' ]]"), ExitStatus::SUCCESS);
+    }
+
+    #[test]
+    fn pattern_matching() {
+        let mut env = env_with_variable("x", "foo.rs");
+        assert_eq!(status(&mut env, "[[ $x == *.rs ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ $x = f?o.* ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ $x == *.c ]]"), ExitStatus::FAILURE);
+        assert_eq!(status(&mut env, "[[ $x != *.c ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ $x == '*.rs' ]]"), ExitStatus::FAILURE);
+        assert_eq!(status(&mut env, "[[ $x == foo\\.* ]]"), ExitStatus::SUCCESS);
+    }
+
+    #[test]
+    fn regex_matching() {
+        let mut env = env_with_variable("x", "abc123");
+        assert_eq!(
+            status(&mut env, "[[ $x =~ ^[a-z]+[0-9]+$ ]]"),
+            ExitStatus::SUCCESS
+        );
+        assert_eq!(status(&mut env, "[[ $x =~ c1 ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ $x =~ ^[0-9] ]]"), ExitStatus::FAILURE);
+        assert_eq!(status(&mut env, "[[ a.c =~ 'a.c' ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ abc =~ 'a.c' ]]"), ExitStatus::FAILURE);
+        assert_eq!(status(&mut env, "[[ ab =~ (a|c)b ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ cd =~ (a|c)b ]]"), ExitStatus::FAILURE);
+        assert_eq!(
+            status(&mut env, "[[ 'a b' =~ ^(a b)$ ]]"),
+            ExitStatus::SUCCESS
+        );
+    }
+
+    #[test]
+    fn invalid_regex() {
+        in_virtual_system(|mut env, state| async move {
+            let command: CompoundCommand = "[[ a =~ [ ]]".parse().unwrap();
+            let result = command.execute(&mut env).await;
+            assert_eq!(result, Continue(()));
+            assert_eq!(env.exit_status, ExitStatus::ERROR);
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("invalid regular expression"), "{stderr:?}")
+            });
+        })
+    }
+
+    #[test]
+    fn integer_comparison() {
+        let mut env = Env::new_virtual();
+        assert_eq!(status(&mut env, "[[ 2 -lt 10 ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ 2 -ge 10 ]]"), ExitStatus::FAILURE);
+        assert_eq!(status(&mut env, "[[ 3 -eq x ]]"), ExitStatus::ERROR);
+    }
+
+    #[test]
+    fn file_primaries() {
+        let system = VirtualSystem::new();
+        {
+            let mut state = system.state.borrow_mut();
+            let file = INode {
+                body: FileBody::new([1]),
+                permissions: Mode(0o644),
+            };
+            state
+                .file_system
+                .save("/file", Rc::new(RefCell::new(file)))
+                .unwrap();
+        }
+        let mut env = Env::with_system(Box::new(system));
+        assert_eq!(status(&mut env, "[[ -f /file ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ -s /file ]]"), ExitStatus::SUCCESS);
+        assert_eq!(status(&mut env, "[[ -d /file ]]"), ExitStatus::FAILURE);
+        assert_eq!(status(&mut env, "[[ -x /file ]]"), ExitStatus::FAILURE);
+        assert_eq!(status(&mut env, "[[ -e /none ]]"), ExitStatus::FAILURE);
+        assert_eq!(
+            status(&mut env, "[[ /file -nt /none ]]"),
+            ExitStatus::SUCCESS
+        );
+    }
+
+    #[test]
+    fn errexit_on_false_expression() {
+        let mut env = Env::new_virtual();
+        env.options.set(ErrExit, On);
+        assert_eq!(run(&mut env, "[[ '' ]]"), Break(Divert::Exit(None)));
+    }
+
+    #[test]
+    fn rejected_in_posixly_correct_mode() {
+        in_virtual_system(|mut env, state| async move {
+            env.options.set(PosixlyCorrect, On);
+            let command: CompoundCommand = "[[ a ]]".parse().unwrap();
+            let result = command.execute(&mut env).await;
+            assert_eq!(result, Break(Divert::Interrupt(Some(ExitStatus::ERROR))));
+            assert_stderr(&state, |stderr| {
+                assert!(stderr.contains("POSIXly-correct"), "{stderr:?}")
+            });
+        })
+    }
+}
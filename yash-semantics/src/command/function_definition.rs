@@ -17,6 +17,7 @@
 //! Implementations of function definition semantics.
 
 use crate::command::Command;
+use crate::command_search::search;
 use crate::expansion::expand_word;
 use crate::expansion::Field;
 use crate::Handle;
@@ -25,10 +26,13 @@ use std::ops::ControlFlow::Continue;
 use std::rc::Rc;
 use yash_env::function::Function;
 use yash_env::function::HashEntry;
+use yash_env::option::On;
+use yash_env::option::Option::HashOnDefinition;
 use yash_env::semantics::ExitStatus;
 use yash_env::semantics::Result;
 use yash_env::Env;
 use yash_syntax::syntax;
+use yash_syntax::syntax::MaybeLiteral;
 
 /// Executes the function definition command.
 ///
@@ -39,6 +43,11 @@ use yash_syntax::syntax;
 /// Finally, the function definition is inserted into the environment, and the
 /// execution ends with an exit status of zero.
 ///
+/// If the `HashOnDefinition` shell option is on, the [command
+/// search](crate::command_search::search) is performed for each literal
+/// command name in the function body so that the paths of external utilities
+/// are cached in advance.
+///
 /// The `ErrExit` shell option is [applied](Env::apply_errexit) on error.
 #[async_trait(?Send)]
 impl Command for syntax::FunctionDefinition {
@@ -78,19 +87,94 @@ async fn define_function(env: &mut Env, def: &syntax::FunctionDefinition) -> Res
     };
     let entry = HashEntry(Rc::new(function));
     env.functions.replace(entry);
+
+    if env.options.get(HashOnDefinition) == On {
+        let mut names = Vec::new();
+        command_names_in_compound_command(&def.body.command, &mut names);
+        for name in names {
+            search(env, &name);
+        }
+    }
+
     env.exit_status = ExitStatus::SUCCESS;
     Continue(())
 }
 
+/// Collects literal command names of simple commands in the list.
+///
+/// Nested function definitions are not examined because their bodies are
+/// searched when they are defined.
+fn command_names_in_list(list: &syntax::List, names: &mut Vec<String>) {
+    for item in &list.0 {
+        let and_or = &item.and_or;
+        let pipelines = std::iter::once(&and_or.first).chain(and_or.rest.iter().map(|(_, p)| p));
+        for pipeline in pipelines {
+            for command in &pipeline.commands {
+                match &**command {
+                    syntax::Command::Simple(command) => {
+                        if let Some(name) = command.words.first() {
+                            names.extend(name.to_string_if_literal());
+                        }
+                    }
+                    syntax::Command::Compound(command) => {
+                        command_names_in_compound_command(&command.command, names)
+                    }
+                    syntax::Command::Function(_) => (),
+                }
+            }
+        }
+    }
+}
+
+/// Collects literal command names of simple commands in the compound command.
+fn command_names_in_compound_command(command: &syntax::CompoundCommand, names: &mut Vec<String>) {
+    use syntax::CompoundCommand::*;
+    match command {
+        Grouping(list) => command_names_in_list(list, names),
+        Subshell { body, .. } => command_names_in_list(body, names),
+        For { body, .. } => command_names_in_list(body, names),
+        While { condition, body } | Until { condition, body } => {
+            command_names_in_list(condition, names);
+            command_names_in_list(body, names);
+        }
+        If {
+            condition,
+            body,
+            elifs,
+            r#else,
+        } => {
+            command_names_in_list(condition, names);
+            command_names_in_list(body, names);
+            for elif in elifs {
+                command_names_in_list(&elif.condition, names);
+                command_names_in_list(&elif.body, names);
+            }
+            if let Some(r#else) = r#else {
+                command_names_in_list(r#else, names);
+            }
+        }
+        Case { items, .. } => {
+            for item in items {
+                command_names_in_list(&item.body, names);
+            }
+        }
+        DoubleBracket { .. } => (),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tests::assert_stderr;
     use futures_util::FutureExt;
+    use std::cell::RefCell;
     use std::ops::ControlFlow::Break;
     use yash_env::option::On;
     use yash_env::option::Option::ErrExit;
     use yash_env::semantics::Divert;
+    use yash_env::system::r#virtual::INode;
+    use yash_env::variable::Scope;
+    use yash_env::variable::Variable;
     use yash_env::VirtualSystem;
     use yash_syntax::source::Location;
 
@@ -213,4 +297,57 @@ mod tests {
         assert_eq!(result, Break(Divert::Exit(None)));
         assert_eq!(env.exit_status, ExitStatus::ERROR);
     }
+
+    fn env_with_executables(paths: &[&str]) -> Env {
+        let system = VirtualSystem::new();
+        {
+            let mut state = system.state.borrow_mut();
+            for path in paths {
+                let mut content = INode::default();
+                content.permissions.0 |= 0o100;
+                let content = Rc::new(RefCell::new(content));
+                state.file_system.save(path, content).unwrap();
+            }
+        }
+        let mut env = Env::with_system(Box::new(system));
+        env.variables
+            .assign(Scope::Global, "PATH".to_string(), Variable::new("/bin"))
+            .unwrap();
+        env
+    }
+
+    #[test]
+    fn hash_on_definition_caches_command_paths() {
+        let mut env = env_with_executables(&["/bin/ls", "/bin/cat", "/bin/sort"]);
+        env.options.set(HashOnDefinition, On);
+        let definition = syntax::FunctionDefinition {
+            has_keyword: false,
+            name: "foo".parse().unwrap(),
+            body: Rc::new("{ ls; if cat; then \\sort; fi | wc; }".parse().unwrap()),
+        };
+
+        let result = definition.execute(&mut env).now_or_never().unwrap();
+        assert_eq!(result, Continue(()));
+        let names = env
+            .path_cache
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["cat", "ls"]);
+        assert_eq!(env.path_cache.get("ls").unwrap().to_bytes(), b"/bin/ls");
+    }
+
+    #[test]
+    fn no_hash_on_definition_by_default() {
+        let mut env = env_with_executables(&["/bin/ls"]);
+        let definition = syntax::FunctionDefinition {
+            has_keyword: false,
+            name: "foo".parse().unwrap(),
+            body: Rc::new("{ ls; }".parse().unwrap()),
+        };
+
+        let result = definition.execute(&mut env).now_or_never().unwrap();
+        assert_eq!(result, Continue(()));
+        assert_eq!(env.path_cache.iter().count(), 0);
+    }
 }
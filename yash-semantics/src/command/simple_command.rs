@@ -121,6 +121,12 @@ use yash_syntax::syntax::Assign;
 /// modified by the redirections are restored after the target has finished
 /// except for external utilities executed in a subshell.
 ///
+/// # Process substitutions
+///
+/// File descriptors opened by process substitutions in the command words,
+/// assignments, and redirections are
+/// [closed](Env::close_process_subst_fds) after the target has finished.
+///
 /// # Assignments
 ///
 /// Assignments are performed in the order of appearance. For each assignment,
@@ -164,35 +170,42 @@ use yash_syntax::syntax::Assign;
 #[async_trait(?Send)]
 impl Command for syntax::SimpleCommand {
     async fn execute(&self, env: &mut Env) -> Result {
-        let (fields, exit_status) = match expand_words(env, &self.words).await {
-            Ok(result) => result,
-            Err(error) => return error.handle(env).await,
-        };
+        let process_subst_fds = env.process_subst_fds.len();
+        let result = execute_simple_command(env, self).await;
+        env.close_process_subst_fds(process_subst_fds);
+        result
+    }
+}
 
-        use crate::command_search::Target::{Builtin, External, Function};
-        if let Some(name) = fields.get(0) {
-            match search(env, &name.value) {
-                Some(Builtin(builtin)) => {
-                    execute_builtin(env, builtin, &self.assigns, fields, &self.redirs).await
-                }
-                Some(Function(function)) => {
-                    execute_function(env, function, &self.assigns, fields, &self.redirs).await
-                }
-                Some(External { path }) => {
-                    execute_external_utility(env, path, &self.assigns, fields, &self.redirs).await
-                }
-                None => {
-                    let path = CString::default();
-                    execute_external_utility(env, path, &self.assigns, fields, &self.redirs).await
-                }
+async fn execute_simple_command(env: &mut Env, command: &syntax::SimpleCommand) -> Result {
+    let (fields, exit_status) = match expand_words(env, &command.words).await {
+        Ok(result) => result,
+        Err(error) => return error.handle(env).await,
+    };
+
+    use crate::command_search::Target::{Builtin, External, Function};
+    if let Some(name) = fields.get(0) {
+        match search(env, &name.value) {
+            Some(Builtin(builtin)) => {
+                execute_builtin(env, builtin, &command.assigns, fields, &command.redirs).await
+            }
+            Some(Function(function)) => {
+                execute_function(env, function, &command.assigns, fields, &command.redirs).await
+            }
+            Some(External { path }) => {
+                execute_external_utility(env, path, &command.assigns, fields, &command.redirs).await
             }
-        } else {
-            let exit_status = exit_status.unwrap_or_default();
-            execute_absent_target(env, &self.assigns, &self.redirs, exit_status).await
-        }?;
+            None => {
+                let path = CString::default();
+                execute_external_utility(env, path, &command.assigns, fields, &command.redirs).await
+            }
+        }
+    } else {
+        let exit_status = exit_status.unwrap_or_default();
+        execute_absent_target(env, &command.assigns, &command.redirs, exit_status).await
+    }?;
 
-        env.apply_errexit()
-    }
+    env.apply_errexit()
 }
 
 async fn perform_assignments(
@@ -227,17 +240,24 @@ use function::execute_function;
 mod external;
 use external::execute_external_utility;
 pub use external::replace_current_process;
+pub use external::start_external_utility_in_subshell_and_wait;
 pub use external::to_c_strings;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tests::assert_stdout;
+    use crate::tests::echo_builtin;
+    use crate::tests::in_virtual_system;
     use crate::tests::return_builtin;
     use futures_util::FutureExt;
     use std::ops::ControlFlow::Break;
+    use yash_env::io::Fd;
     use yash_env::option::Option::ErrExit;
     use yash_env::option::State::On;
     use yash_env::semantics::Divert;
+    use yash_env::system::Errno;
+    use yash_env::System;
 
     #[test]
     fn errexit_on_simple_command() {
@@ -249,4 +269,23 @@ mod tests {
         assert_eq!(result, Break(Divert::Exit(None)));
         assert_eq!(env.exit_status, ExitStatus(93));
     }
+
+    #[test]
+    fn process_subst_fds_closed_after_command() {
+        in_virtual_system(|mut env, state| async move {
+            env.builtins.insert("echo", echo_builtin());
+            let (outer, _) = env.system.pipe().unwrap();
+            env.process_subst_fds.push(outer);
+            let command: syntax::SimpleCommand = "echo <(echo)".parse().unwrap();
+            let result = command.execute(&mut env).await;
+            assert_eq!(result, Continue(()));
+            assert_eq!(env.process_subst_fds, [outer]);
+
+            let fd = assert_stdout(&state, |stdout| {
+                let fd = stdout.strip_prefix("/dev/fd/").unwrap();
+                Fd(fd.trim_end().parse().unwrap())
+            });
+            assert_eq!(env.system.fcntl_getfl(fd), Err(Errno::EBADF));
+        })
+    }
 }
@@ -49,9 +49,6 @@ pub async fn execute_external_utility(
     fields: Vec<Field>,
     redirs: &[Redir],
 ) -> Result {
-    let name = fields[0].clone();
-    let location = name.origin.clone();
-
     let mut xtrace = XTrace::from_options(&env.options);
 
     let env = &mut RedirGuard::new(env);
@@ -65,6 +62,32 @@ pub async fn execute_external_utility(
     trace_fields(xtrace.as_mut(), &fields);
     print(&mut env, xtrace).await;
 
+    env.exit_status = start_external_utility_in_subshell_and_wait(&mut env, path, fields).await;
+    Continue(())
+}
+
+/// Starts an external utility in a subshell and waits for it to finish.
+///
+/// `path` is the path to the external utility. `args` are the command line
+/// words of the utility. The first field must exist and be the name of the
+/// utility as it should be used in error messages.
+///
+/// This function starts the utility in a subshell and waits for it to finish.
+/// The subshell will be a foreground job if job control is enabled. If the
+/// subshell is stopped, it is added to the job list.
+///
+/// If `path` is empty, this function prints an error message that the utility
+/// was not found and returns [`ExitStatus::NOT_FOUND`]. If the subshell cannot
+/// be started, this function prints an error message and returns
+/// [`ExitStatus::NOEXEC`].
+pub async fn start_external_utility_in_subshell_and_wait(
+    env: &mut Env,
+    path: CString,
+    args: Vec<Field>,
+) -> ExitStatus {
+    let name = args[0].clone();
+    let location = name.origin.clone();
+
     if path.to_bytes().is_empty() {
         print_error(
             &mut env.system,
@@ -73,16 +96,15 @@ pub async fn execute_external_utility(
             &name.origin,
         )
         .await;
-        env.exit_status = ExitStatus::NOT_FOUND;
-        return Continue(());
+        return ExitStatus::NOT_FOUND;
     }
 
     let job_name = if env.controls_jobs() {
-        to_job_name(&fields)
+        to_job_name(&args)
     } else {
         String::new()
     };
-    let args = to_c_strings(fields);
+    let args = to_c_strings(args);
     let subshell = Subshell::new(move |env, _job_control| {
         Box::pin(async move {
             replace_current_process(env, path, args, location).await;
@@ -91,7 +113,7 @@ pub async fn execute_external_utility(
     })
     .job_control(JobControl::Foreground);
 
-    match subshell.start_and_wait(&mut env).await {
+    match subshell.start_and_wait(env).await {
         Ok(wait_status) => {
             if let Stopped(pid, _signal) = wait_status {
                 let mut job = Job::new(pid);
@@ -101,7 +123,7 @@ pub async fn execute_external_utility(
                 env.jobs.add(job);
             }
 
-            env.exit_status = wait_status.try_into().unwrap();
+            wait_status.try_into().unwrap()
         }
         Err(errno) => {
             print_error(
@@ -111,11 +133,9 @@ pub async fn execute_external_utility(
                 &name.origin,
             )
             .await;
-            env.exit_status = ExitStatus::NOEXEC;
+            ExitStatus::NOEXEC
         }
     }
-
-    Continue(())
 }
 
 fn to_job_name(fields: &[Field]) -> String {
@@ -34,6 +34,10 @@
 //! For a non-intrinsic built-in or external utility to be chosen as a target, a
 //! corresponding executable file must be present in a directory specified in
 //! the `$PATH` variable.
+//!
+//! The paths found in `$PATH` are remembered in the
+//! [`PathCache`](yash_env::path_cache::PathCache) of the environment so that
+//! the next search for the same name can skip scanning the directories.
 
 use std::collections::HashMap;
 use std::ffi::CStr;
@@ -96,7 +100,23 @@ pub trait PathEnv {
     fn path(&self) -> Option<&Variable>;
     /// Whether there is an executable file at the specified path.
     fn is_executable_file(&self, path: &CStr) -> bool;
-    // TODO Cache the results of external utility search
+
+    /// Returns the path cached for the command name.
+    ///
+    /// The cache must be discarded if it was built for a `$PATH` other than
+    /// the current one. The default implementation returns `None`, which means
+    /// the environment does not cache search results.
+    fn cached_path(&mut self, name: &str) -> Option<CString> {
+        let _ = name;
+        None
+    }
+
+    /// Remembers the path found for the command name.
+    ///
+    /// The default implementation does nothing.
+    fn cache_path(&mut self, name: &str, path: &CStr) {
+        let _ = (name, path);
+    }
 }
 
 /// Part of the shell execution environment command search depends on.
@@ -114,6 +134,20 @@ impl PathEnv for Env {
     fn is_executable_file(&self, path: &CStr) -> bool {
         self.system.is_executable_file(path)
     }
+    fn cached_path(&mut self, name: &str) -> Option<CString> {
+        validate_path_cache(self);
+        self.path_cache.get(name).map(CStr::to_owned)
+    }
+    fn cache_path(&mut self, name: &str, path: &CStr) {
+        validate_path_cache(self);
+        self.path_cache.insert(name.to_owned(), path.to_owned());
+    }
+}
+
+/// Discards the path cache of the environment if `$PATH` has changed.
+pub fn validate_path_cache(env: &mut Env) {
+    let path = env.variables.get("PATH").and_then(|v| v.value.as_ref());
+    env.path_cache.validate(path);
 }
 
 impl SearchEnv for Env {
@@ -167,7 +201,24 @@ pub fn search<E: SearchEnv>(env: &mut E, name: &str) -> Option<Target> {
 ///
 /// Returns the path if successful. Note that the returned path may not be
 /// absolute if the `$PATH` contains a relative path.
+///
+/// If the environment has a [cached path](PathEnv::cached_path) for the name
+/// and the path still names an executable file, this function returns it
+/// without scanning the `$PATH`. Otherwise, the path found is
+/// [cached](PathEnv::cache_path) for later searches.
 pub fn search_path<E: PathEnv>(env: &mut E, name: &str) -> Option<CString> {
+    if let Some(file) = env.cached_path(name) {
+        if env.is_executable_file(&file) {
+            return Some(file);
+        }
+    }
+
+    let file = scan_path(env, name)?;
+    env.cache_path(name, &file);
+    Some(file)
+}
+
+fn scan_path<E: PathEnv>(env: &E, name: &str) -> Option<CString> {
     if let Some(path) = env.path().and_then(|v| v.value.as_ref()) {
         for dir in path.split() {
             let mut file = PathBuf::new();
@@ -188,10 +239,16 @@ pub fn search_path<E: PathEnv>(env: &mut E, name: &str) -> Option<CString> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::command::Command;
     use assert_matches::assert_matches;
+    use futures_util::FutureExt;
     use std::collections::HashSet;
+    use std::ops::ControlFlow::Continue;
     use yash_env::function::HashEntry as FunctionEntry;
+    use yash_env::variable::Scope;
+    use yash_env::variable::Value;
     use yash_syntax::source::Location;
+    use yash_syntax::syntax;
     use yash_syntax::syntax::CompoundCommand;
     use yash_syntax::syntax::FullCompoundCommand;
 
@@ -201,6 +258,7 @@ mod tests {
         functions: FunctionSet,
         path: Option<Variable>,
         executables: HashSet<String>,
+        cache: Option<HashMap<String, CString>>,
     }
 
     impl PathEnv for DummyEnv {
@@ -214,6 +272,14 @@ mod tests {
                 false
             }
         }
+        fn cached_path(&mut self, name: &str) -> Option<CString> {
+            self.cache.as_ref()?.get(name).cloned()
+        }
+        fn cache_path(&mut self, name: &str, path: &CStr) {
+            if let Some(cache) = &mut self.cache {
+                cache.insert(name.to_owned(), path.to_owned());
+            }
+        }
     }
 
     impl SearchEnv for DummyEnv {
@@ -478,4 +544,94 @@ mod tests {
             assert_eq!(path.to_bytes(), "foo".as_bytes());
         });
     }
+
+    #[test]
+    fn found_path_is_cached() {
+        let mut env = DummyEnv::default();
+        env.cache = Some(HashMap::new());
+        env.path = Some(Variable::new("/usr/bin:/bin").export());
+        env.executables.insert("/bin/foo".to_string());
+
+        assert_matches!(search(&mut env, "foo"), Some(Target::External { path }) => {
+            assert_eq!(path.to_bytes(), "/bin/foo".as_bytes());
+        });
+        let cached = env.cache.as_ref().unwrap().get("foo").unwrap();
+        assert_eq!(cached.to_bytes(), "/bin/foo".as_bytes());
+
+        // The cached path is used even if another executable appears earlier
+        env.executables.insert("/usr/bin/foo".to_string());
+        assert_matches!(search(&mut env, "foo"), Some(Target::External { path }) => {
+            assert_eq!(path.to_bytes(), "/bin/foo".as_bytes());
+        });
+    }
+
+    #[test]
+    fn stale_cached_path_is_replaced() {
+        let mut env = DummyEnv::default();
+        env.cache = Some(HashMap::new());
+        env.cache
+            .as_mut()
+            .unwrap()
+            .insert("foo".to_string(), CString::new("/bin/foo").unwrap());
+        env.path = Some(Variable::new("/usr/bin:/bin").export());
+        env.executables.insert("/usr/bin/foo".to_string());
+
+        assert_matches!(search(&mut env, "foo"), Some(Target::External { path }) => {
+            assert_eq!(path.to_bytes(), "/usr/bin/foo".as_bytes());
+        });
+        let cached = env.cache.as_ref().unwrap().get("foo").unwrap();
+        assert_eq!(cached.to_bytes(), "/usr/bin/foo".as_bytes());
+    }
+
+    #[test]
+    fn env_path_cache_is_invalidated_by_path_assignment() {
+        let mut env = Env::new_virtual();
+        let set_path = |env: &mut Env, value: &str| {
+            env.variables
+                .assign(Scope::Global, "PATH".to_string(), Variable::new(value))
+                .unwrap();
+        };
+        let foo = CString::new("/bin/foo").unwrap();
+        set_path(&mut env, "/bin");
+        env.cache_path("foo", &foo);
+        assert_eq!(env.cached_path("foo"), Some(foo));
+
+        set_path(&mut env, "/usr/bin");
+        assert_eq!(env.cached_path("foo"), None);
+    }
+
+    #[test]
+    fn env_path_cache_is_invalidated_by_assignment_of_same_path() {
+        let mut env = Env::new_virtual();
+        let command: syntax::SimpleCommand = "PATH=/bin".parse().unwrap();
+        let result = command.execute(&mut env).now_or_never().unwrap();
+        assert_eq!(result, Continue(()));
+        let foo = CString::new("/bin/foo").unwrap();
+        env.cache_path("foo", &foo);
+        assert_eq!(env.cached_path("foo"), Some(foo));
+
+        let command: syntax::SimpleCommand = "PATH=$PATH".parse().unwrap();
+        let result = command.execute(&mut env).now_or_never().unwrap();
+        assert_eq!(result, Continue(()));
+        assert_eq!(
+            env.variables.get("PATH").unwrap().value,
+            Some(Value::scalar("/bin"))
+        );
+        assert_eq!(env.cached_path("foo"), None);
+    }
+
+    #[test]
+    fn env_path_cache_is_invalidated_by_same_assignment_executed_again() {
+        let mut env = Env::new_virtual();
+        let command: syntax::SimpleCommand = "PATH=/bin".parse().unwrap();
+        let result = command.execute(&mut env).now_or_never().unwrap();
+        assert_eq!(result, Continue(()));
+        let foo = CString::new("/bin/foo").unwrap();
+        env.cache_path("foo", &foo);
+        assert_eq!(env.cached_path("foo"), Some(foo));
+
+        let result = command.execute(&mut env).now_or_never().unwrap();
+        assert_eq!(result, Continue(()));
+        assert_eq!(env.cached_path("foo"), None);
+    }
 }
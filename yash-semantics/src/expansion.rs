@@ -42,7 +42,10 @@
 //!
 //! ## Brace expansion
 //!
-//! The brace expansion produces copies of a field containing a pair of braces.
+//! The [brace expansion](brace) produces copies of a field containing a pair
+//! of braces. It is performed only if the `BraceExpand` [shell
+//! option](yash_env::option::Option) is on and the `PosixlyCorrect` option is
+//! off.
 //!
 //! ## Field splitting
 //!
@@ -67,6 +70,7 @@
 
 pub mod attr;
 pub mod attr_strip;
+pub mod brace;
 pub mod glob;
 pub mod initial;
 pub mod phrase;
@@ -88,6 +92,10 @@ use self::quote_removal::skip_quotes;
 use self::split::Ifs;
 use std::borrow::Cow;
 use thiserror::Error;
+use yash_env::option::Option::BraceExpand;
+use yash_env::option::Option::PosixlyCorrect;
+use yash_env::option::State::Off;
+use yash_env::option::State::On;
 use yash_env::semantics::ExitStatus;
 use yash_env::system::Errno;
 use yash_env::variable::ReadOnlyError;
@@ -109,6 +117,10 @@ pub enum ErrorCause {
     #[error("error in command substitution: {0}")]
     CommandSubstError(Errno),
 
+    /// System error while performing a process substitution.
+    #[error("error in process substitution: {0}")]
+    ProcessSubstError(Errno),
+
     /// Error while evaluating an arithmetic expansion.
     #[error(transparent)]
     ArithError(ArithError),
@@ -138,6 +150,7 @@ impl ErrorCause {
         use ErrorCause::*;
         match self {
             CommandSubstError(_) => "error performing the command substitution",
+            ProcessSubstError(_) => "error performing the process substitution",
             ArithError(_) => "error evaluating the arithmetic expansion",
             AssignReadOnly(_) => "cannot assign to read-only variable",
             UnsetParameter => "unset parameter",
@@ -152,7 +165,7 @@ impl ErrorCause {
         // TODO Localize
         use ErrorCause::*;
         match self {
-            CommandSubstError(e) => e.desc().into(),
+            CommandSubstError(e) | ProcessSubstError(e) => e.desc().into(),
             ArithError(e) => e.to_string().into(),
             AssignReadOnly(e) => e.to_string().into(),
             UnsetParameter => "unset parameter disallowed by the nounset option".into(),
@@ -169,6 +182,7 @@ impl ErrorCause {
         use ErrorCause::*;
         match self {
             CommandSubstError(_) => None,
+            ProcessSubstError(_) => None,
             ArithError(e) => e.related_location(),
             AssignReadOnly(e) => Some((
                 &e.read_only_location,
@@ -300,7 +314,15 @@ pub async fn expand_words<'a, I: IntoIterator<Item = &'a Word>>(
         }));
     }
 
-    // TODO brace expansion
+    // brace expansion //
+    let options = &env.inner.options;
+    if options.get(BraceExpand) == On && options.get(PosixlyCorrect) == Off {
+        let mut expanded_fields = Vec::with_capacity(fields.len());
+        for field in fields {
+            brace::expand_into(field, &mut expanded_fields);
+        }
+        fields = expanded_fields;
+    }
 
     // field splitting //
     use yash_env::variable::Value::Scalar;
@@ -410,6 +432,54 @@ mod tests {
         })
     }
 
+    #[test]
+    fn expand_words_performs_brace_expansion_if_enabled() {
+        let mut env = yash_env::Env::new_virtual();
+        let words = &["a{b,'c d'}".parse().unwrap(), "{1..2}".parse().unwrap()];
+        let (fields, _) = expand_words(&mut env, words)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let values = fields.iter().map(|f| f.value.as_str()).collect::<Vec<_>>();
+        assert_eq!(values, ["a{b,c d}", "{1..2}"]);
+
+        env.options.set(BraceExpand, On);
+        let (fields, _) = expand_words(&mut env, words)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let values = fields.iter().map(|f| f.value.as_str()).collect::<Vec<_>>();
+        assert_eq!(values, ["ab", "ac d", "1", "2"]);
+    }
+
+    #[test]
+    fn expand_words_skips_brace_expansion_in_posixly_correct_mode() {
+        let mut env = yash_env::Env::new_virtual();
+        env.options.set(BraceExpand, On);
+        env.options.set(PosixlyCorrect, On);
+        let words = &["{a,b}".parse().unwrap()];
+        let (fields, _) = expand_words(&mut env, words)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_matches!(fields.as_slice(), [f] => assert_eq!(f.value, "{a,b}"));
+    }
+
+    #[test]
+    fn brace_expansion_does_not_apply_to_parameter_values() {
+        let mut env = yash_env::Env::new_virtual();
+        env.options.set(BraceExpand, On);
+        env.variables
+            .assign(Scope::Global, "v".to_string(), Variable::new("{a,b}"))
+            .unwrap();
+        let words = &["$v".parse().unwrap()];
+        let (fields, _) = expand_words(&mut env, words)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_matches!(fields.as_slice(), [f] => assert_eq!(f.value, "{a,b}"));
+    }
+
     #[test]
     fn expand_words_performs_field_splitting_possibly_with_default_ifs() {
         let mut env = yash_env::Env::new_virtual();
@@ -0,0 +1,389 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Brace expansion
+//!
+//! The brace expansion produces copies of a field containing a pair of braces.
+//! A brace expression is either a comma-separated list of alternatives like
+//! `{a,b,c}` or a sequence like `{1..10}` or `{a..z..2}`.
+//!
+//! In a list, the field is copied for each alternative, replacing the brace
+//! expression with the alternative. Brace expressions can be nested. For
+//! example, `a{b,c{d,e}}f` expands to `abf`, `acdf`, and `acef`.
+//!
+//! A sequence consists of a start and end value separated by `..`, optionally
+//! followed by another `..` and a step. The start and end values must be both
+//! integers or both single non-digit characters. The field is copied for each
+//! value between them, inclusive, counting by the absolute value of the step
+//! (one by default). The integers are padded with zeros if either the start or end
+//! value has a leading zero. The generated characters have the
+//! [`HardExpansion`](Origin::HardExpansion) origin.
+//!
+//! Only unquoted characters with the [`Literal`](Origin::Literal) origin can
+//! delimit a brace expression, so characters resulting from other expansions
+//! never introduce a brace expansion. A pair of braces that does not form a
+//! valid list or sequence is left intact.
+//!
+//! # Example
+//!
+//! ```
+//! use yash_syntax::source::Location;
+//! use yash_semantics::expansion::attr::{AttrChar, AttrField, Origin};
+//! use yash_semantics::expansion::brace::expand;
+//!
+//! fn field(s: &str) -> AttrField {
+//!     let chars = s.chars()
+//!         .map(|c| AttrChar {
+//!             value: c,
+//!             origin: Origin::Literal,
+//!             is_quoted: false,
+//!             is_quoting: false,
+//!         })
+//!         .collect();
+//!     let origin = Location::dummy("");
+//!     AttrField { chars, origin }
+//! }
+//! fn values(fields: Vec<AttrField>) -> Vec<String> {
+//!     fields.iter().map(|f| f.chars.iter().map(|c| c.value).collect()).collect()
+//! }
+//!
+//! let fields: Vec<AttrField> = expand(field("a{b,c}d"));
+//! assert_eq!(values(fields), ["abd", "acd"]);
+//!
+//! let fields: Vec<AttrField> = expand(field("x{1..3}"));
+//! assert_eq!(values(fields), ["x1", "x2", "x3"]);
+//!
+//! let fields: Vec<AttrField> = expand(field("{a}"));
+//! assert_eq!(values(fields), ["{a}"]);
+//! ```
+
+use super::attr::AttrChar;
+use super::attr::AttrField;
+use super::attr::Origin;
+use yash_syntax::source::Location;
+
+/// Tests whether the character is an unquoted literal `value`.
+fn is_special(c: &AttrChar, value: char) -> bool {
+    c.value == value && c.origin == Origin::Literal && !c.is_quoted && !c.is_quoting
+}
+
+/// Finds the `}` matching the `{` at `open`.
+///
+/// Returns the index of the `}` and those of the top-level commas in between.
+fn find_close(chars: &[AttrChar], open: usize) -> Option<(usize, Vec<usize>)> {
+    let mut depth = 0_usize;
+    let mut commas = Vec::new();
+    for (i, c) in chars.iter().enumerate().skip(open + 1) {
+        if is_special(c, '{') {
+            depth += 1;
+        } else if is_special(c, '}') {
+            if depth == 0 {
+                return Some((i, commas));
+            }
+            depth -= 1;
+        } else if depth == 0 && is_special(c, ',') {
+            commas.push(i);
+        }
+    }
+    None
+}
+
+fn generated_chars(value: &str) -> Vec<AttrChar> {
+    value
+        .chars()
+        .map(|value| AttrChar {
+            value,
+            origin: Origin::HardExpansion,
+            is_quoted: false,
+            is_quoting: false,
+        })
+        .collect()
+}
+
+/// Returns the values between `start` and `end`, inclusive.
+fn range(start: i64, end: i64, step: i64) -> Option<Vec<i64>> {
+    let step = step.checked_abs().filter(|&step| step != 0)?;
+    let mut values = Vec::new();
+    let mut value = start;
+    loop {
+        values.push(value);
+        let next = if start <= end {
+            value.checked_add(step).filter(|&next| next <= end)
+        } else {
+            value.checked_sub(step).filter(|&next| next >= end)
+        };
+        match next {
+            Some(next) => value = next,
+            None => return Some(values),
+        }
+    }
+}
+
+/// Tests whether the integer has a leading zero that requests padding.
+fn has_leading_zero(value: &str) -> bool {
+    let digits = value.trim_start_matches(['-', '+']);
+    digits.len() > 1 && digits.starts_with('0')
+}
+
+/// Parses the content of braces as a sequence.
+///
+/// Returns `None` if the content is not a valid sequence.
+fn sequence(content: &[AttrChar]) -> Option<Vec<Vec<AttrChar>>> {
+    if !content
+        .iter()
+        .all(|c| c.origin == Origin::Literal && !c.is_quoted && !c.is_quoting)
+    {
+        return None;
+    }
+    let content = content.iter().map(|c| c.value).collect::<String>();
+    let parts = content.split("..").collect::<Vec<_>>();
+    let (start, end, step) = match parts[..] {
+        [start, end] => (start, end, 1),
+        [start, end, step] => (start, end, step.parse().ok()?),
+        _ => return None,
+    };
+
+    if let (Ok(start_value), Ok(end_value)) = (start.parse::<i64>(), end.parse::<i64>()) {
+        let width = if has_leading_zero(start) || has_leading_zero(end) {
+            start.len().max(end.len())
+        } else {
+            0
+        };
+        let values = range(start_value, end_value, step)?;
+        return Some(
+            values
+                .into_iter()
+                .map(|value| generated_chars(&format!("{value:0width$}")))
+                .collect(),
+        );
+    }
+
+    let mut start_chars = start.chars();
+    let mut end_chars = end.chars();
+    match (
+        start_chars.next(),
+        start_chars.next(),
+        end_chars.next(),
+        end_chars.next(),
+    ) {
+        (Some(start), None, Some(end), None)
+            if !start.is_ascii_digit() && !end.is_ascii_digit() =>
+        {
+            let values = range(start as i64, end as i64, step)?;
+            Some(
+                values
+                    .into_iter()
+                    .filter_map(|value| char::from_u32(value as u32))
+                    .map(|value| generated_chars(value.encode_utf8(&mut [0; 4])))
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Parses the brace expression starting at `open`.
+///
+/// Returns the index of the closing brace and the alternatives that replace
+/// the brace expression, or `None` if the braces do not form a valid brace
+/// expression.
+fn parse(chars: &[AttrChar], open: usize) -> Option<(usize, Vec<Vec<AttrChar>>)> {
+    let (close, commas) = find_close(chars, open)?;
+    if commas.is_empty() {
+        let alternatives = sequence(&chars[open + 1..close])?;
+        return Some((close, alternatives));
+    }
+
+    let starts = std::iter::once(open).chain(commas.iter().copied());
+    let ends = commas.iter().copied().chain(std::iter::once(close));
+    let alternatives = starts
+        .zip(ends)
+        .map(|(start, end)| chars[start + 1..end].to_vec())
+        .collect();
+    Some((close, alternatives))
+}
+
+/// Expands brace expressions found at or after `start` in `chars`.
+fn expand_from<R>(chars: Vec<AttrChar>, start: usize, origin: &Location, results: &mut R)
+where
+    R: Extend<AttrField>,
+{
+    let mut start = start;
+    while let Some(open) = chars[start..]
+        .iter()
+        .position(|c| is_special(c, '{'))
+        .map(|i| start + i)
+    {
+        if let Some((close, alternatives)) = parse(&chars, open) {
+            for alternative in alternatives {
+                let mut new_chars = Vec::with_capacity(open + alternative.len() + chars.len());
+                new_chars.extend_from_slice(&chars[..open]);
+                new_chars.extend(alternative);
+                new_chars.extend_from_slice(&chars[close + 1..]);
+                // The prefix contains no more brace expressions to expand
+                expand_from(new_chars, open, origin, results);
+            }
+            return;
+        }
+        start = open + 1;
+    }
+
+    // TODO Use Extend::extend_one when stabilized (rust#72631)
+    results.extend(std::iter::once(AttrField {
+        chars,
+        origin: origin.clone(),
+    }));
+}
+
+/// Performs brace expansion and appends the result to a collection.
+///
+/// This function applies brace expansion to the given field and extends the
+/// given collection with the results. If the field contains no valid brace
+/// expression, the field is added as is. The resultant fields share the same
+/// origin as the input field.
+///
+/// See also [`expand`], which returns the results in a new collection rather
+/// than extending an existing one.
+pub fn expand_into<R>(field: AttrField, results: &mut R)
+where
+    R: Extend<AttrField>,
+{
+    expand_from(field.chars, 0, &field.origin, results)
+}
+
+/// Performs brace expansion and returns the result in a new collection.
+///
+/// This function works similarly to [`expand_into`], but returns the results
+/// in a new collection.
+pub fn expand<R>(field: AttrField) -> R
+where
+    R: Default + Extend<AttrField>,
+{
+    let mut results = R::default();
+    expand_into(field, &mut results);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(s: &str) -> AttrField {
+        let chars = s
+            .chars()
+            .map(|value| AttrChar {
+                value,
+                origin: Origin::Literal,
+                is_quoted: false,
+                is_quoting: false,
+            })
+            .collect();
+        let origin = Location::dummy(s);
+        AttrField { chars, origin }
+    }
+
+    fn expand_to_strings(field: AttrField) -> Vec<String> {
+        let fields: Vec<AttrField> = expand(field);
+        fields
+            .iter()
+            .map(|field| field.chars.iter().map(|c| c.value).collect())
+            .collect()
+    }
+
+    #[test]
+    fn no_braces() {
+        assert_eq!(expand_to_strings(field("")), [""]);
+        assert_eq!(expand_to_strings(field("abc")), ["abc"]);
+    }
+
+    #[test]
+    fn comma_list() {
+        assert_eq!(expand_to_strings(field("{a,b}")), ["a", "b"]);
+        assert_eq!(expand_to_strings(field("x{a,,bc}y")), ["xay", "xy", "xbcy"]);
+        assert_eq!(
+            expand_to_strings(field("{a,b}{1,2}")),
+            ["a1", "a2", "b1", "b2"]
+        );
+    }
+
+    #[test]
+    fn nested_lists() {
+        assert_eq!(
+            expand_to_strings(field("a{b,c{d,e}}f")),
+            ["abf", "acdf", "acef"]
+        );
+        assert_eq!(expand_to_strings(field("{a{b,c}}")), ["{ab}", "{ac}"]);
+    }
+
+    #[test]
+    fn invalid_braces_are_left_intact() {
+        assert_eq!(expand_to_strings(field("{}")), ["{}"]);
+        assert_eq!(expand_to_strings(field("{a}")), ["{a}"]);
+        assert_eq!(expand_to_strings(field("{a,b")), ["{a,b"]);
+        assert_eq!(expand_to_strings(field("a,b}")), ["a,b}"]);
+        assert_eq!(expand_to_strings(field("{1..}")), ["{1..}"]);
+        assert_eq!(expand_to_strings(field("{1..a}")), ["{1..a}"]);
+        assert_eq!(expand_to_strings(field("{1..3..0}")), ["{1..3..0}"]);
+        assert_eq!(expand_to_strings(field("{x{a,b}")), ["{xa", "{xb"]);
+    }
+
+    #[test]
+    fn integer_sequences() {
+        assert_eq!(expand_to_strings(field("{1..3}")), ["1", "2", "3"]);
+        assert_eq!(expand_to_strings(field("{3..1}")), ["3", "2", "1"]);
+        assert_eq!(expand_to_strings(field("{-1..1}")), ["-1", "0", "1"]);
+        assert_eq!(expand_to_strings(field("{0..10..4}")), ["0", "4", "8"]);
+        assert_eq!(expand_to_strings(field("{5..1..-2}")), ["5", "3", "1"]);
+        assert_eq!(expand_to_strings(field("{08..10}")), ["08", "09", "10"]);
+    }
+
+    #[test]
+    fn character_sequences() {
+        assert_eq!(expand_to_strings(field("{a..c}")), ["a", "b", "c"]);
+        assert_eq!(expand_to_strings(field("{e..a..2}")), ["e", "c", "a"]);
+    }
+
+    #[test]
+    fn generated_characters_are_hard_expansion() {
+        let fields: Vec<AttrField> = expand(field("{z..|}"));
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields[1].chars[0].value, '{');
+        assert_eq!(fields[1].chars[0].origin, Origin::HardExpansion);
+    }
+
+    #[test]
+    fn quoted_braces_are_not_special() {
+        let mut input = field("{a,b}");
+        input.chars[2].is_quoted = true;
+        assert_eq!(expand_to_strings(input), ["{a,b}"]);
+
+        let mut input = field("{a,b}");
+        input.chars[0].origin = Origin::SoftExpansion;
+        assert_eq!(expand_to_strings(input), ["{a,b}"]);
+
+        let mut input = field("{1..3}");
+        input.chars[1].is_quoted = true;
+        assert_eq!(expand_to_strings(input), ["{1..3}"]);
+    }
+
+    #[test]
+    fn results_share_origin() {
+        let input = field("{a,b}");
+        let origin = input.origin.clone();
+        let fields: Vec<AttrField> = expand(input);
+        assert!(fields.iter().all(|field| field.origin == origin));
+    }
+}
@@ -125,6 +125,7 @@ pub async fn expand<E: Expand>(env: &mut Env<'_>, e: &E) -> Result<Phrase, Error
 mod arith;
 mod command_subst;
 mod param;
+mod process_subst;
 mod slice;
 mod text;
 mod tilde;
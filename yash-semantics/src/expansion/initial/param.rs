@@ -50,6 +50,7 @@ impl<'a> From<&'a Param> for ParamRef<'a> {
 // TODO Consider exporting these modules
 mod name;
 mod resolve;
+mod subst;
 mod switch;
 mod trim;
 
@@ -109,6 +110,12 @@ impl ParamRef<'_> {
                     trim::apply(env, trim, value).await?
                 }
             }
+
+            Modifier::Subst(subst) => {
+                if let Some(value) = &mut value {
+                    subst::apply(env, subst, value).await?
+                }
+            }
         }
 
         let mut phrase = into_phrase(value);
@@ -296,6 +303,24 @@ pub mod tests {
         assert_eq!(phrase, Phrase::one_empty_field());
     }
 
+    #[test]
+    fn subst_positional_params() {
+        use yash_syntax::syntax::{Subst, SubstType};
+
+        let mut env = env_with_positional_params_and_ifs();
+        let mut param = param("@");
+        param.modifier = Modifier::Subst(Subst {
+            r#type: SubstType::First,
+            pattern: "?".parse().unwrap(),
+            replacement: "x".parse().unwrap(),
+        });
+        let param = ParamRef::from(&param);
+        let mut env = Env::new(&mut env);
+
+        let phrase = param.expand(&mut env).now_or_never().unwrap().unwrap();
+        assert_eq!(phrase, Phrase::Full(vec![to_field("x"), to_field("x")]));
+    }
+
     #[test]
     fn unset_option() {
         let mut env = yash_env::Env::new_virtual();
@@ -0,0 +1,221 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Parameter expansion substitution semantics
+
+use super::Env;
+use super::Error;
+use crate::expansion::attr::fnmatch::apply_escapes;
+use crate::expansion::attr::fnmatch::to_pattern_chars;
+use crate::expansion::initial::expand;
+use yash_env::variable::Value::{self, Array, Scalar};
+use yash_fnmatch::Config;
+use yash_fnmatch::Pattern;
+use yash_syntax::syntax::Subst;
+use yash_syntax::syntax::SubstType::{All, First, Prefix, Suffix};
+
+/// Replaces the first non-empty match of the pattern in the value.
+///
+/// If the pattern is anchored, an empty match is also replaced.
+fn subst_first(pattern: &Pattern, value: &mut String, replacement: &str) {
+    let config = pattern.config();
+    let anchored = config.anchor_begin || config.anchor_end;
+    if let Some(range) = pattern.find(value) {
+        if anchored || !range.is_empty() {
+            value.replace_range(range, replacement);
+        }
+    }
+}
+
+/// Replaces all non-overlapping, non-empty matches of the pattern in the value.
+fn subst_all(pattern: &Pattern, value: &mut String, replacement: &str) {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value.as_str();
+    while let Some(range) = pattern.find(rest) {
+        result.push_str(&rest[..range.start]);
+        if range.is_empty() {
+            // Skip one character to find a non-empty match
+            let Some(c) = rest[range.start..].chars().next() else {
+                rest = "";
+                break;
+            };
+            result.push(c);
+            rest = &rest[range.start + c.len_utf8()..];
+        } else {
+            result.push_str(replacement);
+            rest = &rest[range.end..];
+        }
+    }
+    result.push_str(rest);
+    *value = result;
+}
+
+/// Applies the substitution modifier to the value.
+pub async fn apply(env: &mut Env<'_>, subst: &Subst, value: &mut Value) -> Result<(), Error> {
+    let expansion = expand(env, &subst.pattern).await?;
+    let mut pattern = expansion.ifs_join(&env.inner.variables);
+    apply_escapes(&mut pattern);
+
+    let expansion = expand(env, &subst.replacement).await?;
+    let replacement = expansion
+        .ifs_join(&env.inner.variables)
+        .into_iter()
+        .filter(|c| !c.is_quoting)
+        .map(|c| c.value)
+        .collect::<String>();
+
+    let mut config = Config::default();
+    match subst.r#type {
+        First | All => (),
+        Prefix => config.anchor_begin = true,
+        Suffix => config.anchor_end = true,
+    }
+    let pattern = match Pattern::parse_with_config(to_pattern_chars(&pattern), config) {
+        Ok(parse) => parse,
+        Err(_error) => {
+            // Treat the broken pattern as a valid pattern that does not match anything
+            return Ok(());
+        }
+    };
+
+    let subst_value = match subst.r#type {
+        All => subst_all,
+        First | Prefix | Suffix => subst_first,
+    };
+    match value {
+        Scalar(value) => subst_value(&pattern, value, &replacement),
+        Array(array) => {
+            for value in array {
+                subst_value(&pattern, value, &replacement);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt;
+    use yash_syntax::syntax::SubstType;
+
+    fn modifier(r#type: SubstType, pattern: &str, replacement: &str) -> Subst {
+        Subst {
+            r#type,
+            pattern: pattern.parse().unwrap(),
+            replacement: replacement.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn first_with_scalar() {
+        let mut env = yash_env::Env::new_virtual();
+        let mut env = Env::new(&mut env);
+        let subst = modifier(First, "2*3", "x");
+        let mut value = Value::scalar("123123123");
+        let result = apply(&mut env, &subst, &mut value).now_or_never().unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(value, Value::scalar("1x"));
+    }
+
+    #[test]
+    fn first_with_array() {
+        let mut env = yash_env::Env::new_virtual();
+        let mut env = Env::new(&mut env);
+        let subst = modifier(First, "a", "<'&'>");
+        let mut value = Value::array(["", "bab", "aa"]);
+        let result = apply(&mut env, &subst, &mut value).now_or_never().unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(value, Value::array(["", "b<&>b", "<&>a"]));
+    }
+
+    #[test]
+    fn first_unmatched() {
+        let mut env = yash_env::Env::new_virtual();
+        let mut env = Env::new(&mut env);
+        let subst = modifier(First, "x", "y");
+        let mut value = Value::scalar("abc");
+        let result = apply(&mut env, &subst, &mut value).now_or_never().unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(value, Value::scalar("abc"));
+    }
+
+    #[test]
+    fn all_with_scalar() {
+        let mut env = yash_env::Env::new_virtual();
+        let mut env = Env::new(&mut env);
+        let subst = modifier(All, "2?", "");
+        let mut value = Value::scalar("123123123");
+        let result = apply(&mut env, &subst, &mut value).now_or_never().unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(value, Value::scalar("111"));
+    }
+
+    #[test]
+    fn all_with_empty_pattern() {
+        let mut env = yash_env::Env::new_virtual();
+        let mut env = Env::new(&mut env);
+        let subst = modifier(All, "", "x");
+        let mut value = Value::scalar("abc");
+        let result = apply(&mut env, &subst, &mut value).now_or_never().unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(value, Value::scalar("abc"));
+    }
+
+    #[test]
+    fn prefix() {
+        let mut env = yash_env::Env::new_virtual();
+        let mut env = Env::new(&mut env);
+        let subst = modifier(Prefix, "*2", "x");
+        let mut value = Value::scalar("123123");
+        let result = apply(&mut env, &subst, &mut value).now_or_never().unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(value, Value::scalar("x3"));
+
+        let subst = modifier(Prefix, "", "x");
+        let result = apply(&mut env, &subst, &mut value).now_or_never().unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(value, Value::scalar("xx3"));
+    }
+
+    #[test]
+    fn suffix() {
+        let mut env = yash_env::Env::new_virtual();
+        let mut env = Env::new(&mut env);
+        let subst = modifier(Suffix, "2*", "x");
+        let mut value = Value::scalar("123123");
+        let result = apply(&mut env, &subst, &mut value).now_or_never().unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(value, Value::scalar("1x"));
+
+        let subst = modifier(Suffix, "1", "x");
+        let result = apply(&mut env, &subst, &mut value).now_or_never().unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(value, Value::scalar("1x"));
+    }
+
+    #[test]
+    fn quoted_pattern_matches_literally() {
+        let mut env = yash_env::Env::new_virtual();
+        let mut env = Env::new(&mut env);
+        let subst = modifier(All, r"\*", "-");
+        let mut value = Value::scalar("a*b*c");
+        let result = apply(&mut env, &subst, &mut value).now_or_never().unwrap();
+        assert_eq!(result, Ok(()));
+        assert_eq!(value, Value::scalar("a-b-c"));
+    }
+}
@@ -0,0 +1,193 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Expansion of process substitution
+
+use super::super::attr::AttrChar;
+use super::super::attr::Origin;
+use super::super::phrase::Phrase;
+use super::Env;
+use super::Error;
+use crate::expansion::ErrorCause;
+use crate::Handle;
+use crate::ReadEvalLoop;
+use std::rc::Rc;
+use yash_env::io::Fd;
+use yash_env::io::MIN_INTERNAL_FD;
+use yash_env::subshell::Subshell;
+use yash_env::system::FdFlag;
+use yash_env::System;
+use yash_syntax::parser::lex::Lexer;
+use yash_syntax::source::Location;
+use yash_syntax::source::Source;
+use yash_syntax::syntax::ProcessSubstKind;
+
+/// Performs process substitution
+///
+/// This function starts a subshell that runs the command with its standard
+/// output (for `<(...)`) or standard input (for `>(...)`) connected to a
+/// pipe. The other end of the pipe is kept open in the shell and added to
+/// [`process_subst_fds`](yash_env::Env::process_subst_fds). The result is the
+/// pathname `/dev/fd/N` that refers to the file descriptor.
+///
+/// The shell does not wait for the subshell to finish.
+pub async fn expand(
+    kind: ProcessSubstKind,
+    command: Rc<str>,
+    location: Location,
+    env: &mut Env<'_>,
+) -> Result<Phrase, Error> {
+    let error = |errno| Error {
+        cause: ErrorCause::ProcessSubstError(errno),
+        location: location.clone(),
+    };
+
+    let (reader, writer) = env.inner.system.pipe().map_err(error)?;
+    let (shell_fd, subshell_fd, target_fd) = match kind {
+        ProcessSubstKind::Input => (reader, writer, Fd::STDOUT),
+        ProcessSubstKind::Output => (writer, reader, Fd::STDIN),
+    };
+
+    // Start a subshell to run the command
+    let original = location.clone();
+    let subshell = Subshell::new(move |env, _job_control| {
+        Box::pin(async move {
+            env.system.close(shell_fd).ok();
+            if subshell_fd != target_fd {
+                if let Err(errno) = env.system.dup2(subshell_fd, target_fd) {
+                    let error = Error {
+                        cause: ErrorCause::ProcessSubstError(errno),
+                        location: original,
+                    };
+                    return error.handle(env).await;
+                }
+                env.system.close(subshell_fd).ok();
+            }
+
+            let mut lexer = Lexer::from_memory(&command, Source::ProcessSubst { original });
+            ReadEvalLoop::new(env, &mut lexer).run().await
+        })
+    });
+    let subshell_result = subshell.start(env.inner).await;
+    env.inner.system.close(subshell_fd).ok();
+    if let Err(errno) = subshell_result {
+        env.inner.system.close(shell_fd).ok();
+        return Err(error(errno));
+    }
+
+    // Move the FD out of the range reserved for the user
+    let fd = env
+        .inner
+        .system
+        .dup(shell_fd, MIN_INTERNAL_FD, FdFlag::empty());
+    env.inner.system.close(shell_fd).ok();
+    let fd = fd.map_err(error)?;
+    env.inner.process_subst_fds.push(fd);
+
+    let chars = format!("/dev/fd/{fd}")
+        .chars()
+        .map(|value| AttrChar {
+            value,
+            origin: Origin::HardExpansion,
+            is_quoted: false,
+            is_quoting: false,
+        })
+        .collect();
+    Ok(Phrase::Field(chars))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::assert_stdout;
+    use crate::tests::cat_builtin;
+    use crate::tests::echo_builtin;
+    use crate::tests::in_virtual_system;
+    use futures_util::FutureExt;
+    use yash_env::job::Pid;
+    use yash_env::system::Errno;
+
+    fn path_phrase(fd: Fd) -> Phrase {
+        let chars = format!("/dev/fd/{fd}")
+            .chars()
+            .map(|value| AttrChar {
+                value,
+                origin: Origin::HardExpansion,
+                is_quoted: false,
+                is_quoting: false,
+            })
+            .collect();
+        Phrase::Field(chars)
+    }
+
+    #[test]
+    fn input_substitution() {
+        in_virtual_system(|mut env, _state| async move {
+            env.builtins.insert("echo", echo_builtin());
+            let command = Rc::from("echo foo");
+            let location = Location::dummy("");
+            let mut env = Env::new(&mut env);
+            let result = expand(ProcessSubstKind::Input, command, location, &mut env).await;
+
+            assert_eq!(env.inner.process_subst_fds.len(), 1);
+            let fd = env.inner.process_subst_fds[0];
+            assert!(fd >= MIN_INTERNAL_FD, "{fd}");
+            assert_eq!(result, Ok(path_phrase(fd)));
+
+            let mut buffer = [0; 10];
+            let count = env.inner.system.read_async(fd, &mut buffer).await;
+            assert_eq!(count, Ok(4));
+            assert_eq!(&buffer[..4], b"foo\n");
+        })
+    }
+
+    #[test]
+    fn output_substitution() {
+        in_virtual_system(|mut env, state| async move {
+            env.builtins.insert("cat", cat_builtin());
+            let command = Rc::from("cat");
+            let location = Location::dummy("");
+            let mut env = Env::new(&mut env);
+            let result = expand(ProcessSubstKind::Output, command, location, &mut env).await;
+
+            assert_eq!(env.inner.process_subst_fds.len(), 1);
+            let fd = env.inner.process_subst_fds[0];
+            assert_eq!(result, Ok(path_phrase(fd)));
+
+            env.inner.system.write_all(fd, b"bar\n").await.unwrap();
+            env.inner.close_process_subst_fds(0);
+            env.inner
+                .wait_for_subshell_to_finish(Pid::from_raw(-1))
+                .await
+                .unwrap();
+            assert_stdout(&state, |stdout| assert_eq!(stdout, "bar\n"));
+        })
+    }
+
+    #[test]
+    fn error_in_process_substitution() {
+        let command = Rc::from("");
+        let location = Location::dummy("foo");
+        let mut env = yash_env::Env::new_virtual();
+        let mut env = Env::new(&mut env);
+        let result = expand(ProcessSubstKind::Input, command, location.clone(), &mut env)
+            .now_or_never()
+            .unwrap();
+        let cause = ErrorCause::ProcessSubstError(Errno::ENOSYS);
+        assert_eq!(result, Err(Error { cause, location }));
+        assert_eq!(env.inner.process_subst_fds, []);
+    }
+}
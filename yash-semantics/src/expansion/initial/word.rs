@@ -100,6 +100,12 @@ fn double_quote(phrase: &mut Phrase) {
 /// `Tilde(user)` expands to the `user`'s home directory.
 ///
 /// TODO: `~+`, `~-`, `~+n`, `~-n`
+///
+/// # Process substitution
+///
+/// `ProcessSubst` starts the command in a subshell connected by a pipe and
+/// expands to the pathname of the pipe's file descriptor in the shell. See
+/// [`process_subst::expand`](super::process_subst::expand).
 #[async_trait(?Send)]
 impl Expand for WordUnit {
     type Interim = ();
@@ -111,6 +117,7 @@ impl Expand for WordUnit {
             // TODO Can we call text.quick_expand here?
             DoubleQuote(_text) => Interim(()),
             Tilde(name) => Ready(Ok(super::tilde::expand(name, env.inner).into())),
+            ProcessSubst { .. } => Interim(()),
         }
     }
 
@@ -128,6 +135,15 @@ impl Expand for WordUnit {
                 Ok(phrase)
             }
             Tilde(_name) => unimplemented!("async_expand not expecting Tilde"),
+            ProcessSubst {
+                kind,
+                content,
+                location,
+            } => {
+                let command = content.clone();
+                let location = location.clone();
+                super::process_subst::expand(*kind, command, location, env).await
+            }
         }
     }
 }
@@ -0,0 +1,274 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Evaluation of primaries in conditional expressions
+//!
+//! This module implements the primaries shared by the test built-in and the
+//! double-bracket command. File primaries
+//! examine files through [`System::fstatat`], [`System::faccessat`],
+//! [`System::is_executable_file`] and [`System::isatty`], so they can be tested
+//! on a virtual system.
+//!
+//! String comparisons other than `<` and `>` are not included because the test
+//! built-in and the double-bracket command interpret them differently.
+
+use std::borrow::Cow;
+use std::ffi::CString;
+use thiserror::Error;
+use yash_env::io::Fd;
+use yash_env::semantics::Field;
+use yash_env::system::AccessFlags;
+use yash_env::system::AtFlags;
+use yash_env::system::FileStat;
+use yash_env::system::SFlag;
+use yash_env::system::AT_FDCWD;
+use yash_env::Env;
+use yash_env::System;
+use yash_syntax::source::pretty::Annotation;
+use yash_syntax::source::pretty::AnnotationType;
+use yash_syntax::source::pretty::MessageBase;
+use yash_syntax::syntax::CondUnaryOperator;
+
+/// Error in evaluating a primary
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// An operand is not a valid integer.
+    #[error("invalid integer")]
+    InvalidInteger(Field),
+}
+
+impl MessageBase for Error {
+    fn message_title(&self) -> Cow<'_, str> {
+        self.to_string().into()
+    }
+
+    fn main_annotation(&self) -> Annotation<'_> {
+        match self {
+            Error::InvalidInteger(operand) => Annotation::new(
+                AnnotationType::Error,
+                format!("{}: not a valid integer", operand.value).into(),
+                &operand.origin,
+            ),
+        }
+    }
+}
+
+/// Result of evaluation
+pub type Result = std::result::Result<bool, Error>;
+
+/// Parses the operand as a decimal integer.
+///
+/// Leading and trailing whitespaces are ignored.
+pub fn parse_integer(operand: &Field) -> std::result::Result<i64, Error> {
+    operand
+        .value
+        .trim()
+        .parse()
+        .map_err(|_| Error::InvalidInteger(operand.clone()))
+}
+
+fn stat(env: &Env, path: &str, flags: AtFlags) -> Option<FileStat> {
+    let path = CString::new(path).ok()?;
+    env.system.fstatat(AT_FDCWD, &path, flags).ok()
+}
+
+fn file_type(stat: &FileStat) -> SFlag {
+    SFlag::from_bits_truncate(stat.st_mode) & SFlag::S_IFMT
+}
+
+fn mode_bits(stat: &FileStat) -> u32 {
+    #[allow(clippy::unnecessary_cast)]
+    let mode = stat.st_mode as u32;
+    mode & 0o7777
+}
+
+fn modification_time(stat: &FileStat) -> (i64, i64) {
+    #[allow(clippy::unnecessary_cast)]
+    let time = (stat.st_mtime as i64, stat.st_mtime_nsec as i64);
+    time
+}
+
+fn is_executable(env: &Env, path: &str) -> bool {
+    let Some(stat) = stat(env, path, AtFlags::empty()) else {
+        return false;
+    };
+    if file_type(&stat) == SFlag::S_IFDIR {
+        mode_bits(&stat) & 0o111 != 0
+    } else {
+        CString::new(path).map_or(false, |path| env.system.is_executable_file(&path))
+    }
+}
+
+fn is_accessible(env: &Env, path: &str, mode: AccessFlags) -> bool {
+    CString::new(path).map_or(false, |path| {
+        env.system.faccessat(AT_FDCWD, &path, mode).is_ok()
+    })
+}
+
+/// Evaluates a unary primary.
+pub fn evaluate_unary(env: &Env, operator: CondUnaryOperator, operand: &Field) -> Result {
+    use CondUnaryOperator::*;
+    let path = operand.value.as_str();
+    let has_type =
+        |r#type| stat(env, path, AtFlags::empty()).map_or(false, |s| file_type(&s) == r#type);
+    let has_bits =
+        |bits| stat(env, path, AtFlags::empty()).map_or(false, |s| mode_bits(&s) & bits != 0);
+    Ok(match operator {
+        BlockSpecial => has_type(SFlag::S_IFBLK),
+        CharacterSpecial => has_type(SFlag::S_IFCHR),
+        Directory => has_type(SFlag::S_IFDIR),
+        Exists => stat(env, path, AtFlags::empty()).is_some(),
+        Regular => has_type(SFlag::S_IFREG),
+        SetGroupId => has_bits(0o2000),
+        SymbolicLink => stat(env, path, AtFlags::AT_SYMLINK_NOFOLLOW)
+            .map_or(false, |s| file_type(&s) == SFlag::S_IFLNK),
+        Sticky => has_bits(0o1000),
+        NonEmptyString => !operand.value.is_empty(),
+        Fifo => has_type(SFlag::S_IFIFO),
+        Readable => is_accessible(env, path, AccessFlags::R_OK),
+        Socket => has_type(SFlag::S_IFSOCK),
+        NonEmptyFile => stat(env, path, AtFlags::empty()).map_or(false, |s| s.st_size > 0),
+        Terminal => {
+            let fd = parse_integer(operand)?
+                .try_into()
+                .map_err(|_| Error::InvalidInteger(operand.clone()))?;
+            env.system.isatty(Fd(fd)).unwrap_or(false)
+        }
+        SetUserId => has_bits(0o4000),
+        Writable => is_accessible(env, path, AccessFlags::W_OK),
+        Executable => is_executable(env, path),
+        EmptyString => operand.value.is_empty(),
+    })
+}
+
+/// Parses both operands as integers and compares them with the predicate.
+pub fn compare_integers(left: &Field, right: &Field, predicate: fn(i64, i64) -> bool) -> Result {
+    Ok(predicate(parse_integer(left)?, parse_integer(right)?))
+}
+
+/// Tests if the two paths name the same file (`-ef`).
+pub fn is_same_file(env: &Env, left: &str, right: &str) -> bool {
+    match (
+        stat(env, left, AtFlags::empty()),
+        stat(env, right, AtFlags::empty()),
+    ) {
+        (Some(l), Some(r)) => l.st_dev == r.st_dev && l.st_ino == r.st_ino,
+        _ => false,
+    }
+}
+
+/// Tests if the left file is newer than the right (`-nt`).
+///
+/// An existing file is newer than a non-existing file.
+pub fn is_newer_than(env: &Env, left: &str, right: &str) -> bool {
+    match (
+        stat(env, left, AtFlags::empty()),
+        stat(env, right, AtFlags::empty()),
+    ) {
+        (Some(l), Some(r)) => modification_time(&l) > modification_time(&r),
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+/// Tests if the left file is older than the right (`-ot`).
+///
+/// A non-existing file is older than an existing file.
+pub fn is_older_than(env: &Env, left: &str, right: &str) -> bool {
+    is_newer_than(env, right, left)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use yash_env::system::r#virtual::FileBody;
+    use yash_env::system::r#virtual::INode;
+    use yash_env::system::r#virtual::Mode;
+    use yash_env::VirtualSystem;
+
+    fn env_with_files() -> Env {
+        let system = VirtualSystem::new();
+        let mut state = system.state.borrow_mut();
+        let mut save = |path: &str, body, permissions| {
+            let inode = INode { body, permissions };
+            state
+                .file_system
+                .save(path, Rc::new(RefCell::new(inode)))
+                .unwrap();
+        };
+        save("/file", FileBody::new([1, 2, 3]), Mode(0o644));
+        save("/others", FileBody::new([]), Mode(0o066));
+        let directory = FileBody::Directory {
+            files: Default::default(),
+        };
+        save("/dir", directory, Mode(0o755));
+        let link = FileBody::Symlink {
+            target: "file".into(),
+        };
+        save("/link", link, Mode(0o777));
+        drop(state);
+        Env::with_system(Box::new(system))
+    }
+
+    fn unary(env: &Env, operator: &str, operand: &str) -> Result {
+        let operator = CondUnaryOperator::parse(operator).unwrap();
+        evaluate_unary(env, operator, &Field::dummy(operand))
+    }
+
+    #[test]
+    fn unary_primaries() {
+        let env = env_with_files();
+        assert_eq!(unary(&env, "-f", "/link"), Ok(true));
+        assert_eq!(unary(&env, "-h", "/link"), Ok(true));
+        assert_eq!(unary(&env, "-d", "/dir"), Ok(true));
+        assert_eq!(unary(&env, "-s", "/file"), Ok(true));
+        assert_eq!(unary(&env, "-x", "/dir"), Ok(true));
+        assert_eq!(unary(&env, "-x", "/file"), Ok(false));
+        assert_eq!(unary(&env, "-r", "/others"), Ok(false));
+        assert_eq!(unary(&env, "-e", "/none"), Ok(false));
+        assert_eq!(unary(&env, "-n", ""), Ok(false));
+        assert_eq!(
+            unary(&env, "-t", "x"),
+            Err(Error::InvalidInteger(Field::dummy("x")))
+        );
+    }
+
+    #[test]
+    fn integer_comparison() {
+        let (one, two) = (Field::dummy(" 1"), Field::dummy("2 "));
+        assert_eq!(compare_integers(&one, &two, |l, r| l < r), Ok(true));
+        assert_eq!(compare_integers(&two, &one, |l, r| l < r), Ok(false));
+        let x = Field::dummy("x");
+        assert_eq!(
+            compare_integers(&one, &x, |l, r| l < r),
+            Err(Error::InvalidInteger(x))
+        );
+    }
+
+    #[test]
+    fn file_comparison() {
+        let env = env_with_files();
+        assert!(is_same_file(&env, "/file", "/link"));
+        assert!(!is_same_file(&env, "/file", "/none"));
+        assert!(is_newer_than(&env, "/file", "/none"));
+        assert!(!is_newer_than(&env, "/none", "/file"));
+        assert!(is_older_than(&env, "/none", "/file"));
+        assert!(!is_older_than(&env, "/file", "/none"));
+    }
+}
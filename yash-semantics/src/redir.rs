@@ -41,13 +41,18 @@
 //! - `Pipe`: Opens a pipe, regarding the expanded field as a
 //!   non-negative decimal integer denoting a file descriptor to become the
 //!   reading end of the pipe. The target file descriptor will be the writing
-//!   end.
+//!   end. Both file descriptors are restored when the redirection is undone.
 //! - `String`: Opens a readable file descriptor from which you can read the
-//!   expanded field followed by a newline character.
+//!   expanded field followed by a newline character. Like a here-document,
+//!   the content is stored in an unnamed temporary file.
 //!
 //! If the `Clobber` [shell option](yash_env::option::Option) is off and a
 //! regular file exists at the target pathname, then `FileOut` will fail.
 //!
+//! `Pipe` and `String` are not defined in POSIX. They fail with an
+//! [`UnsupportedOperator`](ErrorCause::UnsupportedOperator) error if the
+//! `PosixlyCorrect` shell option is on.
+//!
 //! If the body is `HereDoc`, the redirection opens a readable file descriptor
 //! that yields [expansion](crate::expansion) of the content. The current
 //! implementation uses an unnamed temporary file for the file descriptor, but
@@ -89,7 +94,9 @@ use thiserror::Error;
 use yash_env::io::Fd;
 use yash_env::io::MIN_INTERNAL_FD;
 use yash_env::option::Option::Clobber;
+use yash_env::option::Option::PosixlyCorrect;
 use yash_env::option::State::Off;
+use yash_env::option::State::On;
 use yash_env::semantics::ExitStatus;
 use yash_env::semantics::Field;
 use yash_env::system::Errno;
@@ -97,6 +104,7 @@ use yash_env::system::FdFlag;
 use yash_env::system::Mode;
 use yash_env::system::OFlag;
 use yash_env::system::SFlag;
+use yash_env::system::SystemEx;
 use yash_env::Env;
 use yash_env::System;
 use yash_quote::quoted;
@@ -164,6 +172,18 @@ pub enum ErrorCause {
     /// Error preparing a temporary file to save here-document content
     #[error("cannot prepare temporary file for here-document: {0}")]
     TemporaryFileUnavailable(Errno),
+
+    /// Error opening a pipe for `>>|`
+    #[error("cannot open a pipe: {0}")]
+    PipeUnavailable(Errno),
+
+    /// `>>|` whose operand is the same as the target file descriptor
+    #[error("file descriptor {0} cannot be both ends of a pipe")]
+    SamePipeFd(Fd),
+
+    /// Use of a non-POSIX redirection operator in the POSIXly-correct mode
+    #[error("the {0} redirection is not supported in the POSIXly-correct mode")]
+    UnsupportedOperator(RedirOp),
 }
 
 impl ErrorCause {
@@ -180,6 +200,8 @@ impl ErrorCause {
             MalformedFd(_, _) => "not a valid file descriptor",
             UnreadableFd(_) | UnwritableFd(_) => "cannot copy file descriptor",
             TemporaryFileUnavailable(_) => "cannot prepare here-document",
+            PipeUnavailable(_) | SamePipeFd(_) => "cannot open a pipe",
+            UnsupportedOperator(_) => "non-POSIX redirection",
         }
     }
 
@@ -198,6 +220,11 @@ impl ErrorCause {
             UnreadableFd(fd) => format!("{fd}: not a readable file descriptor").into(),
             UnwritableFd(fd) => format!("{fd}: not a writable file descriptor").into(),
             TemporaryFileUnavailable(errno) => errno.desc().into(),
+            PipeUnavailable(errno) => errno.desc().into(),
+            SamePipeFd(fd) => format!("{fd}: same as the target file descriptor").into(),
+            UnsupportedOperator(operator) => {
+                format!("{operator}: not supported in the POSIXly-correct mode").into()
+            }
         }
     }
 }
@@ -401,9 +428,121 @@ fn copy_fd(
     Ok((FdSpec::Borrowed(fd), target.origin))
 }
 
+/// Saves the open file description at the file descriptor to a new internal
+/// file descriptor.
+///
+/// Returns `None` if the file descriptor is not open.
+fn save_fd(env: &mut Env, fd: Fd, location: &Location) -> Result<Option<Fd>, Error> {
+    match env.system.dup(fd, MIN_INTERNAL_FD, FdFlag::FD_CLOEXEC) {
+        Ok(save_fd) => Ok(Some(save_fd)),
+        Err(Errno::EBADF) => Ok(None),
+        Err(errno) => Err(Error {
+            cause: ErrorCause::FdNotOverwritten(fd, errno),
+            location: location.clone(),
+        }),
+    }
+}
+
+/// Opens a pipe for `>>|`.
+///
+/// The reading end of the pipe is moved to the file descriptor specified by
+/// the operand, whose original state is saved in `saved_fds`. The returned
+/// `FdSpec` is the writing end.
+fn open_pipe(
+    env: &mut Env,
+    saved_fds: &mut Vec<SavedFd>,
+    target_fd: Fd,
+    operand: Field,
+) -> Result<(FdSpec, Location), Error> {
+    let reader_fd = match operand.value.parse() {
+        Ok(number) => Fd(number),
+        Err(error) => {
+            return Err(Error {
+                cause: ErrorCause::MalformedFd(operand.value, error),
+                location: operand.origin,
+            })
+        }
+    };
+    let location = operand.origin;
+    if reader_fd == target_fd {
+        return Err(Error {
+            cause: ErrorCause::SamePipeFd(reader_fd),
+            location,
+        });
+    }
+    if is_cloexec(env, reader_fd) {
+        return Err(Error {
+            cause: ErrorCause::ReservedFd(reader_fd),
+            location,
+        });
+    }
+
+    let save = save_fd(env, reader_fd, &location)?;
+    let close_save = |env: &mut Env| {
+        if let Some(save) = save {
+            let _: Result<_, _> = env.system.close(save);
+        }
+    };
+    // Move both ends of the pipe out of the way so that neither of them is
+    // overwritten by the other when the reading end is moved to `reader_fd`.
+    let fds = env.system.pipe().and_then(|(reader, writer)| {
+        let reader = env.system.move_fd_internal(reader);
+        let writer = env.system.move_fd_internal(writer);
+        match (reader, writer) {
+            (Ok(reader), Ok(writer)) => Ok((reader, writer)),
+            (Ok(fd), Err(errno)) | (Err(errno), Ok(fd)) => {
+                let _: Result<_, _> = env.system.close(fd);
+                Err(errno)
+            }
+            (Err(errno), Err(_)) => Err(errno),
+        }
+    });
+    let (reader, writer) = match fds {
+        Ok(fds) => fds,
+        Err(errno) => {
+            close_save(env);
+            return Err(Error {
+                cause: ErrorCause::PipeUnavailable(errno),
+                location,
+            });
+        }
+    };
+    if reader != reader_fd {
+        let dup_result = env.system.dup2(reader, reader_fd);
+        let _: Result<_, _> = env.system.close(reader);
+        if let Err(errno) = dup_result {
+            let _: Result<_, _> = env.system.close(writer);
+            close_save(env);
+            return Err(Error {
+                cause: ErrorCause::FdNotOverwritten(reader_fd, errno),
+                location,
+            });
+        }
+    }
+
+    let original = reader_fd;
+    saved_fds.push(SavedFd { original, save });
+    Ok((FdSpec::Owned(writer), location))
+}
+
+/// Opens a file descriptor for `<<<`.
+async fn open_here_string(env: &mut Env, operand: Field) -> Result<(FdSpec, Location), Error> {
+    let mut content = operand.value;
+    content.push('\n');
+    match here_doc::open_fd(env, content).await {
+        Ok(fd) => Ok((FdSpec::Owned(fd), operand.origin)),
+        Err(cause) => Err(Error {
+            cause,
+            location: operand.origin,
+        }),
+    }
+}
+
 /// Opens the file for a normal redirection.
 async fn open_normal(
     env: &mut Env,
+    saved_fds: &mut Vec<SavedFd>,
+    target_fd: Fd,
     operator: RedirOp,
     operand: Field,
 ) -> Result<(FdSpec, Location), Error> {
@@ -424,8 +563,8 @@ async fn open_normal(
         FileInOut => open_file(env, OFlag::O_RDWR | OFlag::O_CREAT, operand),
         FdIn => copy_fd(env, operand, OFlag::O_RDONLY),
         FdOut => copy_fd(env, operand, OFlag::O_WRONLY),
-        Pipe => todo!("pipe redirection: {:?}", operand.value),
-        String => todo!("here-string: {:?}", operand.value),
+        Pipe => open_pipe(env, saved_fds, target_fd, operand),
+        String => open_here_string(env, operand).await,
     }
 }
 
@@ -455,14 +594,30 @@ fn trace_here_doc(xtrace: Option<&mut XTrace>, target_fd: Fd, here_doc: &HereDoc
 mod here_doc;
 
 /// Performs a redirection.
+///
+/// The original states of the file descriptors affected by the redirection
+/// are pushed to `saved_fds`.
 #[allow(clippy::await_holding_refcell_ref)]
 async fn perform(
     env: &mut Env,
+    saved_fds: &mut Vec<SavedFd>,
     redir: &Redir,
     xtrace: Option<&mut XTrace>,
-) -> Result<(SavedFd, Option<ExitStatus>), Error> {
+) -> Result<Option<ExitStatus>, Error> {
     let target_fd = redir.fd_or_default();
 
+    // Reject non-POSIX operators in the POSIXly-correct mode
+    if let RedirBody::Normal { operator, operand } = &redir.body {
+        if matches!(operator, RedirOp::Pipe | RedirOp::String)
+            && env.options.get(PosixlyCorrect) == On
+        {
+            return Err(Error {
+                cause: ErrorCause::UnsupportedOperator(*operator),
+                location: operand.location.clone(),
+            });
+        }
+    }
+
     // Make sure target_fd doesn't have the CLOEXEC flag
     if is_cloexec(env, target_fd) {
         return Err(Error {
@@ -472,19 +627,7 @@ async fn perform(
     }
 
     // Save the current open file description at target_fd to a new FD
-    let save = match env
-        .system
-        .dup(target_fd, MIN_INTERNAL_FD, FdFlag::FD_CLOEXEC)
-    {
-        Ok(save_fd) => Some(save_fd),
-        Err(Errno::EBADF) => None,
-        Err(errno) => {
-            return Err(Error {
-                cause: ErrorCause::FdNotOverwritten(target_fd, errno),
-                location: redir.body.operand().location.clone(),
-            })
-        }
-    };
+    let save = save_fd(env, target_fd, &redir.body.operand().location)?;
 
     // Prepare an FD from the redirection body
     let (fd_spec, location, exit_status) = match &redir.body {
@@ -492,7 +635,8 @@ async fn perform(
             // TODO perform pathname expansion if applicable
             let (expansion, exit_status) = expand_word(env, operand).await?;
             trace_normal(xtrace, target_fd, *operator, &expansion);
-            let (fd, location) = open_normal(env, *operator, expansion).await?;
+            let (fd, location) =
+                open_normal(env, saved_fds, target_fd, *operator, expansion).await?;
             (fd, location, exit_status)
         }
         RedirBody::HereDoc(here_doc) => {
@@ -525,7 +669,8 @@ async fn perform(
     }
 
     let original = target_fd;
-    Ok((SavedFd { original, save }, exit_status))
+    saved_fds.push(SavedFd { original, save });
+    Ok(exit_status)
 }
 
 /// `Env` wrapper for performing redirections.
@@ -582,7 +727,7 @@ impl<'e> RedirGuard<'e> {
     /// Performs a redirection.
     ///
     /// If successful, this function saves internally a backing copy of the file
-    /// descriptor affected by the redirection, and returns the exit status of
+    /// descriptors affected by the redirection, and returns the exit status of
     /// the last command substitution performed during the redirection, if any.
     ///
     /// If `xtrace` is `Some` instance of `XTrace`, the redirection operators
@@ -592,9 +737,7 @@ impl<'e> RedirGuard<'e> {
         redir: &Redir,
         xtrace: Option<&mut XTrace>,
     ) -> Result<Option<ExitStatus>, Error> {
-        let (saved_fd, exit_status) = perform(self, redir, xtrace).await?;
-        self.saved_fds.push(saved_fd);
-        Ok(exit_status)
+        perform(self.env, &mut self.saved_fds, redir, xtrace).await
     }
 
     /// Performs redirections.
@@ -1534,4 +1677,132 @@ mod tests {
         let write_count = env.system.write(Fd(1), &[0x20]).unwrap();
         assert_eq!(write_count, 1);
     }
+
+    #[test]
+    fn here_string_reads_operand_with_newline() {
+        let mut env = Env::new_virtual();
+        let mut env = RedirGuard::new(&mut env);
+        let redir = "3<<< 'foo bar'".parse().unwrap();
+        let result = env
+            .perform_redir(&redir, None)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_eq!(result, None);
+
+        let mut buffer = [0; 10];
+        let read_count = env.system.read(Fd(3), &mut buffer).unwrap();
+        assert_eq!(&buffer[..read_count], b"foo bar\n");
+    }
+
+    #[test]
+    fn xtrace_here_string() {
+        let mut xtrace = XTrace::new();
+        let mut env = Env::new_virtual();
+        let mut env = RedirGuard::new(&mut env);
+        env.perform_redir(&"<<< 'a b'".parse().unwrap(), Some(&mut xtrace))
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let result = xtrace.finish(&mut env).now_or_never().unwrap();
+        assert_eq!(result, "0<<<'a b'\n");
+    }
+
+    #[test]
+    fn pipe_connects_target_fd_to_operand_fd() {
+        let mut env = Env::new_virtual();
+        let mut env = RedirGuard::new(&mut env);
+        let redir = "4>>| 3".parse().unwrap();
+        env.perform_redir(&redir, None)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let write_count = env.system.write(Fd(4), &[1, 2, 3]).unwrap();
+        assert_eq!(write_count, 3);
+        let mut buffer = [0; 4];
+        let read_count = env.system.read(Fd(3), &mut buffer).unwrap();
+        assert_eq!(buffer[..read_count], [1, 2, 3]);
+    }
+
+    #[test]
+    fn pipe_with_operand_fd_colliding_with_writing_end() {
+        // The pipe would be opened at file descriptors 3 and 4 if the ends were
+        // not moved, so the writing end would be overwritten by the reading end.
+        let mut env = Env::new_virtual();
+        let mut env = RedirGuard::new(&mut env);
+        let redir = "5>>| 4".parse().unwrap();
+        env.perform_redir(&redir, None)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let write_count = env.system.write(Fd(5), &[1, 2, 3]).unwrap();
+        assert_eq!(write_count, 3);
+        let mut buffer = [0; 4];
+        let read_count = env.system.read(Fd(4), &mut buffer).unwrap();
+        assert_eq!(buffer[..read_count], [1, 2, 3]);
+        assert_eq!(env.system.fcntl_getfl(Fd(3)), Err(Errno::EBADF));
+    }
+
+    #[test]
+    fn undoing_pipe_restores_both_fds() {
+        let mut env = Env::new_virtual();
+        let mut redir_env = RedirGuard::new(&mut env);
+        let redir = "4>>| 3".parse().unwrap();
+        redir_env
+            .perform_redir(&redir, None)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        redir_env.undo_redirs();
+        drop(redir_env);
+
+        assert_eq!(env.system.fcntl_getfl(Fd(3)), Err(Errno::EBADF));
+        assert_eq!(env.system.fcntl_getfl(Fd(4)), Err(Errno::EBADF));
+    }
+
+    #[test]
+    fn pipe_rejects_same_fd() {
+        let mut env = Env::new_virtual();
+        let mut env = RedirGuard::new(&mut env);
+        let redir = "3>>| 3".parse().unwrap();
+        let e = env
+            .perform_redir(&redir, None)
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(e.cause, ErrorCause::SamePipeFd(Fd(3)));
+        assert_eq!(e.location, redir.body.operand().location);
+    }
+
+    #[test]
+    fn pipe_rejects_malformed_fd() {
+        let mut env = Env::new_virtual();
+        let mut env = RedirGuard::new(&mut env);
+        let redir = ">>| x".parse().unwrap();
+        let e = env
+            .perform_redir(&redir, None)
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+        assert_matches!(e.cause, ErrorCause::MalformedFd(value, _) if value == "x");
+    }
+
+    #[test]
+    fn non_posix_operators_rejected_in_posixly_correct_mode() {
+        let mut env = Env::new_virtual();
+        env.options.set(PosixlyCorrect, On);
+        let mut env = RedirGuard::new(&mut env);
+        for (source, operator) in [("<<< foo", RedirOp::String), (">>| 3", RedirOp::Pipe)] {
+            let redir: Redir = source.parse().unwrap();
+            let e = env
+                .perform_redir(&redir, None)
+                .now_or_never()
+                .unwrap()
+                .unwrap_err();
+            assert_eq!(e.cause, ErrorCause::UnsupportedOperator(operator));
+            assert_eq!(e.location, redir.body.operand().location);
+        }
+    }
 }
@@ -78,6 +78,7 @@ mod and_or;
 mod case;
 mod command;
 mod compound_command;
+mod double_bracket;
 mod for_loop;
 mod function;
 mod grouping;
@@ -23,7 +23,7 @@ use super::core::Parser;
 use super::core::Result;
 use super::error::Error;
 use super::error::SyntaxError;
-use super::lex::Keyword::{Case, Do, Done, For, If, OpenBrace, Until, While};
+use super::lex::Keyword::{Case, Do, Done, For, If, OpenBrace, OpenBracketBracket, Until, While};
 use super::lex::Operator::OpenParen;
 use super::lex::TokenId::{Operator, Token};
 use crate::syntax::CompoundCommand;
@@ -71,6 +71,7 @@ impl Parser<'_, '_> {
             Token(Some(Until)) => self.until_loop().await.map(Some),
             Token(Some(If)) => self.if_command().await.map(Some),
             Token(Some(Case)) => self.case_command().await.map(Some),
+            Token(Some(OpenBracketBracket)) => self.double_bracket().await.map(Some),
             _ => Ok(None),
         }
     }
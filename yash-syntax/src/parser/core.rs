@@ -176,6 +176,20 @@ impl<'a, 'b> Parser<'a, 'b> {
         self.token.take().unwrap()
     }
 
+    /// Consumes the current token as the right-hand side of the `=~` operator.
+    ///
+    /// If the current token has already been peeked, it is discarded and lexed
+    /// again by [`Lexer::regex_token`] so that the word may contain
+    /// parentheses and `|`. This function does not perform alias substitution.
+    pub async fn take_regex_token(&mut self) -> Result<Token> {
+        match self.token.take() {
+            Some(Ok(token)) => self.lexer.rewind(token.index),
+            Some(Err(error)) => return Err(error),
+            None => self.lexer.skip_blanks_and_comment().await?,
+        }
+        self.lexer.regex_token().await
+    }
+
     /// Performs alias substitution on a token that has just been
     /// [taken](Self::take_token_raw).
     fn substitute_alias(&mut self, token: Token, is_command_name: bool) -> Rec<Token> {
@@ -0,0 +1,420 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Syntax parser for the double-bracket command
+//!
+//! The conditional expression in a double-bracket command is parsed according
+//! to the following grammar, where `&&` has higher precedence than `||`:
+//!
+//! ```text
+//! or_expr  := and_expr ("||" and_expr)*
+//! and_expr := not_expr ("&&" not_expr)*
+//! not_expr := "!" not_expr | primary
+//! primary  := "(" or_expr ")"
+//!           | unary_operator word
+//!           | word binary_operator word
+//!           | word
+//! ```
+//!
+//! Newlines may appear between tokens. Aliases are not substituted in the
+//! expression.
+//!
+//! The right-hand side of the `=~` operator is lexed by
+//! [`Lexer::regex_token`](super::lex::Lexer::regex_token), so it may contain
+//! parentheses and `|`, as in `[[ $x =~ (a|b)c ]]`.
+
+use super::core::Parser;
+use super::core::Result;
+use super::error::Error;
+use super::error::SyntaxError;
+use super::lex::Keyword::{Bang, OpenBracketBracket};
+use super::lex::Operator::{AndAnd, BarBar, CloseParen, Greater, Less, OpenParen};
+use super::lex::Token;
+use super::lex::TokenId::{Operator, Token as WordToken};
+use crate::syntax::CompoundCommand;
+use crate::syntax::CondBinaryOperator;
+use crate::syntax::CondExpr;
+use crate::syntax::CondUnaryOperator;
+use crate::syntax::MaybeLiteral;
+use crate::syntax::Word;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Tests whether the token is the closing `]]`.
+fn is_closing_brackets(token: &Token) -> bool {
+    token.id == WordToken(None) && token.word.to_string_if_literal().as_deref() == Some("]]")
+}
+
+/// Tests whether the token can be an operand of a conditional expression.
+fn is_operand(token: &Token) -> bool {
+    matches!(token.id, WordToken(_)) && !is_closing_brackets(token)
+}
+
+/// Returns the word of the token if it can be an operand.
+fn operand_word(token: Token) -> Result<Word> {
+    if is_operand(&token) {
+        Ok(token.word)
+    } else {
+        let cause = SyntaxError::MissingConditionalOperand.into();
+        let location = token.word.location;
+        Err(Error { cause, location })
+    }
+}
+
+impl Parser<'_, '_> {
+    /// Parses a double-bracket command.
+    ///
+    /// The next token must be the `[[` reserved word.
+    ///
+    /// # Panics
+    ///
+    /// If the first token is not `[[`.
+    pub async fn double_bracket(&mut self) -> Result<CompoundCommand> {
+        let open = self.take_token_raw().await?;
+        assert_eq!(open.id, WordToken(Some(OpenBracketBracket)));
+
+        let expression = self.cond_or_expr().await?;
+
+        let close = self.cond_take_token().await?;
+        if !is_closing_brackets(&close) {
+            let opening_location = open.word.location;
+            let cause = SyntaxError::UnclosedDoubleBracket { opening_location }.into();
+            let location = close.word.location;
+            return Err(Error { cause, location });
+        }
+
+        let location = open.word.location;
+        Ok(CompoundCommand::DoubleBracket {
+            expression,
+            location,
+        })
+    }
+
+    /// Returns the current token, skipping newlines.
+    async fn cond_peek_token(&mut self) -> Result<&Token> {
+        while self.newline_and_here_doc_contents().await? {}
+        self.peek_token().await
+    }
+
+    /// Consumes the current token, skipping newlines.
+    async fn cond_take_token(&mut self) -> Result<Token> {
+        while self.newline_and_here_doc_contents().await? {}
+        self.take_token_raw().await
+    }
+
+    /// Like [`cond_or_expr`](Self::cond_or_expr), but returns the future in a
+    /// pinned box.
+    fn cond_or_expr_boxed(&mut self) -> Pin<Box<dyn Future<Output = Result<CondExpr>> + '_>> {
+        Box::pin(self.cond_or_expr())
+    }
+
+    /// Parses a disjunction of conjunctions.
+    async fn cond_or_expr(&mut self) -> Result<CondExpr> {
+        let mut expr = self.cond_and_expr().await?;
+        while self.cond_peek_token().await?.id == Operator(BarBar) {
+            self.take_token_raw().await?;
+            let right = self.cond_and_expr().await?;
+            expr = CondExpr::Or(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    /// Parses a conjunction of possibly negated primaries.
+    async fn cond_and_expr(&mut self) -> Result<CondExpr> {
+        let mut expr = self.cond_not_expr().await?;
+        while self.cond_peek_token().await?.id == Operator(AndAnd) {
+            self.take_token_raw().await?;
+            let right = self.cond_not_expr().await?;
+            expr = CondExpr::And(Box::new(expr), Box::new(right));
+        }
+        Ok(expr)
+    }
+
+    /// Parses a primary preceded by any number of `!`s.
+    async fn cond_not_expr(&mut self) -> Result<CondExpr> {
+        let mut negation_count = 0;
+        while self.cond_peek_token().await?.id == WordToken(Some(Bang)) {
+            self.take_token_raw().await?;
+            negation_count += 1;
+        }
+
+        let mut expr = self.cond_primary().await?;
+        for _ in 0..negation_count {
+            expr = CondExpr::Not(Box::new(expr));
+        }
+        Ok(expr)
+    }
+
+    /// Parses a primary.
+    async fn cond_primary(&mut self) -> Result<CondExpr> {
+        let token = self.cond_take_token().await?;
+
+        if token.id == Operator(OpenParen) {
+            let expr = self.cond_or_expr_boxed().await?;
+            let close = self.cond_take_token().await?;
+            if close.id != Operator(CloseParen) {
+                let opening_location = token.word.location;
+                let cause = SyntaxError::UnclosedParen { opening_location }.into();
+                let location = close.word.location;
+                return Err(Error { cause, location });
+            }
+            return Ok(CondExpr::Group(Box::new(expr)));
+        }
+
+        if !is_operand(&token) {
+            let cause = SyntaxError::MissingConditionalExpression.into();
+            let location = token.word.location;
+            return Err(Error { cause, location });
+        }
+
+        let literal = token.word.to_string_if_literal();
+        if let Some(operator) = literal.as_deref().and_then(CondUnaryOperator::parse) {
+            let operand = self.cond_operand().await?;
+            return Ok(CondExpr::Unary { operator, operand });
+        }
+
+        let next = self.cond_peek_token().await?;
+        let operator = match next.id {
+            Operator(Less) => Some(CondBinaryOperator::StringLess),
+            Operator(Greater) => Some(CondBinaryOperator::StringGreater),
+            WordToken(_) => next
+                .word
+                .to_string_if_literal()
+                .as_deref()
+                .and_then(CondBinaryOperator::parse),
+            _ => None,
+        };
+        if let Some(operator) = operator {
+            self.take_token_raw().await?;
+            let right = if operator == CondBinaryOperator::RegexMatch {
+                self.cond_regex_operand().await?
+            } else {
+                self.cond_operand().await?
+            };
+            return Ok(CondExpr::Binary {
+                left: token.word,
+                operator,
+                right,
+            });
+        }
+
+        Ok(CondExpr::Word(token.word))
+    }
+
+    /// Parses an operand of a unary or binary operator.
+    async fn cond_operand(&mut self) -> Result<Word> {
+        let token = self.cond_take_token().await?;
+        operand_word(token)
+    }
+
+    /// Parses the right-hand side operand of the `=~` operator.
+    async fn cond_regex_operand(&mut self) -> Result<Word> {
+        while self.newline_and_here_doc_contents().await? {}
+        let token = self.take_regex_token().await?;
+        operand_word(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::error::ErrorCause;
+    use super::super::lex::Lexer;
+    use super::super::lex::TokenId::EndOfInput;
+    use super::*;
+    use crate::source::Source;
+    use assert_matches::assert_matches;
+    use futures_util::FutureExt;
+
+    fn parse(source: &str) -> Result<CompoundCommand> {
+        let mut lexer = Lexer::from_memory(source, Source::Unknown);
+        let aliases = Default::default();
+        let mut parser = Parser::new(&mut lexer, &aliases);
+        let result = parser.compound_command().now_or_never().unwrap();
+        if result.is_ok() {
+            let next = parser.peek_token().now_or_never().unwrap().unwrap();
+            assert_eq!(next.id, EndOfInput);
+        }
+        result.map(Option::unwrap)
+    }
+
+    fn parse_expression(source: &str) -> CondExpr {
+        assert_matches!(parse(source), Ok(CompoundCommand::DoubleBracket { expression, location }) => {
+            assert_eq!(location.range, 0..2);
+            expression
+        })
+    }
+
+    #[test]
+    fn single_word() {
+        let expr = parse_expression("[[ foo ]]");
+        assert_matches!(expr, CondExpr::Word(word) => {
+            assert_eq!(word.to_string(), "foo");
+        });
+    }
+
+    #[test]
+    fn unary_primary() {
+        let expr = parse_expression("[[ -f $file ]]");
+        assert_matches!(expr, CondExpr::Unary { operator, operand } => {
+            assert_eq!(operator, CondUnaryOperator::Regular);
+            assert_eq!(operand.to_string(), "$file");
+        });
+    }
+
+    #[test]
+    fn binary_primaries() {
+        let expr = parse_expression("[[ $x == a* ]]");
+        assert_matches!(expr, CondExpr::Binary { left, operator, right } => {
+            assert_eq!(left.to_string(), "$x");
+            assert_eq!(operator, CondBinaryOperator::PatternMatch);
+            assert_eq!(right.to_string(), "a*");
+        });
+
+        let expr = parse_expression("[[ a < b ]]");
+        assert_matches!(expr, CondExpr::Binary { operator, .. } => {
+            assert_eq!(operator, CondBinaryOperator::StringLess);
+        });
+
+        let expr = parse_expression("[[ a =~ ^b ]]");
+        assert_matches!(expr, CondExpr::Binary { operator, .. } => {
+            assert_eq!(operator, CondBinaryOperator::RegexMatch);
+        });
+    }
+
+    #[test]
+    fn regex_operand_with_parentheses_and_bars() {
+        let expr = parse_expression("[[ ab =~ (a|c)b ]]");
+        assert_matches!(expr, CondExpr::Binary { operator, right, .. } => {
+            assert_eq!(operator, CondBinaryOperator::RegexMatch);
+            assert_eq!(right.to_string(), "(a|c)b");
+        });
+
+        let expr = parse_expression("[[ x =~ ^(a b|c)$ && ( y =~ |y ) ]]");
+        assert_matches!(expr, CondExpr::And(left, right) => {
+            assert_matches!(*left, CondExpr::Binary { right, .. } => {
+                assert_eq!(right.to_string(), "^(a b|c)$");
+            });
+            assert_matches!(*right, CondExpr::Group(inner) => {
+                assert_matches!(*inner, CondExpr::Binary { right, .. } => {
+                    assert_eq!(right.to_string(), "|y");
+                });
+            });
+        });
+
+        let expr = parse_expression("[[ a =~\n(a) ]]");
+        assert_matches!(expr, CondExpr::Binary { right, .. } => {
+            assert_eq!(right.to_string(), "(a)");
+        });
+    }
+
+    #[test]
+    fn missing_regex_operand() {
+        let e = parse("[[ a =~ ]]").unwrap_err();
+        assert_eq!(
+            e.cause,
+            ErrorCause::Syntax(SyntaxError::MissingConditionalOperand)
+        );
+        assert_eq!(e.location.range, 8..10);
+    }
+
+    #[test]
+    fn quoted_operator_is_operand() {
+        let expr = parse_expression("[[ '-f' ]]");
+        assert_matches!(expr, CondExpr::Word(_));
+    }
+
+    #[test]
+    fn precedence_and_grouping() {
+        let expr = parse_expression("[[ a || ! b && c ]]");
+        assert_eq!(expr.to_string(), "a || ! b && c");
+        assert_matches!(expr, CondExpr::Or(left, right) => {
+            assert_matches!(*left, CondExpr::Word(_));
+            assert_matches!(*right, CondExpr::And(left, _) => {
+                assert_matches!(*left, CondExpr::Not(_));
+            });
+        });
+
+        let expr = parse_expression("[[ ( a || b ) && c ]]");
+        assert_matches!(expr, CondExpr::And(left, _) => {
+            assert_matches!(*left, CondExpr::Group(_));
+        });
+    }
+
+    #[test]
+    fn newlines_between_tokens() {
+        let expr = parse_expression("[[\na &&\nb\n]]");
+        assert_eq!(expr.to_string(), "a && b");
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let source = "[[ ! -d dir && ( $a != \"$b\" || 1 -lt 2 ) ]]";
+        let command = parse(source).unwrap();
+        assert_eq!(command.to_string(), source);
+        assert_eq!(parse(&command.to_string()).unwrap(), command);
+    }
+
+    #[test]
+    fn unclosed_double_bracket() {
+        let e = parse("[[ a b ]]").unwrap_err();
+        assert_matches!(e.cause,
+            ErrorCause::Syntax(SyntaxError::UnclosedDoubleBracket { opening_location }) => {
+            assert_eq!(opening_location.range, 0..2);
+        });
+        assert_eq!(e.location.range, 5..6);
+    }
+
+    #[test]
+    fn missing_expression() {
+        let e = parse("[[ ]]").unwrap_err();
+        assert_eq!(
+            e.cause,
+            ErrorCause::Syntax(SyntaxError::MissingConditionalExpression)
+        );
+        assert_eq!(e.location.range, 3..5);
+
+        let e = parse("[[ a && ]]").unwrap_err();
+        assert_eq!(
+            e.cause,
+            ErrorCause::Syntax(SyntaxError::MissingConditionalExpression)
+        );
+    }
+
+    #[test]
+    fn missing_operand() {
+        let e = parse("[[ -f ]]").unwrap_err();
+        assert_eq!(
+            e.cause,
+            ErrorCause::Syntax(SyntaxError::MissingConditionalOperand)
+        );
+        assert_eq!(e.location.range, 6..8);
+
+        let e = parse("[[ a == ]]").unwrap_err();
+        assert_eq!(
+            e.cause,
+            ErrorCause::Syntax(SyntaxError::MissingConditionalOperand)
+        );
+    }
+
+    #[test]
+    fn unclosed_parenthesis() {
+        let e = parse("[[ ( a ]]").unwrap_err();
+        assert_matches!(e.cause,
+            ErrorCause::Syntax(SyntaxError::UnclosedParen { opening_location }) => {
+            assert_eq!(opening_location.range, 3..4);
+        });
+    }
+}
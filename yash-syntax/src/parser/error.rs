@@ -46,6 +46,8 @@ pub enum SyntaxError {
     EmptyParam,
     /// A command substitution started with `$(` but lacks a closing `)`.
     UnclosedCommandSubstitution { opening_location: Location },
+    /// A process substitution lacks a closing `)`.
+    UnclosedProcessSubstitution { opening_location: Location },
     /// A command substitution started with `` ` `` but lacks a closing `` ` ``.
     UnclosedBackquote { opening_location: Location },
     /// An arithmetic expansion lacks a closing `))`.
@@ -139,6 +141,12 @@ pub enum SyntaxError {
     UnopenedCase,
     /// A case command is not closed.
     UnclosedCase { opening_location: Location },
+    /// A double-bracket command is not closed.
+    UnclosedDoubleBracket { opening_location: Location },
+    /// A conditional expression is missing in a double-bracket command.
+    MissingConditionalExpression,
+    /// A conditional operator is missing its operand.
+    MissingConditionalOperand,
     /// The `(` is not followed by `)` in a function definition.
     UnmatchedParenthesis,
     /// The function body is missing in a function definition command.
@@ -173,6 +181,7 @@ impl SyntaxError {
             UnclosedParam { .. } => "The parameter expansion is not closed",
             EmptyParam => "The parameter name is missing",
             UnclosedCommandSubstitution { .. } => "The command substitution is not closed",
+            UnclosedProcessSubstitution { .. } => "The process substitution is not closed",
             UnclosedBackquote { .. } => "The backquote is not closed",
             UnclosedArith { .. } => "The arithmetic expansion is not closed",
             InvalidCommandToken => "The command starts with an inappropriate token",
@@ -217,6 +226,9 @@ impl SyntaxError {
             InvalidPattern => "The pattern is not a valid word token",
             EsacAsPattern => "`esac` cannot be the first of a pattern list",
             UnclosedCase { .. } => "The `case` command is missing its closing `esac`",
+            UnclosedDoubleBracket { .. } => "The `[[` command is missing its closing `]]`",
+            MissingConditionalExpression => "A conditional expression is missing",
+            MissingConditionalOperand => "The conditional operator is missing its operand",
             UnmatchedParenthesis => "`)` is missing after `(`",
             MissingFunctionBody => "The function body is missing",
             InvalidFunctionBody => "The function body must be a compound command",
@@ -236,6 +248,7 @@ impl SyntaxError {
         match self {
             UnclosedParen { .. }
             | UnclosedCommandSubstitution { .. }
+            | UnclosedProcessSubstitution { .. }
             | UnclosedArrayValue { .. }
             | UnclosedSubshell { .. }
             | UnclosedPatternList
@@ -287,6 +300,9 @@ impl SyntaxError {
             EsacAsPattern => "needs quoting",
             UnopenedCase => "not in a `case` command",
             UnclosedCase { .. } => "expected `esac`",
+            UnclosedDoubleBracket { .. } => "expected `]]`",
+            MissingConditionalExpression => "expected an expression",
+            MissingConditionalOperand => "expected an operand",
             MissingFunctionBody | InvalidFunctionBody => "expected a compound command",
             InAsCommandName => "cannot be used as a command name",
             DoubleNegation => "only one `!` allowed",
@@ -314,6 +330,9 @@ impl SyntaxError {
             UnclosedCommandSubstitution { opening_location } => {
                 Some((opening_location, "the command substitution started here"))
             }
+            UnclosedProcessSubstitution { opening_location } => {
+                Some((opening_location, "the process substitution started here"))
+            }
             UnclosedBackquote { opening_location } => {
                 Some((opening_location, "the opening backquote was here"))
             }
@@ -348,6 +367,9 @@ impl SyntaxError {
             MissingIn { opening_location } | UnclosedCase { opening_location } => {
                 Some((opening_location, "the `case` command started here"))
             }
+            UnclosedDoubleBracket { opening_location } => {
+                Some((opening_location, "the `[[` command started here"))
+            }
             _ => None,
         }
     }
@@ -31,6 +31,7 @@ mod keyword;
 mod misc;
 mod modifier;
 mod op;
+mod process_subst;
 mod raw_param;
 mod text;
 mod tilde;
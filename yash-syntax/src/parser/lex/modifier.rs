@@ -23,6 +23,8 @@ use crate::parser::core::Result;
 use crate::parser::error::Error;
 use crate::parser::error::SyntaxError;
 use crate::syntax::Modifier;
+use crate::syntax::Subst;
+use crate::syntax::SubstType;
 use crate::syntax::Switch;
 use crate::syntax::SwitchCondition;
 use crate::syntax::SwitchType;
@@ -127,10 +129,58 @@ impl WordLexer<'_, '_> {
         }))
     }
 
+    /// Parses a [substitution](Subst).
+    ///
+    /// This function blindly consumes the current character, which must be
+    /// `/`.
+    async fn subst(&mut self, start_index: usize, colon: bool) -> Result<Modifier> {
+        self.consume_char();
+        if colon {
+            return self.invalid_modifier(start_index);
+        }
+
+        let r#type = match self.peek_char().await? {
+            Some('/') => SubstType::All,
+            Some('#') => SubstType::Prefix,
+            Some('%') => SubstType::Suffix,
+            _ => SubstType::First,
+        };
+        if r#type != SubstType::First {
+            self.consume_char();
+        }
+
+        let mut lexer = WordLexer {
+            lexer: self.lexer,
+            context: WordContext::Word,
+        };
+        // Boxing needed for recursion
+        let pattern = Box::pin(lexer.word(|c| c == '/' || c == '}'))
+            as Pin<Box<dyn Future<Output = Result<Word>>>>;
+        let mut pattern = pattern.await?;
+        pattern.parse_tilde_front();
+
+        // If the replacement is omitted, this yields an empty word.
+        self.skip_if(|c| c == '/').await?;
+        let replacement =
+            Box::pin(self.word(|c| c == '}')) as Pin<Box<dyn Future<Output = Result<Word>>>>;
+        let mut replacement = replacement.await?;
+        match self.context {
+            WordContext::Text => (),
+            WordContext::Word => replacement.parse_tilde_front(),
+        }
+
+        Ok(Modifier::Subst(Subst {
+            r#type,
+            pattern,
+            replacement,
+        }))
+    }
+
     /// Parses a suffix modifier, i.e., a modifier other than the length prefix.
     ///
-    /// If there is a [switch](Switch), [`self.context`](Self::context) affects
-    /// how the word of the switch is parsed: If the context is `Word`, a tilde
+    /// If there is a [switch](Switch) or [substitution](Subst),
+    /// [`self.context`](Self::context) affects how the word of the switch or
+    /// the replacement of the substitution is parsed: If the context is `Word`, a tilde
     /// expansion is recognized at the beginning of the word and any character
     /// can be escaped by a backslash. If the context is `Text`, only `$`, `"`,
     /// `` ` ``, `\` and `}` can be escaped and single quotes are not recognized
@@ -143,6 +193,7 @@ impl WordLexer<'_, '_> {
             match symbol {
                 '+' | '-' | '=' | '?' => self.switch(colon, symbol).await,
                 '#' | '%' => self.trim(start_index, colon, symbol).await,
+                '/' => self.subst(start_index, colon).await,
                 _ => self.suffix_modifier_not_found(start_index, colon),
             }
         } else {
@@ -592,4 +643,131 @@ mod tests {
         assert_eq!(*e.location.code.value.borrow(), ":#}");
         assert_eq!(e.location.range, 0..2);
     }
+
+    #[test]
+    fn lexer_suffix_modifier_subst_first() {
+        let mut lexer = Lexer::from_memory("/a*/b}", Source::Unknown);
+        let mut lexer = WordLexer {
+            lexer: &mut lexer,
+            context: WordContext::Word,
+        };
+
+        let result = lexer.suffix_modifier().now_or_never().unwrap().unwrap();
+        assert_matches!(result, Modifier::Subst(subst) => {
+            assert_eq!(subst.r#type, SubstType::First);
+            assert_eq!(
+                subst.pattern.units,
+                [
+                    WordUnit::Unquoted(TextUnit::Literal('a')),
+                    WordUnit::Unquoted(TextUnit::Literal('*')),
+                ]
+            );
+            assert_eq!(subst.pattern.location.range, 1..3);
+            assert_eq!(
+                subst.replacement.units,
+                [WordUnit::Unquoted(TextUnit::Literal('b'))]
+            );
+            assert_eq!(subst.replacement.location.range, 4..5);
+        });
+
+        assert_eq!(lexer.peek_char().now_or_never().unwrap(), Ok(Some('}')));
+    }
+
+    #[test]
+    fn lexer_suffix_modifier_subst_all() {
+        let mut lexer = Lexer::from_memory("//x/}", Source::Unknown);
+        let mut lexer = WordLexer {
+            lexer: &mut lexer,
+            context: WordContext::Word,
+        };
+
+        let result = lexer.suffix_modifier().now_or_never().unwrap().unwrap();
+        assert_matches!(result, Modifier::Subst(subst) => {
+            assert_eq!(subst.r#type, SubstType::All);
+            assert_eq!(
+                subst.pattern.units,
+                [WordUnit::Unquoted(TextUnit::Literal('x'))]
+            );
+            assert_eq!(subst.replacement.units, []);
+        });
+
+        assert_eq!(lexer.peek_char().now_or_never().unwrap(), Ok(Some('}')));
+    }
+
+    #[test]
+    fn lexer_suffix_modifier_subst_prefix_and_suffix() {
+        let mut lexer = Lexer::from_memory("/#x/y}", Source::Unknown);
+        let mut lexer = WordLexer {
+            lexer: &mut lexer,
+            context: WordContext::Word,
+        };
+        let result = lexer.suffix_modifier().now_or_never().unwrap().unwrap();
+        assert_matches!(result, Modifier::Subst(subst) => {
+            assert_eq!(subst.r#type, SubstType::Prefix);
+        });
+
+        let mut lexer = Lexer::from_memory("/%x/y}", Source::Unknown);
+        let mut lexer = WordLexer {
+            lexer: &mut lexer,
+            context: WordContext::Word,
+        };
+        let result = lexer.suffix_modifier().now_or_never().unwrap().unwrap();
+        assert_matches!(result, Modifier::Subst(subst) => {
+            assert_eq!(subst.r#type, SubstType::Suffix);
+        });
+    }
+
+    #[test]
+    fn lexer_suffix_modifier_subst_without_replacement() {
+        let mut lexer = Lexer::from_memory("/'/'}", Source::Unknown);
+        let mut lexer = WordLexer {
+            lexer: &mut lexer,
+            context: WordContext::Word,
+        };
+
+        let result = lexer.suffix_modifier().now_or_never().unwrap().unwrap();
+        assert_matches!(result, Modifier::Subst(subst) => {
+            assert_eq!(subst.r#type, SubstType::First);
+            assert_eq!(subst.pattern.units, [WordUnit::SingleQuote("/".to_string())]);
+            assert_eq!(subst.replacement.units, []);
+            assert_eq!(subst.replacement.location.range, 4..4);
+        });
+
+        assert_eq!(lexer.peek_char().now_or_never().unwrap(), Ok(Some('}')));
+    }
+
+    #[test]
+    fn lexer_suffix_modifier_subst_replacement_in_text_context() {
+        let mut lexer = Lexer::from_memory("/a/~'b'}", Source::Unknown);
+        let mut lexer = WordLexer {
+            lexer: &mut lexer,
+            context: WordContext::Text,
+        };
+
+        let result = lexer.suffix_modifier().now_or_never().unwrap().unwrap();
+        assert_matches!(result, Modifier::Subst(subst) => {
+            assert_eq!(
+                subst.replacement.units,
+                [
+                    WordUnit::Unquoted(TextUnit::Literal('~')),
+                    WordUnit::Unquoted(TextUnit::Literal('\'')),
+                    WordUnit::Unquoted(TextUnit::Literal('b')),
+                    WordUnit::Unquoted(TextUnit::Literal('\'')),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn lexer_suffix_modifier_colon_subst() {
+        let mut lexer = Lexer::from_memory(":/a/b}", Source::Unknown);
+        let mut lexer = WordLexer {
+            lexer: &mut lexer,
+            context: WordContext::Word,
+        };
+
+        let e = lexer.suffix_modifier().now_or_never().unwrap().unwrap_err();
+        assert_eq!(e.cause, ErrorCause::Syntax(SyntaxError::InvalidModifier));
+        assert_eq!(e.location.range, 0..2);
+    }
 }
@@ -0,0 +1,136 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Part of the lexer that parses process substitutions.
+
+use super::core::Lexer;
+use crate::parser::core::Result;
+use crate::parser::error::Error;
+use crate::parser::error::SyntaxError;
+use crate::syntax::ProcessSubstKind;
+use crate::syntax::WordUnit;
+
+impl Lexer<'_> {
+    /// Tests if the next characters are `<(` or `>(`.
+    ///
+    /// This function returns the kind of the process substitution that starts
+    /// with the characters, if any. No characters are consumed.
+    pub async fn process_substitution_start(&mut self) -> Result<Option<ProcessSubstKind>> {
+        let kind = match self.peek_char().await? {
+            Some('<') => ProcessSubstKind::Input,
+            Some('>') => ProcessSubstKind::Output,
+            _ => return Ok(None),
+        };
+
+        let index = self.index();
+        self.consume_char();
+        let is_paren = self.peek_char().await? == Some('(');
+        self.rewind(index);
+        Ok(is_paren.then_some(kind))
+    }
+
+    /// Parses a process substitution of the form `<(...)` or `>(...)`.
+    ///
+    /// If the next two characters are `<(` or `>(`, the following characters
+    /// are parsed as commands to find a matching `)`, which will be consumed
+    /// before this function returns. Otherwise, no characters are consumed and
+    /// the return value is `Ok(None)`.
+    pub async fn process_substitution(&mut self) -> Result<Option<WordUnit>> {
+        let Some(kind) = self.process_substitution_start().await? else {
+            return Ok(None);
+        };
+
+        let start_index = self.index();
+        self.consume_char();
+        let opening_location = self.location().await?.clone();
+        self.consume_char();
+
+        let content = self.inner_program_boxed().await?.into();
+
+        if !self.skip_if(|c| c == ')').await? {
+            let cause = SyntaxError::UnclosedProcessSubstitution { opening_location }.into();
+            let location = self.location().await?.clone();
+            return Err(Error { cause, location });
+        }
+
+        let location = self.location_range(start_index..self.index());
+        Ok(Some(WordUnit::ProcessSubst {
+            kind,
+            content,
+            location,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::error::ErrorCause;
+    use crate::source::Source;
+    use assert_matches::assert_matches;
+    use futures_util::FutureExt;
+
+    #[test]
+    fn lexer_process_substitution_input() {
+        let mut lexer = Lexer::from_memory("<( sort a )b", Source::Unknown);
+        let result = lexer.process_substitution().now_or_never().unwrap();
+        let unit = result.unwrap().unwrap();
+        assert_matches!(unit, WordUnit::ProcessSubst { kind, content, location } => {
+            assert_eq!(kind, ProcessSubstKind::Input);
+            assert_eq!(&*content, " sort a ");
+            assert_eq!(location.range, 0..11);
+        });
+        assert_eq!(lexer.peek_char().now_or_never().unwrap(), Ok(Some('b')));
+    }
+
+    #[test]
+    fn lexer_process_substitution_output() {
+        let mut lexer = Lexer::from_memory(">(tee log)", Source::Unknown);
+        let result = lexer.process_substitution().now_or_never().unwrap();
+        let unit = result.unwrap().unwrap();
+        assert_matches!(unit, WordUnit::ProcessSubst { kind, content, location } => {
+            assert_eq!(kind, ProcessSubstKind::Output);
+            assert_eq!(&*content, "tee log");
+            assert_eq!(location.range, 0..10);
+        });
+        assert_eq!(lexer.peek_char().now_or_never().unwrap(), Ok(None));
+    }
+
+    #[test]
+    fn lexer_process_substitution_none() {
+        let mut lexer = Lexer::from_memory("< (foo)", Source::Unknown);
+        let result = lexer.process_substitution().now_or_never().unwrap();
+        assert_eq!(result, Ok(None));
+        assert_eq!(lexer.index(), 0);
+
+        let mut lexer = Lexer::from_memory("(foo)", Source::Unknown);
+        let result = lexer.process_substitution().now_or_never().unwrap();
+        assert_eq!(result, Ok(None));
+        assert_eq!(lexer.index(), 0);
+    }
+
+    #[test]
+    fn lexer_process_substitution_unclosed() {
+        let mut lexer = Lexer::from_memory("<( foo bar", Source::Unknown);
+        let result = lexer.process_substitution().now_or_never().unwrap();
+        let e = result.unwrap_err();
+        assert_matches!(e.cause,
+            ErrorCause::Syntax(SyntaxError::UnclosedProcessSubstitution { opening_location }) => {
+            assert_eq!(opening_location.range, 1..2);
+        });
+        assert_eq!(e.location.range, 10..10);
+    }
+}
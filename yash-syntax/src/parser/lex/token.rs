@@ -26,7 +26,10 @@ use super::keyword::Keyword;
 use super::op::is_operator_char;
 use crate::parser::core::Result;
 use crate::syntax::MaybeLiteral;
+use crate::syntax::TextUnit::Literal;
 use crate::syntax::Word;
+use crate::syntax::WordUnit::Unquoted;
+use std::cell::Cell;
 
 /// Tests whether the given character is a token delimiter.
 ///
@@ -67,8 +70,11 @@ impl Lexer<'_> {
     /// If there is no more token that can be parsed, the result is a token with an empty word and
     /// [`EndOfInput`](TokenId::EndOfInput) token identifier.
     pub async fn token(&mut self) -> Result<Token> {
-        if let Some(op) = self.operator().await? {
-            return Ok(op);
+        // `<(` and `>(` start a process substitution rather than an operator
+        if self.process_substitution_start().await?.is_none() {
+            if let Some(op) = self.operator().await? {
+                return Ok(op);
+            }
         }
 
         let index = self.index();
@@ -84,14 +90,58 @@ impl Lexer<'_> {
 
         Ok(Token { word, id, index })
     }
+
+    /// Parses a token that is the right-hand side of the `=~` operator.
+    ///
+    /// This function is similar to [`token`](Self::token), but `(`, `)` and
+    /// `|` are part of the word as long as the parentheses are balanced.
+    /// Between parentheses, blanks and operator characters other than a newline
+    /// are part of the word as well. If there is no such word at the current
+    /// position, this function falls back on `token`.
+    pub async fn regex_token(&mut self) -> Result<Token> {
+        let index = self.index();
+        let depth = Cell::new(0_usize);
+        let is_delimiter = |c| match c {
+            '(' | '|' => false,
+            '\n' => true,
+            _ => depth.get() == 0 && is_token_delimiter_char(c),
+        };
+
+        let mut word_lexer = WordLexer {
+            lexer: self,
+            context: WordContext::Word,
+        };
+        let mut units = Vec::new();
+        while let Some(unit) = word_lexer.word_unit(is_delimiter).await? {
+            match unit {
+                Unquoted(Literal('(')) => depth.set(depth.get() + 1),
+                Unquoted(Literal(')')) => depth.set(depth.get() - 1),
+                _ => (),
+            }
+            units.push(unit);
+        }
+        if units.is_empty() {
+            return self.token().await;
+        }
+
+        let location = self.location_range(index..self.index());
+        let mut word = Word { units, location };
+        word.parse_tilde_front();
+
+        let id = self.token_id(&word).await?;
+
+        Ok(Token { word, id, index })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::lex::Operator;
     use crate::source::Source;
     use crate::syntax::TextUnit;
     use crate::syntax::WordUnit;
+    use assert_matches::assert_matches;
     use futures_util::FutureExt;
 
     #[test]
@@ -127,6 +177,20 @@ mod tests {
         assert_eq!(lexer.peek_char().now_or_never().unwrap(), Ok(Some(' ')));
     }
 
+    #[test]
+    fn lexer_token_process_substitution() {
+        let mut lexer = Lexer::from_memory("a<(b)c >x", Source::Unknown);
+
+        let t = lexer.token().now_or_never().unwrap().unwrap();
+        assert_eq!(t.word.to_string(), "a<(b)c");
+        assert_eq!(t.id, TokenId::Token(None));
+        assert_matches!(&t.word.units[1], WordUnit::ProcessSubst { .. });
+
+        lexer.skip_blanks().now_or_never().unwrap().unwrap();
+        let t = lexer.token().now_or_never().unwrap().unwrap();
+        assert_eq!(t.id, TokenId::Operator(Operator::Greater));
+    }
+
     #[test]
     fn lexer_token_tilde() {
         let mut lexer = Lexer::from_memory("~a:~", Source::Unknown);
@@ -202,4 +266,18 @@ mod tests {
         assert_eq!(t.id, TokenId::EndOfInput);
         assert_eq!(t.index, 4);
     }
+
+    #[test]
+    fn lexer_regex_token_with_parentheses() {
+        let mut lexer = Lexer::from_memory("(a |b)|c) d", Source::Unknown);
+
+        let t = lexer.regex_token().now_or_never().unwrap().unwrap();
+        assert_eq!(t.word.to_string(), "(a |b)|c");
+        assert_eq!(t.word.location.range, 0..8);
+        assert_eq!(t.id, TokenId::Token(None));
+        assert_eq!(t.index, 0);
+
+        let t = lexer.regex_token().now_or_never().unwrap().unwrap();
+        assert_eq!(t.id, TokenId::Operator(Operator::CloseParen));
+    }
 }
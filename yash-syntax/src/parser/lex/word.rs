@@ -93,6 +93,9 @@ impl WordLexer<'_, '_> {
     /// escaped. If `Text`, then `$`, `"`, `` ` `` and `\` can be escaped as
     /// well as delimiters.
     ///
+    /// In the `Word` context, a process substitution is parsed even if
+    /// `is_delimiter` returns true for the initial `<` or `>`.
+    ///
     /// This function does not parse tilde expansion. See [`word`](Self::word).
     pub async fn word_unit<F>(&mut self, is_delimiter: F) -> Result<Option<WordUnit>>
     where
@@ -117,6 +120,12 @@ impl WordLexer<'_, '_> {
             WordContext::Text => &escape_some,
         };
 
+        if self.context == WordContext::Word {
+            if let Some(unit) = self.process_substitution().await? {
+                return Ok(Some(unit));
+            }
+        }
+
         match self.peek_char().await? {
             Some(c) if c == '\'' && allow_single_quote => {
                 let location = self.location().await?.clone();
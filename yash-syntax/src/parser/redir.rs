@@ -47,7 +47,7 @@ impl Parser<'_, '_> {
 
     /// Parses a normal redirection body.
     async fn normal_redirection_body(&mut self, operator: RedirOp) -> Result<RedirBody> {
-        // TODO reject >>| and <<< if POSIXly-correct
+        // `>>|` and `<<<` are rejected on execution in the POSIXly-correct mode
         self.take_token_raw().await?;
         let operand = self
             .redirection_operand()
@@ -92,7 +92,6 @@ impl Parser<'_, '_> {
         match operator {
             LessLess => Ok(Some(self.here_doc_redirection_body(false).await?)),
             LessLessDash => Ok(Some(self.here_doc_redirection_body(true).await?)),
-            // TODO <() >()
             _ => Ok(None),
         }
     }
@@ -284,6 +283,31 @@ mod tests {
         });
     }
 
+    #[test]
+    fn parser_redirection_process_substitution_operand() {
+        let mut lexer = Lexer::from_memory("< <(sort a)\n", Source::Unknown);
+        let aliases = Default::default();
+        let mut parser = Parser::new(&mut lexer, &aliases);
+
+        let result = parser.redirection().now_or_never().unwrap();
+        let redir = result.unwrap().unwrap();
+        assert_eq!(redir.fd, None);
+        assert_matches!(redir.body, RedirBody::Normal { operator, operand } => {
+            assert_eq!(operator, RedirOp::FileIn);
+            assert_eq!(operand.to_string(), "<(sort a)")
+        });
+    }
+
+    #[test]
+    fn parser_redirection_process_substitution_not_operator() {
+        let mut lexer = Lexer::from_memory(">(tee b)", Source::Unknown);
+        let aliases = Default::default();
+        let mut parser = Parser::new(&mut lexer, &aliases);
+
+        let result = parser.redirection().now_or_never().unwrap();
+        assert_eq!(result, Ok(None));
+    }
+
     #[test]
     fn parser_redirection_less_less() {
         let mut lexer = Lexer::from_memory("<<end \nend\n", Source::Unknown);
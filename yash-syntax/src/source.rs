@@ -42,6 +42,15 @@ pub enum Source {
     /// Standard input.
     Stdin,
 
+    /// Command string specified with the `-c` option on the shell startup.
+    CommandString,
+
+    /// File containing a command string.
+    CommandFile {
+        /// Path to the file
+        path: String,
+    },
+
     /// Alias substitution.
     ///
     /// This applies to a code fragment that replaced another as a result of alias substitution.
@@ -55,9 +64,26 @@ pub enum Source {
     /// Command substitution.
     CommandSubst { original: Location },
 
+    /// Process substitution.
+    ProcessSubst { original: Location },
+
     /// Arithmetic expansion.
     Arith { original: Location },
 
+    /// Command string executed by the eval built-in.
+    Eval {
+        /// Location of the operand that contains the command string
+        original: Location,
+    },
+
+    /// Script file executed by the dot built-in.
+    DotScript {
+        /// Pathname of the script file as given to the built-in
+        name: String,
+        /// Location of the operand that named the script file
+        origin: Location,
+    },
+
     /// Trap command.
     Trap {
         /// Trap condition name, typically the signal name.
@@ -133,9 +159,14 @@ impl Source {
         match self {
             Unknown => "<?>",
             Stdin => "<stdin>",
+            CommandString => "<command_string>",
+            CommandFile { path } => path,
             Alias { .. } => "<alias>",
             CommandSubst { .. } => "<command_substitution>",
+            ProcessSubst { .. } => "<process_substitution>",
             Arith { .. } => "<arith>",
+            Eval { .. } => "<eval>",
+            DotScript { name, .. } => name,
             Trap { condition, .. } => condition,
         }
     }
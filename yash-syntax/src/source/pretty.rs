@@ -103,7 +103,7 @@ impl super::Source {
     pub fn complement_annotations<'a, 's: 'a, T: Extend<Annotation<'a>>>(&'s self, result: &mut T) {
         use super::Source::*;
         match self {
-            Unknown | Stdin => (),
+            Unknown | Stdin | CommandString | CommandFile { .. } => (),
             CommandSubst { original } => {
                 // TODO Use Extend::extend_one
                 result.extend(std::iter::once(Annotation::new(
@@ -112,6 +112,14 @@ impl super::Source {
                     original,
                 )));
             }
+            ProcessSubst { original } => {
+                // TODO Use Extend::extend_one
+                result.extend(std::iter::once(Annotation::new(
+                    AnnotationType::Info,
+                    "process substitution appeared here".into(),
+                    original,
+                )));
+            }
             Arith { original } => {
                 // TODO Use Extend::extend_one
                 result.extend(std::iter::once(Annotation::new(
@@ -120,6 +128,22 @@ impl super::Source {
                     original,
                 )));
             }
+            Eval { original } => {
+                // TODO Use Extend::extend_one
+                result.extend(std::iter::once(Annotation::new(
+                    AnnotationType::Info,
+                    "command passed to the eval built-in here".into(),
+                    original,
+                )));
+            }
+            DotScript { origin, .. } => {
+                // TODO Use Extend::extend_one
+                result.extend(std::iter::once(Annotation::new(
+                    AnnotationType::Info,
+                    "script was sourced here".into(),
+                    origin,
+                )));
+            }
             Trap { origin, .. } => {
                 // TODO Use Extend::extend_one
                 result.extend(std::iter::once(Annotation::new(
@@ -309,6 +309,67 @@ impl Unquote for Trim {
     }
 }
 
+/// Flag that specifies which part of the expanded value is replaced in a
+/// [substitution](Subst).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SubstType {
+    /// First match in the value. (`/`)
+    First,
+    /// All matches in the value. (`//`)
+    All,
+    /// Match at the beginning of the value. (`/#`)
+    Prefix,
+    /// Match at the end of the value. (`/%`)
+    Suffix,
+}
+
+impl fmt::Display for SubstType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use SubstType::*;
+        let s = match self {
+            First => "/",
+            All => "//",
+            Prefix => "/#",
+            Suffix => "/%",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Parameter expansion [modifier](Modifier) that replaces part of the value
+/// being expanded.
+///
+/// Examples of substitutions include `/foo/bar`, `//x` and `/#*/baz`.
+///
+/// A substitution is composed of a [type](SubstType), pattern and
+/// replacement. If the replacement is omitted in the source code, it is
+/// represented as an empty word.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subst {
+    /// Which part of the value should be replaced?
+    pub r#type: SubstType,
+    /// Pattern to be matched with the expanded value.
+    pub pattern: Word,
+    /// Word that replaces the matched part of the value.
+    pub replacement: Word,
+}
+
+impl fmt::Display for Subst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}/{}", self.r#type, self.pattern, self.replacement)
+    }
+}
+
+impl Unquote for Subst {
+    fn write_unquoted<W: fmt::Write>(&self, w: &mut W) -> UnquoteResult {
+        write!(w, "{}", self.r#type)?;
+        let pattern_quoted = self.pattern.write_unquoted(w)?;
+        w.write_char('/')?;
+        let replacement_quoted = self.replacement.write_unquoted(w)?;
+        Ok(pattern_quoted || replacement_quoted)
+    }
+}
+
 /// Attribute that modifies a parameter expansion.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Modifier {
@@ -320,7 +381,8 @@ pub enum Modifier {
     Switch(Switch),
     /// `#`, `##`, `%` or `%%` suffix.
     Trim(Trim),
-    // TODO Subst
+    /// `/`, `//`, `/#` or `/%` suffix. (`${foo/bar/baz}`)
+    Subst(Subst),
 }
 
 /// Parameter expansion enclosed in braces.
@@ -348,6 +410,7 @@ impl fmt::Display for Param {
             Length => write!(f, "${{#{}}}", self.name),
             Switch(ref switch) => write!(f, "${{{}{}}}", self.name, switch),
             Trim(ref trim) => write!(f, "${{{}{}}}", self.name, trim),
+            Subst(ref subst) => write!(f, "${{{}{}}}", self.name, subst),
         }
     }
 }
@@ -376,6 +439,12 @@ impl Unquote for Param {
                 w.write_char('}')?;
                 Ok(quoted)
             }
+            Subst(ref subst) => {
+                write!(w, "${{{}", self.name)?;
+                let quoted = subst.write_unquoted(w)?;
+                w.write_char('}')?;
+                Ok(quoted)
+            }
         }
     }
 }
@@ -562,6 +631,25 @@ impl MaybeLiteral for Text {
     }
 }
 
+/// Direction of a [process substitution](WordUnit::ProcessSubst)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ProcessSubstKind {
+    /// `<(...)`: Reading from the pathname yields the output of the command.
+    Input,
+    /// `>(...)`: Data written to the pathname is passed to the command as its
+    /// input.
+    Output,
+}
+
+impl fmt::Display for ProcessSubstKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessSubstKind::Input => f.write_char('<'),
+            ProcessSubstKind::Output => f.write_char('>'),
+        }
+    }
+}
+
 /// Element of a [Word], i.e., text with quotes and tilde expansion.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum WordUnit {
@@ -575,6 +663,16 @@ pub enum WordUnit {
     ///
     /// The `String` value does not contain the initial tilde.
     Tilde(String),
+    /// Process substitution of the form `<(...)` or `>(...)`.
+    ProcessSubst {
+        /// Whether the command's output or input is substituted
+        kind: ProcessSubstKind,
+        /// Command string that will be parsed and executed when the process
+        /// substitution is expanded.
+        content: Rc<str>,
+        /// Position of this process substitution in the source code.
+        location: Location,
+    },
 }
 
 pub use WordUnit::*;
@@ -586,6 +684,7 @@ impl fmt::Display for WordUnit {
             SingleQuote(s) => write!(f, "'{s}'"),
             DoubleQuote(content) => write!(f, "\"{content}\""),
             Tilde(s) => write!(f, "~{s}"),
+            ProcessSubst { kind, content, .. } => write!(f, "{kind}({content})"),
         }
     }
 }
@@ -603,6 +702,10 @@ impl Unquote for WordUnit {
                 write!(w, "~{s}")?;
                 Ok(false)
             }
+            ProcessSubst { kind, content, .. } => {
+                write!(w, "{kind}({content})")?;
+                Ok(false)
+            }
         }
     }
 }
@@ -873,7 +976,6 @@ pub enum RedirBody {
     Normal { operator: RedirOp, operand: Word },
     /// Here-document.
     HereDoc(Rc<HereDoc>),
-    // TODO process redirection
 }
 
 impl RedirBody {
@@ -1011,6 +1113,243 @@ impl fmt::Display for CaseItem {
     }
 }
 
+/// Unary operator in a [conditional expression](CondExpr)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CondUnaryOperator {
+    /// `-b`: block special file
+    BlockSpecial,
+    /// `-c`: character special file
+    CharacterSpecial,
+    /// `-d`: directory
+    Directory,
+    /// `-e`: existing file
+    Exists,
+    /// `-f`: regular file
+    Regular,
+    /// `-g`: file with the set-group-ID bit
+    SetGroupId,
+    /// `-h` or `-L`: symbolic link
+    SymbolicLink,
+    /// `-k`: file with the sticky bit
+    Sticky,
+    /// `-n`: non-empty string
+    NonEmptyString,
+    /// `-p`: FIFO
+    Fifo,
+    /// `-r`: readable file
+    Readable,
+    /// `-S`: socket
+    Socket,
+    /// `-s`: file with a non-zero size
+    NonEmptyFile,
+    /// `-t`: file descriptor associated with a terminal
+    Terminal,
+    /// `-u`: file with the set-user-ID bit
+    SetUserId,
+    /// `-w`: writable file
+    Writable,
+    /// `-x`: executable file (or searchable directory)
+    Executable,
+    /// `-z`: empty string
+    EmptyString,
+}
+
+impl CondUnaryOperator {
+    /// Parses a unary operator.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        use CondUnaryOperator::*;
+        Some(match s {
+            "-b" => BlockSpecial,
+            "-c" => CharacterSpecial,
+            "-d" => Directory,
+            "-e" => Exists,
+            "-f" => Regular,
+            "-g" => SetGroupId,
+            "-h" | "-L" => SymbolicLink,
+            "-k" => Sticky,
+            "-n" => NonEmptyString,
+            "-p" => Fifo,
+            "-r" => Readable,
+            "-S" => Socket,
+            "-s" => NonEmptyFile,
+            "-t" => Terminal,
+            "-u" => SetUserId,
+            "-w" => Writable,
+            "-x" => Executable,
+            "-z" => EmptyString,
+            _ => return None,
+        })
+    }
+
+    /// Returns the string representation of the operator.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        use CondUnaryOperator::*;
+        match self {
+            BlockSpecial => "-b",
+            CharacterSpecial => "-c",
+            Directory => "-d",
+            Exists => "-e",
+            Regular => "-f",
+            SetGroupId => "-g",
+            SymbolicLink => "-h",
+            Sticky => "-k",
+            NonEmptyString => "-n",
+            Fifo => "-p",
+            Readable => "-r",
+            Socket => "-S",
+            NonEmptyFile => "-s",
+            Terminal => "-t",
+            SetUserId => "-u",
+            Writable => "-w",
+            Executable => "-x",
+            EmptyString => "-z",
+        }
+    }
+}
+
+impl fmt::Display for CondUnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Binary operator in a [conditional expression](CondExpr)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CondBinaryOperator {
+    /// `==` or `=`: string matching a pattern
+    PatternMatch,
+    /// `!=`: string not matching a pattern
+    PatternNotMatch,
+    /// `=~`: string matching a regular expression
+    RegexMatch,
+    /// `<`: string sorted before another
+    StringLess,
+    /// `>`: string sorted after another
+    StringGreater,
+    /// `-eq`: equal integers
+    IntEqual,
+    /// `-ne`: different integers
+    IntNotEqual,
+    /// `-gt`: greater integer
+    IntGreater,
+    /// `-ge`: greater or equal integer
+    IntGreaterEqual,
+    /// `-lt`: less integer
+    IntLess,
+    /// `-le`: less or equal integer
+    IntLessEqual,
+    /// `-ef`: same file
+    SameFile,
+    /// `-nt`: newer file
+    NewerThan,
+    /// `-ot`: older file
+    OlderThan,
+}
+
+impl CondBinaryOperator {
+    /// Parses a binary operator.
+    ///
+    /// This function does not recognize `<` and `>`, which are not word tokens
+    /// but operator tokens.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        use CondBinaryOperator::*;
+        Some(match s {
+            "==" | "=" => PatternMatch,
+            "!=" => PatternNotMatch,
+            "=~" => RegexMatch,
+            "-eq" => IntEqual,
+            "-ne" => IntNotEqual,
+            "-gt" => IntGreater,
+            "-ge" => IntGreaterEqual,
+            "-lt" => IntLess,
+            "-le" => IntLessEqual,
+            "-ef" => SameFile,
+            "-nt" => NewerThan,
+            "-ot" => OlderThan,
+            _ => return None,
+        })
+    }
+
+    /// Returns the string representation of the operator.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        use CondBinaryOperator::*;
+        match self {
+            PatternMatch => "==",
+            PatternNotMatch => "!=",
+            RegexMatch => "=~",
+            StringLess => "<",
+            StringGreater => ">",
+            IntEqual => "-eq",
+            IntNotEqual => "-ne",
+            IntGreater => "-gt",
+            IntGreaterEqual => "-ge",
+            IntLess => "-lt",
+            IntLessEqual => "-le",
+            SameFile => "-ef",
+            NewerThan => "-nt",
+            OlderThan => "-ot",
+        }
+    }
+}
+
+impl fmt::Display for CondBinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Conditional expression in a double-bracket command
+///
+/// Operands of a conditional expression are not subject to field splitting
+/// and pathname expansion.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CondExpr {
+    /// Single word that is tested for non-emptiness
+    Word(Word),
+    /// Unary primary
+    Unary {
+        operator: CondUnaryOperator,
+        operand: Word,
+    },
+    /// Binary primary
+    Binary {
+        left: Word,
+        operator: CondBinaryOperator,
+        right: Word,
+    },
+    /// Negation (`!`)
+    Not(Box<CondExpr>),
+    /// Logical conjunction (`&&`)
+    And(Box<CondExpr>, Box<CondExpr>),
+    /// Logical disjunction (`||`)
+    Or(Box<CondExpr>, Box<CondExpr>),
+    /// Parenthesized expression
+    Group(Box<CondExpr>),
+}
+
+impl fmt::Display for CondExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use CondExpr::*;
+        match self {
+            Word(word) => word.fmt(f),
+            Unary { operator, operand } => write!(f, "{operator} {operand}"),
+            Binary {
+                left,
+                operator,
+                right,
+            } => write!(f, "{left} {operator} {right}"),
+            Not(expr) => write!(f, "! {expr}"),
+            And(left, right) => write!(f, "{left} && {right}"),
+            Or(left, right) => write!(f, "{left} || {right}"),
+            Group(expr) => write!(f, "( {expr} )"),
+        }
+    }
+}
+
 /// Command that contains other commands.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum CompoundCommand {
@@ -1037,7 +1376,11 @@ pub enum CompoundCommand {
     },
     /// Case conditional construct.
     Case { subject: Word, items: Vec<CaseItem> },
-    // TODO [[ ]]
+    /// Double-bracket conditional construct.
+    DoubleBracket {
+        expression: CondExpr,
+        location: Location,
+    },
 }
 
 impl fmt::Display for CompoundCommand {
@@ -1081,6 +1424,7 @@ impl fmt::Display for CompoundCommand {
                 }
                 f.write_str("esac")
             }
+            DoubleBracket { expression, .. } => write!(f, "[[ {expression} ]]"),
         }
     }
 }
@@ -1407,6 +1751,67 @@ mod tests {
         assert_eq!(is_quoted, false);
     }
 
+    #[test]
+    fn subst_display() {
+        let subst = Subst {
+            r#type: SubstType::First,
+            pattern: "foo".parse().unwrap(),
+            replacement: "bar".parse().unwrap(),
+        };
+        assert_eq!(subst.to_string(), "/foo/bar");
+
+        let subst = Subst {
+            r#type: SubstType::All,
+            pattern: "?".parse().unwrap(),
+            replacement: "".parse().unwrap(),
+        };
+        assert_eq!(subst.to_string(), "//?/");
+
+        let subst = Subst {
+            r#type: SubstType::Prefix,
+            pattern: "*".parse().unwrap(),
+            replacement: "x y".parse().unwrap(),
+        };
+        assert_eq!(subst.to_string(), "/#*/x y");
+
+        let subst = Subst {
+            r#type: SubstType::Suffix,
+            pattern: "".parse().unwrap(),
+            replacement: "z".parse().unwrap(),
+        };
+        assert_eq!(subst.to_string(), "/%/z");
+    }
+
+    #[test]
+    fn subst_unquote() {
+        let subst = Subst {
+            r#type: SubstType::First,
+            pattern: "foo".parse().unwrap(),
+            replacement: "bar".parse().unwrap(),
+        };
+        let (unquoted, is_quoted) = subst.unquote();
+        assert_eq!(unquoted, "/foo/bar");
+        assert_eq!(is_quoted, false);
+
+        let subst = Subst {
+            r#type: SubstType::All,
+            pattern: r"\*".parse().unwrap(),
+            replacement: "".parse().unwrap(),
+        };
+        let (unquoted, is_quoted) = subst.unquote();
+        assert_eq!(unquoted, "//*/");
+        assert_eq!(is_quoted, true);
+
+        let subst = Subst {
+            r#type: SubstType::Suffix,
+            pattern: "x".parse().unwrap(),
+            replacement: "'y'".parse().unwrap(),
+        };
+        let (unquoted, is_quoted) = subst.unquote();
+        assert_eq!(unquoted, "/%x/y");
+        assert_eq!(is_quoted, true);
+    }
+
     #[test]
     fn braced_param_display() {
         let param = Param {
@@ -1443,6 +1848,17 @@ mod tests {
             ..param
         };
         assert_eq!(param.to_string(), "${foo%baz' 'bar}");
+
+        let subst = Subst {
+            r#type: SubstType::All,
+            pattern: "a*".parse().unwrap(),
+            replacement: "'b c'".parse().unwrap(),
+        };
+        let param = Param {
+            modifier: Modifier::Subst(subst),
+            ..param
+        };
+        assert_eq!(param.to_string(), "${foo//a*/'b c'}");
     }
 
     #[test]
@@ -1489,6 +1905,19 @@ mod tests {
         let (unquoted, is_quoted) = param.unquote();
         assert_eq!(unquoted, "${foo%baz bar}");
         assert_eq!(is_quoted, true);
+
+        let subst = Subst {
+            r#type: SubstType::Prefix,
+            pattern: "a".parse().unwrap(),
+            replacement: "b".parse().unwrap(),
+        };
+        let param = Param {
+            modifier: Modifier::Subst(subst),
+            ..param
+        };
+        let (unquoted, is_quoted) = param.unquote();
+        assert_eq!(unquoted, "${foo/#a/b}");
+        assert_eq!(is_quoted, false);
     }
 
     #[test]
@@ -1655,6 +2084,19 @@ mod tests {
         assert_eq!(tilde.to_string(), "~");
         let tilde = Tilde("foo".to_string());
         assert_eq!(tilde.to_string(), "~foo");
+
+        let process_subst = ProcessSubst {
+            kind: ProcessSubstKind::Input,
+            content: "sort a".into(),
+            location: Location::dummy(""),
+        };
+        assert_eq!(process_subst.to_string(), "<(sort a)");
+        let process_subst = ProcessSubst {
+            kind: ProcessSubstKind::Output,
+            content: " tee b ".into(),
+            location: Location::dummy(""),
+        };
+        assert_eq!(process_subst.to_string(), ">( tee b )");
     }
 
     #[test]
@@ -2071,6 +2513,47 @@ mod tests {
         assert_eq!(case.to_string(), "case baz in (1) ;; (a | b | c) :&;; esac");
     }
 
+    #[test]
+    fn cond_expr_display() {
+        let word = |s: &str| Word::from_str(s).unwrap();
+        let unary = CondExpr::Unary {
+            operator: CondUnaryOperator::Regular,
+            operand: word("file"),
+        };
+        assert_eq!(unary.to_string(), "-f file");
+
+        let binary = CondExpr::Binary {
+            left: word("$x"),
+            operator: CondBinaryOperator::PatternMatch,
+            right: word("a*"),
+        };
+        assert_eq!(binary.to_string(), "$x == a*");
+
+        let expr = CondExpr::Or(
+            Box::new(CondExpr::Not(Box::new(unary))),
+            Box::new(CondExpr::Group(Box::new(CondExpr::And(
+                Box::new(binary),
+                Box::new(CondExpr::Word(word("y"))),
+            )))),
+        );
+        assert_eq!(expr.to_string(), "! -f file || ( $x == a* && y )");
+    }
+
+    #[test]
+    fn double_bracket_display() {
+        let expression = CondExpr::Binary {
+            left: Word::from_str("a").unwrap(),
+            operator: CondBinaryOperator::StringLess,
+            right: Word::from_str("b").unwrap(),
+        };
+        let location = Location::dummy("");
+        let command = CompoundCommand::DoubleBracket {
+            expression,
+            location,
+        };
+        assert_eq!(command.to_string(), "[[ a < b ]]");
+    }
+
     #[test]
     fn function_definition_display() {
         let body = FullCompoundCommand {
@@ -25,37 +25,66 @@ pub use yash_semantics as semantics;
 #[doc(no_inline)]
 pub use yash_syntax::{alias, parser, source, syntax};
 
-// TODO Allow user to select input source
-async fn parse_and_print(mut env: yash_env::Env) -> i32 {
+pub mod startup;
+
+/// Opens the script file at `path` for reading commands.
+///
+/// The returned file descriptor is moved to [`MIN_INTERNAL_FD`] or above so
+/// that it does not interfere with the user's redirections.
+///
+/// [`MIN_INTERNAL_FD`]: env::io::MIN_INTERNAL_FD
+fn open_script_file(
+    env: &mut yash_env::Env,
+    path: &str,
+) -> Result<env::io::Fd, env::system::Errno> {
+    use env::system::{Errno, Mode, OFlag, SystemEx};
+    use env::System;
+    use std::ffi::CString;
+
+    let c_path = CString::new(path).map_err(|_| Errno::EILSEQ)?;
+    let fd = env
+        .system
+        .open(&c_path, OFlag::O_RDONLY | OFlag::O_CLOEXEC, Mode::empty())?;
+    env.system.move_fd_internal(fd)
+}
+
+async fn parse_and_run(mut env: yash_env::Env) -> i32 {
     use env::option::Option::{Interactive, Monitor};
     use env::option::State::{Off, On};
+    use env::semantics::ExitStatus;
+    use env::system::Errno;
     use std::cell::Cell;
     use std::num::NonZeroU64;
     use std::ops::ControlFlow::{Break, Continue};
     use std::rc::Rc;
-    use yash_env::input::Stdin;
+    use yash_env::input::FdReader;
+    use yash_env::io::Fd;
     use yash_env::variable::Scope;
+    use yash_env::variable::Value::Array;
     use yash_env::variable::Variable;
     use yash_semantics::trap::run_exit_trap;
     use yash_semantics::Divert;
 
-    let mut args = std::env::args();
-    if let Some(arg0) = args.next() {
-        env.arg0 = arg0;
-
-        for arg in args {
-            match arg.as_str() {
-                "-i" => {
-                    env.options.set(Interactive, On);
-                    _ = env.traps.enable_terminator_handlers(&mut env.system);
-                }
-                "-m" => {
-                    env.options.set(Monitor, On);
-                    _ = env.traps.enable_stopper_handlers(&mut env.system);
-                }
-                _ => todo!("sorry, this argument is not yet supported: {arg:?}"),
-            }
+    // TODO std::env::args() would panic on broken UTF-8
+    let args: Vec<String> = std::env::args().collect();
+    let shell_name = args.first().map_or("yash", String::as_str).to_owned();
+    let run = match startup::args::parse(args) {
+        Ok(run) => run,
+        Err(error) => {
+            env.print_error(&format!("{shell_name}: {error}\n")).await;
+            return ExitStatus::ERROR.0;
         }
+    };
+
+    env.arg0 = run.arg0;
+    for (option, state) in run.options {
+        env.options.set(option, state);
+    }
+    if env.options.get(Interactive) == On {
+        _ = env.traps.enable_terminator_handlers(&mut env.system);
+    }
+    if env.options.get(Monitor) == On {
+        _ = env.traps.enable_stopper_handlers(&mut env.system);
     }
 
     env.builtins.extend(builtin::BUILTINS.iter().cloned());
@@ -68,12 +97,45 @@ async fn parse_and_print(mut env: yash_env::Env) -> i32 {
     }
     env.init_variables();
 
-    // Run the read-eval loop
-    let mut input = Box::new(Stdin::new(env.system.clone()));
+    env.variables.positional_params_mut().value = Some(Array(run.positional_params));
+
+    // Prepare the input
+    let code;
     let echo = Rc::new(Cell::new(Off));
-    input.set_echo(Some(Rc::clone(&echo)));
-    let line = NonZeroU64::new(1).unwrap();
-    let mut lexer = parser::lex::Lexer::new(input, line, source::Source::Stdin);
+    let mut lexer = match run.source {
+        startup::args::Source::Stdin => {
+            let mut input = Box::new(FdReader::new(Fd::STDIN, env.system.clone()));
+            input.set_echo(Some(Rc::clone(&echo)));
+            let line = NonZeroU64::new(1).unwrap();
+            parser::lex::Lexer::new(input, line, source::Source::Stdin)
+        }
+        startup::args::Source::String(command) => {
+            code = command;
+            parser::lex::Lexer::from_memory(&code, source::Source::CommandString)
+        }
+        startup::args::Source::File { path } => match open_script_file(&mut env, &path) {
+            Ok(fd) => {
+                let mut input = Box::new(FdReader::new(fd, env.system.clone()));
+                input.set_echo(Some(Rc::clone(&echo)));
+                let line = NonZeroU64::new(1).unwrap();
+                let source = source::Source::CommandFile { path };
+                parser::lex::Lexer::new(input, line, source)
+            }
+            Err(errno) => {
+                let message = format!(
+                    "{shell_name}: cannot open file {path:?}: {}\n",
+                    errno.desc()
+                );
+                env.print_error(&message).await;
+                return match errno {
+                    Errno::ENOENT | Errno::ENOTDIR => ExitStatus::NOT_FOUND.0,
+                    _ => ExitStatus::NOEXEC.0,
+                };
+            }
+        },
+    };
+
+    // Run the read-eval loop
     let mut rel = semantics::ReadEvalLoop::new(&mut env, &mut lexer);
     rel.set_verbose(Some(echo));
     let result = rel.run().await;
@@ -115,7 +177,7 @@ pub fn bin_main() -> i32 {
 
     let system = env.system.clone();
     let mut pool = futures_executor::LocalPool::new();
-    let task = parse_and_print(env);
+    let task = parse_and_run(env);
     let result = Rc::new(Cell::new(Poll::Pending));
     let result_2 = Rc::clone(&result);
     pool.spawner()
@@ -0,0 +1,486 @@
+// This file is part of yash, an extended POSIX shell.
+// Copyright (C) 2023 WATANABE Yuki
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Command line argument parser for the shell
+//!
+//! The [`parse`] function parses the command line arguments passed to the
+//! shell on its startup. The syntax is the one defined in POSIX for the `sh`
+//! utility, extended with long options:
+//!
+//! ```sh
+//! yash [-abCefhimnuvx] [-o option]... [+abCefhimnuvx] [+o option]... [script_file [argument...]]
+//! yash -c [-abCefhimnuvx] [-o option]... [+abCefhimnuvx] [+o option]... command_string [command_name [argument...]]
+//! yash -s [-abCefhimnuvx] [-o option]... [+abCefhimnuvx] [+o option]... [argument...]
+//! ```
+//!
+//! Long options can be specified as `--option` or `++option` as well as with
+//! `-o option` or `+o option`.
+
+use std::iter::Peekable;
+use thiserror::Error;
+use yash_env::option::canonicalize;
+use yash_env::option::parse_long;
+use yash_env::option::parse_short;
+use yash_env::option::FromStrError::*;
+use yash_env::option::Option::{CmdLine, Stdin};
+use yash_env::option::State::{self, On};
+
+/// Source of the commands the shell executes
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Source {
+    /// Read the standard input
+    #[default]
+    Stdin,
+
+    /// Read a script file
+    File {
+        /// Path to the script file
+        path: String,
+    },
+
+    /// Execute the command string given by the `-c` option
+    String(String),
+}
+
+/// Shell invocation configuration resulting from command line parsing
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Run {
+    /// Source of the commands to execute
+    pub source: Source,
+
+    /// Options to be set on startup, in the order of appearance
+    pub options: Vec<(yash_env::option::Option, State)>,
+
+    /// Value of the special parameter `0`
+    pub arg0: String,
+
+    /// Initial positional parameters
+    pub positional_params: Vec<String>,
+}
+
+/// Error in command line parsing
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum Error {
+    /// Short option that is not a valid shell option
+    #[error("unknown option {0:?}")]
+    UnknownShortOption(char, String),
+
+    /// Long option that is not a valid shell option
+    #[error("unknown option {0:?}")]
+    UnknownLongOption(String),
+
+    /// Long option that matches the prefix of more than one option name
+    #[error("ambiguous option name {0:?}")]
+    AmbiguousLongOption(String),
+
+    /// `-o` or `+o` used without an option name
+    #[error("option {0:?} missing an argument")]
+    MissingOptionArgument(String),
+
+    /// `-c` option used without a command string operand
+    #[error("the -c option is specified but no command is given")]
+    MissingCommandString,
+}
+
+/// Converts the result of [`parse_long`] into an option occurrence.
+fn long_option(
+    name: &str,
+    negate: bool,
+    arg: &str,
+) -> Result<(yash_env::option::Option, State), Error> {
+    match parse_long(&canonicalize(name)) {
+        Ok((option, state)) => Ok((option, if negate { !state } else { state })),
+        Err(NoSuchOption) => Err(Error::UnknownLongOption(arg.to_owned())),
+        Err(Ambiguous) => Err(Error::AmbiguousLongOption(arg.to_owned())),
+    }
+}
+
+/// Tries to parse the next argument as short options.
+///
+/// Returns `Ok(true)` if the next argument contained short options, in which
+/// case the parsed argument is consumed from the iterator.
+fn try_parse_short<I: Iterator<Item = String>>(
+    args: &mut Peekable<I>,
+    option_occurrences: &mut Vec<(yash_env::option::Option, State)>,
+) -> Result<bool, Error> {
+    let arg = match args.peek() {
+        Some(arg) => arg,
+        None => return Ok(false),
+    };
+
+    let mut chars = arg.chars();
+    let negate = match chars.next() {
+        Some('-') => false,
+        Some('+') => true,
+        _ => return Ok(false),
+    };
+    match chars.next() {
+        Some('-') if !negate => return Ok(false),
+        Some('+') if negate => return Ok(false),
+        None => return Ok(false),
+        _ => (),
+    }
+
+    let arg = args.next().unwrap();
+    let mut chars = arg.chars();
+    chars.next().unwrap();
+    while let Some(c) = chars.next() {
+        if c == 'o' {
+            let name = chars.as_str();
+            let occurrence = if !name.is_empty() {
+                long_option(name, negate, &arg)?
+            } else {
+                let name = args
+                    .next()
+                    .ok_or_else(|| Error::MissingOptionArgument(arg.clone()))?;
+                long_option(&name, negate, &name)?
+            };
+            option_occurrences.push(occurrence);
+            break;
+        }
+
+        match parse_short(c) {
+            Some((option, state)) => {
+                option_occurrences.push((option, if negate { !state } else { state }))
+            }
+            None => return Err(Error::UnknownShortOption(c, arg)),
+        }
+    }
+    Ok(true)
+}
+
+/// Tries to parse and consume the next argument as a long option.
+fn try_parse_long<I: Iterator<Item = String>>(
+    args: &mut Peekable<I>,
+) -> Result<Option<(yash_env::option::Option, State)>, Error> {
+    let arg = match args.peek() {
+        Some(arg) => arg,
+        None => return Ok(None),
+    };
+
+    let (name, negate) = if let Some(name) = arg.strip_prefix("--") {
+        if name.is_empty() {
+            return Ok(None);
+        }
+        (name, false)
+    } else if let Some(name) = arg.strip_prefix("++") {
+        (name, true)
+    } else {
+        return Ok(None);
+    };
+
+    let result = long_option(name, negate, arg);
+    args.next();
+    result.map(Some)
+}
+
+/// Parses command line arguments.
+///
+/// The first item of `args` must be the name of the shell executable, which
+/// is used as the default value of [`Run::arg0`].
+///
+/// If the `-c` option is specified, the first operand is the command string,
+/// the second (if any) is the value of `$0`, and the rest are the positional
+/// parameters. Otherwise, if the `-s` option is specified or there are no
+/// operands, the commands are read from the standard input and all the
+/// operands are the positional parameters. In this case, the `-s` option is
+/// included in the result even if it was not specified. Otherwise, the first
+/// operand names the script file to run, which is also used as `$0`.
+pub fn parse<I, S>(args: I) -> Result<Run, Error>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut args = args.into_iter().map(Into::into);
+    let arg0 = args.next().unwrap_or_default();
+    let mut args = args.peekable();
+
+    let mut options = Vec::new();
+    loop {
+        if try_parse_short(&mut args, &mut options)? {
+            continue;
+        }
+        if let Some(result) = try_parse_long(&mut args)? {
+            options.push(result);
+        } else {
+            break;
+        }
+    }
+
+    if let Some("--" | "-") = args.peek().map(String::as_str) {
+        args.next();
+    }
+
+    let last_state = |option| {
+        options
+            .iter()
+            .rev()
+            .find(|&&(o, _)| o == option)
+            .map(|&(_, state)| state)
+    };
+
+    if last_state(CmdLine) == Some(On) {
+        let command = args.next().ok_or(Error::MissingCommandString)?;
+        let arg0 = args.next().unwrap_or(arg0);
+        return Ok(Run {
+            source: Source::String(command),
+            options,
+            arg0,
+            positional_params: args.collect(),
+        });
+    }
+
+    if last_state(Stdin) == Some(On) || args.peek().is_none() {
+        if last_state(Stdin).is_none() {
+            options.push((Stdin, On));
+        }
+        return Ok(Run {
+            source: Source::Stdin,
+            options,
+            arg0,
+            positional_params: args.collect(),
+        });
+    }
+
+    let path = args.next().unwrap();
+    Ok(Run {
+        source: Source::File { path: path.clone() },
+        options,
+        arg0: path,
+        positional_params: args.collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yash_env::option::Option::*;
+    use yash_env::option::State::*;
+
+    #[test]
+    fn no_arguments() {
+        assert_eq!(
+            parse(["yash"]),
+            Ok(Run {
+                source: Source::Stdin,
+                options: vec![(Stdin, On)],
+                arg0: "yash".to_string(),
+                positional_params: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn script_file() {
+        assert_eq!(
+            parse(["yash", "my-script", "foo", "-x"]),
+            Ok(Run {
+                source: Source::File {
+                    path: "my-script".to_string()
+                },
+                options: vec![],
+                arg0: "my-script".to_string(),
+                positional_params: vec!["foo".to_string(), "-x".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn command_string() {
+        assert_eq!(
+            parse(["yash", "-c", "echo"]),
+            Ok(Run {
+                source: Source::String("echo".to_string()),
+                options: vec![(CmdLine, On)],
+                arg0: "yash".to_string(),
+                positional_params: vec![],
+            })
+        );
+
+        assert_eq!(
+            parse(["sh", "-c", "echo", "name", "1", "2"]),
+            Ok(Run {
+                source: Source::String("echo".to_string()),
+                options: vec![(CmdLine, On)],
+                arg0: "name".to_string(),
+                positional_params: vec!["1".to_string(), "2".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn missing_command_string() {
+        assert_eq!(parse(["yash", "-c"]), Err(Error::MissingCommandString));
+        assert_eq!(
+            parse(["yash", "-c", "--"]),
+            Err(Error::MissingCommandString)
+        );
+    }
+
+    #[test]
+    fn command_string_option_cancelled() {
+        assert_eq!(
+            parse(["yash", "-c", "+c", "file"]),
+            Ok(Run {
+                source: Source::File {
+                    path: "file".to_string()
+                },
+                options: vec![(CmdLine, On), (CmdLine, Off)],
+                arg0: "file".to_string(),
+                positional_params: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn stdin_with_operands() {
+        assert_eq!(
+            parse(["yash", "-s", "a", "b"]),
+            Ok(Run {
+                source: Source::Stdin,
+                options: vec![(Stdin, On)],
+                arg0: "yash".to_string(),
+                positional_params: vec!["a".to_string(), "b".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn grouped_short_options() {
+        assert_eq!(
+            parse(["yash", "-ex", "+fu", "-s"]),
+            Ok(Run {
+                source: Source::Stdin,
+                options: vec![
+                    (ErrExit, On),
+                    (XTrace, On),
+                    (Glob, On),
+                    (Unset, On),
+                    (Stdin, On)
+                ],
+                arg0: "yash".to_string(),
+                positional_params: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn o_option_with_separate_argument() {
+        assert_eq!(
+            parse(["yash", "-o", "errexit", "+o", "allexport"]),
+            Ok(Run {
+                source: Source::Stdin,
+                options: vec![(ErrExit, On), (AllExport, Off), (Stdin, On)],
+                arg0: "yash".to_string(),
+                positional_params: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn o_option_with_adjoined_argument() {
+        assert_eq!(
+            parse(["yash", "-vonoclobber", "+oPosixly-Correct"]),
+            Ok(Run {
+                source: Source::Stdin,
+                options: vec![
+                    (Verbose, On),
+                    (Clobber, Off),
+                    (PosixlyCorrect, Off),
+                    (Stdin, On)
+                ],
+                arg0: "yash".to_string(),
+                positional_params: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn long_options() {
+        assert_eq!(
+            parse(["yash", "--interactive", "++monitor", "--no-exec"]),
+            Ok(Run {
+                source: Source::Stdin,
+                options: vec![(Interactive, On), (Monitor, Off), (Exec, Off), (Stdin, On)],
+                arg0: "yash".to_string(),
+                positional_params: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn separators() {
+        assert_eq!(
+            parse(["yash", "-e", "--", "-x"]),
+            Ok(Run {
+                source: Source::File {
+                    path: "-x".to_string()
+                },
+                options: vec![(ErrExit, On)],
+                arg0: "-x".to_string(),
+                positional_params: vec![],
+            })
+        );
+
+        assert_eq!(
+            parse(["yash", "-", "+x"]),
+            Ok(Run {
+                source: Source::File {
+                    path: "+x".to_string()
+                },
+                options: vec![],
+                arg0: "+x".to_string(),
+                positional_params: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_short_option() {
+        assert_eq!(
+            parse(["yash", "-eX"]),
+            Err(Error::UnknownShortOption('X', "-eX".to_string()))
+        );
+    }
+
+    #[test]
+    fn unknown_long_option() {
+        assert_eq!(
+            parse(["yash", "--foo"]),
+            Err(Error::UnknownLongOption("--foo".to_string()))
+        );
+        assert_eq!(
+            parse(["yash", "-o", "bar"]),
+            Err(Error::UnknownLongOption("bar".to_string()))
+        );
+    }
+
+    #[test]
+    fn ambiguous_long_option() {
+        assert_eq!(
+            parse(["yash", "++no"]),
+            Err(Error::AmbiguousLongOption("++no".to_string()))
+        );
+    }
+
+    #[test]
+    fn missing_option_argument() {
+        assert_eq!(
+            parse(["yash", "-eo"]),
+            Err(Error::MissingOptionArgument("-eo".to_string()))
+        );
+    }
+}